@@ -0,0 +1,11 @@
+// Слой освещения: цвет карты освещения умножается на цвет мира (смешивание задаётся в LightMaterial)
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var lightmap: texture_2d<f32>;
+@group(2) @binding(1) var lightmap_sampler: sampler;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let light = textureSample(lightmap, lightmap_sampler, mesh.uv);
+    return vec4<f32>(light.rgb, 1.0);
+}
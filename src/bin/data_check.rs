@@ -0,0 +1,111 @@
+//! Проверка модуля без запуска игры: `cargo run --bin data_check --no-default-features -- Data/Core`
+//!
+//! Определения разбираются тем же кодом, что и при загрузке игры, без фичи `client` окно и звук не собираются
+
+use std::{
+    collections::HashSet,
+    env,
+    path::{
+        Path,
+        PathBuf
+    },
+    process::ExitCode
+};
+
+use sint_et::core::resource::{
+    Diagnostic::{
+        Diagnostic,
+        Severity
+    },
+    Module::{
+        self,
+        ModuleEntry
+    },
+    Registry::DefKind,
+    ResourcePlugin
+};
+
+fn main() -> ExitCode {
+    let target = PathBuf::from(env::args().nth(1).unwrap_or_else(|| format!("{}/Core", Module::DATA_DIR)));
+    let data = target.parent().map_or(PathBuf::from(Module::DATA_DIR), Path::to_path_buf);
+
+    let (modules, mut diagnostics) = Module::discover_modules(&data);
+    let (modules, order_errors) = Module::resolve_load_order(modules);
+    diagnostics.extend(order_errors);
+
+    let Some(module) = find_module(&modules, &target) else {
+        print_diagnostics(&diagnostics);
+        eprintln!("{} is not a loadable module", target.display());
+        return ExitCode::FAILURE;
+    };
+    let module = module.clone();
+
+    // Зависимости загружаются вместе с модулем, но отчёт только по его файлам
+    let required = dependencies(&modules, &module.info.id);
+    let modules: Vec<ModuleEntry> = modules.into_iter().filter(|entry| required.contains(&entry.info.id)).collect();
+
+    println!("Checking {} ...", module.path.display());
+
+    let (registry, load_errors) = ResourcePlugin::check_modules(modules);
+    diagnostics.extend(load_errors);
+    let diagnostics: Vec<Diagnostic> = diagnostics.into_iter()
+        .filter(|diagnostic| diagnostic.file.as_ref().map_or(true, |file| file.starts_with(&module.path)))
+        .collect();
+
+    println!();
+    print_diagnostics(&diagnostics);
+
+    println!();
+    println!("Module {} {} ({})", module.info.id, module.info.version, module.path.display());
+    for kind in DefKind::ALL {
+        let count = registry.def_ids(kind).iter()
+            .filter(|id_name| registry.def_fields(kind, id_name).iter()
+                .any(|(field, value, _)| field == "id_source" && value.as_str() == Some(module.info.id.as_str())))
+            .count();
+        if count > 0 {
+            println!("  {:<12} {}", kind.label(), count);
+        }
+    }
+
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.severity == Severity::Error).count();
+    let warnings = diagnostics.len() - errors;
+    println!("Errors: {}  Warnings: {}", errors, warnings);
+
+    if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Модуль по его папке
+fn find_module<'a>(modules: &'a [ModuleEntry], target: &Path) -> Option<&'a ModuleEntry> {
+    let target = target.canonicalize().ok()?;
+    modules.iter().find(|module| module.path.canonicalize().is_ok_and(|path| path == target))
+}
+
+/// Модуль и все модули, от которых он зависит
+///
+/// Ядро нужно всегда, как и в `resolve_load_order`, где оно загружается раньше остальных модулей
+fn dependencies(modules: &[ModuleEntry], id: &str) -> HashSet<String> {
+    let mut required = HashSet::from([id.to_string(), Module::CORE_MODULE.to_string()]);
+    let mut stack = vec![id.to_string(), Module::CORE_MODULE.to_string()];
+
+    while let Some(id) = stack.pop() {
+        let Some(module) = modules.iter().find(|module| module.info.id == id) else {
+            continue;
+        };
+        for dependency in &module.info.dependencies {
+            if required.insert(dependency.id.clone()) {
+                stack.push(dependency.id.clone());
+            }
+        }
+    }
+    required
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        println!("{}", diagnostic);
+    }
+}
@@ -3,8 +3,25 @@ use bevy::prelude::*;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct CraftResult {
     pub output: String,
     pub count:  usize,
-}
\ No newline at end of file
+}
+
+/// Ингредиент рецепта
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CraftIngredient {
+    pub item:   String,
+    pub count:  usize,
+}
+
+/// Инструмент, необходимый для рецепта
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CraftTool {
+    pub item:               String,
+    #[serde(default)]
+    pub consumed:           bool,   // Инструмент расходуется при крафте
+    #[serde(default)]
+    pub durability_cost:    usize,  // Износ инструмента за один крафт
+}
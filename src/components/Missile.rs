@@ -3,34 +3,42 @@ use bevy::prelude::*;
 use crate::core::{
     //player::PlayerEntity::User, 
     Entity::EntityBase,
-    UserSystem::{
-        User,
-        UserControl
-    }
+    Attributes::{
+        InvincibilityCooldown,
+        Defence
+    },
+    interact::Damage::mitigate,
+    skills::is_opponent,
+    EntityType::EntityNeutrality,
+    UserSystem::User,
+    world::World::WorldInfo
 };
 
 pub const BULLET_LIFETIME: f32 = 10.0;
 pub const BULLET_SPEED: f32 = 3000.;
+pub const BULLET_DAMAGE: f32 = 25.;
 
 #[derive(Component)]
 pub struct Bullet {
     pub lifetime: f32,
     pub speed: f32,
     pub direction: Vec2,
+    pub damage: f32,
+    pub owner: Option<Entity>,
 }
 
 pub fn update_bullets(
     mut commands: Commands,
     mut bullet_query: Query<(&mut Bullet, &mut Transform, Entity)>,
-    time: Res<Time>,
+    clock: Res<WorldInfo>,
 ) {
     if bullet_query.is_empty() {
         return;
     }
 
     for (mut bullet, mut transform, entity) in bullet_query.iter_mut() {
-        bullet.lifetime -= time.delta_seconds();
-        let moving = bullet.speed * bullet.direction * time.delta_seconds();
+        bullet.lifetime -= clock.delta();
+        let moving = bullet.speed * bullet.direction * clock.delta();
         transform.translation += Vec3::new(moving.x, moving.y, 0.);
 
         if bullet.lifetime <= 0. {
@@ -42,37 +50,50 @@ pub fn update_bullets(
 pub struct BulletInfo {
     pub translation: Vec3,
     pub entity: Entity,
+    pub damage: f32,
+    pub owner: Option<Entity>,
+    pub faction: Option<EntityNeutrality>,
 }
 
+/// Попадания снарядов: снаряд задевает только противников своего владельца (как и урон по области)
 pub fn update_bullet_hits(
     mut commands: Commands,
-    bullet_query: Query<(&Transform, Entity), (With<Bullet>, Without<EntityBase>)>,
+    bullet_query: Query<(&Transform, &Bullet, Entity), Without<EntityBase>>,
     mut enemy_query: Query<
-        (&mut EntityBase, &mut Transform),
-        (Without<Bullet>, Without<UserControl>),
+        (Entity, &mut EntityBase, &mut Transform, Option<&Defence>, Option<&EntityNeutrality>),
+        (Without<Bullet>, Without<InvincibilityCooldown>),
     >,
+    factions: Query<Option<&EntityNeutrality>>,
 ) {
     if bullet_query.is_empty() {
         return;
     }
 
     let mut bullet_list = Vec::new();
-    for (transform, entity) in bullet_query.iter() {
+    for (transform, bullet, entity) in bullet_query.iter() {
         bullet_list.push(BulletInfo {
             translation: Vec3::new(transform.translation.x, transform.translation.y, 0.),
             entity,
+            damage: bullet.damage,
+            owner: bullet.owner,
+            faction: bullet.owner.and_then(|owner| factions.get(owner).ok().flatten().copied()),
         });
     }
     
     let mut bullet_len = bullet_list.len();
-    for (mut enemy, transform) in enemy_query.iter_mut() {
+    for (enemy_entity, mut enemy, transform, defence, faction) in enemy_query.iter_mut() {
         let mut i: i32 = 0;
         while i < bullet_len as i32 {
+            if bullet_list[i as usize].owner == Some(enemy_entity) || !is_opponent(bullet_list[i as usize].faction, faction.copied()) {
+                i += 1;
+                continue;
+            }
+
             if Vec3::distance(
                 bullet_list[i as usize].translation,
                 Vec3::new(transform.translation.x, transform.translation.y, 0.),
             ) <= 36. {
-                enemy.health.0 -= 25.;
+                enemy.health.0 -= mitigate(bullet_list[i as usize].damage, defence);
 
                 commands.entity(bullet_list[i as usize].entity).despawn();
 
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use bevy_egui::EguiContexts;
+
+use bevy_rapier2d::prelude::*;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    world::World::WorldInfo,
+    AppState
+};
+
+/// Время года, влияет на скорость роста растений
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum Season {
+    #[default]
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            Season::Spring  => Season::Summer,
+            Season::Summer  => Season::Autumn,
+            Season::Autumn  => Season::Winter,
+            Season::Winter  => Season::Spring,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Season::Spring  => Season::Winter,
+            Season::Summer  => Season::Spring,
+            Season::Autumn  => Season::Summer,
+            Season::Winter  => Season::Autumn,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Season::Spring  => "Spring",
+            Season::Summer  => "Summer",
+            Season::Autumn  => "Autumn",
+            Season::Winter  => "Winter",
+        }
+    }
+}
+
+//
+//
+//
+
+/// Доступные скорости игрового времени
+pub const GAME_SPEEDS: [f32; 4] = [1., 2., 4., 8.];
+/// Рассвет, доля суток (06:00)
+pub const DAWN: f32 = 6. / 24.;
+/// Закат, доля суток (20:00)
+pub const DUSK: f32 = 20. / 24.;
+/// Длительность сумерек, доля суток
+pub const TWILIGHT: f32 = 1. / 24.;
+/// Освещённость ночью
+pub const NIGHT_LIGHT: f32 = 0.1;
+
+pub fn time_cycle_plugin(app: &mut App) {
+    app
+        // Init Events
+        .add_event::<Dawn>()
+        .add_event::<Dusk>()
+        .add_event::<NewDay>()
+        // Init Systems
+        .add_systems(OnExit(AppState::Game), reset_clock)
+        // Часы идут до всех игровых систем, чтобы кадр видел одно игровое время
+        .add_systems(PreUpdate, advance_clock.run_if(in_state(AppState::Game)))
+        .add_systems(Update,
+            (
+                clock_input,
+                sync_physics_time
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+/// Смена части суток, возвращаемая часами мира
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockEvent {
+    Dawn,
+    Dusk,
+    NewDay,
+}
+
+/// Ивент рассвета (0 - День)
+#[derive(Event)]
+pub struct Dawn(pub u32);
+
+/// Ивент заката (0 - День)
+#[derive(Event)]
+pub struct Dusk(pub u32);
+
+/// Ивент наступления новых суток (0 - День)
+#[derive(Event)]
+pub struct NewDay(pub u32);
+
+/// Новый мир начинается с утра первого дня
+fn reset_clock(
+    mut clock:  ResMut<WorldInfo>,
+) {
+    *clock = WorldInfo::default();
+}
+
+fn advance_clock(
+    mut clock:          ResMut<WorldInfo>,
+    mut dawn_event:     EventWriter<Dawn>,
+    mut dusk_event:     EventWriter<Dusk>,
+    mut day_event:      EventWriter<NewDay>,
+        time:           Res<Time>,
+) {
+    let season = clock.season();
+
+    for event in clock.advance(time.delta_seconds()) {
+        match event {
+            ClockEvent::Dawn    => { dawn_event.send(Dawn(clock.day)); },
+            ClockEvent::Dusk    => { dusk_event.send(Dusk(clock.day)); },
+            ClockEvent::NewDay  => { day_event.send(NewDay(clock.day)); },
+        }
+    }
+
+    if clock.season() != season {
+        info!("Clock - наступило время года {}", clock.season().label());
+    }
+}
+
+/// Пауза (Space) и скорость игрового времени (+/-)
+fn clock_input(
+    mut clock:          ResMut<WorldInfo>,
+    mut contexts:       EguiContexts,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    // Пробел и +/- в полях ввода интерфейса не управляют часами
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        clock.is_paused = !clock.is_paused;
+    }
+
+    let index = GAME_SPEEDS.iter().position(|speed| *speed >= clock.speed).unwrap_or(0);
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        clock.speed = GAME_SPEEDS[(index + 1).min(GAME_SPEEDS.len() - 1)];
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        clock.speed = GAME_SPEEDS[index.saturating_sub(1)];
+    }
+}
+
+/// Физика идёт с той же скоростью, что и часы мира, и останавливается на паузе
+fn sync_physics_time(
+    mut physics:    ResMut<RapierConfiguration>,
+        clock:      Res<WorldInfo>,
+) {
+    physics.physics_pipeline_active = !clock.is_paused;
+    physics.timestep_mode = TimestepMode::Variable {
+        max_dt:     clock.speed / 60.,
+        time_scale: clock.speed,
+        substeps:   clock.speed.ceil() as usize,
+    };
+}
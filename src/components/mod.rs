@@ -4,4 +4,6 @@ use bevy::prelude::*;
 
 pub mod Craft;
 pub mod Missile;
-pub mod Movement;
\ No newline at end of file
+pub mod Movement;
+pub mod TimeCycle;
+
@@ -19,10 +19,31 @@ use crate::core::{
     EntityAnimation::EntityDirectionState,
     EntityType::*,
     entities::ai::{
+        Brain,
         LastDirection,
+        Pawn,
+        Job::{
+            WorkPriorities,
+            PAWN_INTERACTION_RADIUS
+        },
         Path::AiPath,
     },
-    stats::Stats,
+    ContainerSystem::{
+        Inventory,
+        CarryCapacity
+    },
+    stats::{
+        Stats,
+        Level,
+        LevelCurve,
+        SkillPoints,
+        LearnedSkills
+    },
+    skills::{
+        Energy,
+        SkillSlots,
+        SkillCooldowns
+    },
 };
 
 /// Компонент отвечающий за [Здоровье]
@@ -103,6 +124,7 @@ pub fn spawn_entity(
     mut registry:   ResMut<Registry>,
     mut event:      EventReader<EntitySpawn>,
         atlas:      Res<AtlasRes>,
+        curve:      Res<LevelCurve>,
 ) {
     if event.is_empty() {
         return;
@@ -128,6 +150,7 @@ pub fn spawn_entity(
                         position:   Position(Vec2::new(64., 64.)),
                         direction:  EntityDirectionState::South,
                         movable:    true,
+                        interaction_radius: if info.pawn { PAWN_INTERACTION_RADIUS } else { EntityBase::default().interaction_radius },
                         ..default()
                     },
                     SpriteSheetBundle {
@@ -149,6 +172,28 @@ pub fn spawn_entity(
                     Name::new(info.id_name.clone()),
                 )).id();
 
+                commands.entity(entity).insert((
+                    Level::with_curve(1, &curve),
+                    SkillPoints::default(),
+                    LearnedSkills::default(),
+                    Energy::default(),
+                    SkillSlots::from_list(info.skills.as_deref().unwrap_or_default()),
+                    SkillCooldowns::default(),
+                    Brain::default(),
+                ));
+
+                // Рабочие колонии носят материалы и берут работы с доски
+                if info.pawn {
+                    let capacity = CarryCapacity::default();
+
+                    commands.entity(entity).insert((
+                        Pawn,
+                        WorkPriorities::default(),
+                        Inventory::with_capacity(capacity.slots).with_limits(capacity.max_mass, capacity.max_volume),
+                        capacity,
+                    ));
+                }
+
                 if !info.id_texture_h.is_none() {
                     if let Some(texture_h) = info.id_texture_h.clone() {
                         if let Some(sprite_h) = registry.get_entity_texture(&texture_h, &atlas) {
@@ -20,7 +20,7 @@ pub enum HumonoidType {
 
 // будет переделываться на систему репутации и хищничества
 /// Поведение в отношении игрока
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EntityNeutrality {
     Hostile,
     Friendly,
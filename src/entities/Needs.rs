@@ -1,6 +1,8 @@
 #![allow(unused)]
 use bevy::prelude::*;
 
+use crate::core::world::World::WorldInfo;
+
 //
 //
 //
@@ -25,14 +27,14 @@ pub struct Recreation {
 }
 
 // Could be generic needs system
-fn apply_hunger(mut hungers: Query<&mut Hunger>, time: Res<Time>) {
+fn apply_hunger(mut hungers: Query<&mut Hunger>, clock: Res<WorldInfo>) {
     for mut hunger in &mut hungers {
-        hunger.value -= time.delta_seconds() * 3.0;
+        hunger.value -= clock.delta() * 3.0;
     }
 }
 
-fn apply_recreation(mut recreations: Query<&mut Recreation>, time: Res<Time>) {
+fn apply_recreation(mut recreations: Query<&mut Recreation>, clock: Res<WorldInfo>) {
     for mut recreations in &mut recreations {
-        recreations.value -= time.delta_seconds() * 10.0;
+        recreations.value -= clock.delta() * 10.0;
     }
 }
\ No newline at end of file
@@ -1,31 +1,360 @@
 #![allow(unused)]
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 use bevy_rapier2d::dynamics::Velocity;
 
+use serde::{
+    Deserialize,
+    Serialize
+};
+
 use super::Entity::EntityBase;
 
+use crate::core::{
+    world::World::WorldInfo,
+    resource::{
+        Registry::Registry,
+        graphic::Atlas::{
+            AtlasRes,
+            AtlasType
+        }
+    },
+    Attributes::{
+        InvincibilityCooldown,
+        MaxHealth
+    },
+    Missile::Bullet,
+    UserSystem::UserControl,
+    stats::max_health,
+    EntityType::EntityNeutrality,
+    interact::Damage::DamageObject,
+    AppState
+};
+
+//
+//
+//
+
+pub fn skills_plugin(app: &mut App) {
+    app
+        // Init Events
+        .add_event::<UseSkill>()
+        .add_event::<DeshEvent>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                regen_energy,
+                tick_cooldowns,
+                tick_invincibility,
+                tick_dashing,
+                use_skill,
+                process_casting,
+                dash_skill
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
 //
 //
 //
 
+/// Кол-во слотов активных навыков у сущности
+pub const SKILL_SLOTS: usize = 4;
+
+/// Эффект активного навыка, определяется в `Defs/skills`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum SkillEffect {
+    /// Рывок по направлению к цели через `Velocity` с неуязвимостью на время `iframes`
+    Dash { impulse: f32, iframes: f32 },
+    /// Урон всем сущностям в радиусе вокруг заклинателя
+    AreaDamage { radius: f32, damage: f32 },
+    /// Восстановление здоровья, не выше максимального из реестра
+    Heal { amount: f32 },
+    /// Снаряд в сторону цели, `texture` - id предмета для текстуры снаряда
+    Projectile { speed: f32, lifetime: f32, damage: f32, texture: String },
+}
+
+/// Компонент отвечающий за ресурс, который тратят навыки
+#[derive(Component, Reflect, Clone, Debug)]
+pub struct Energy {
+    pub value:  f32,
+    pub max:    f32,
+    pub regen:  f32,    // Восстановление в секунду
+}
+
+impl Default for Energy {
+    fn default() -> Self {
+        Self {
+            value:  100.0,
+            max:    100.0,
+            regen:  5.0,
+        }
+    }
+}
+
+/// Навыки, привязанные к слотам (0..4 - клавиши 1..4 у игрока)
+#[derive(Component, Clone, Debug, Default)]
+pub struct SkillSlots {
+    pub slots: [Option<String>; SKILL_SLOTS],
+}
+
+impl SkillSlots {
+    pub fn from_list(skills: &[String]) -> Self {
+        let mut slots = SkillSlots::default();
+        for (slot, skill) in slots.slots.iter_mut().zip(skills.iter()) {
+            *slot = Some(skill.clone());
+        }
+        slots
+    }
+}
+
+/// Оставшееся время перезарядки навыков
+#[derive(Component, Clone, Debug, Default)]
+pub struct SkillCooldowns {
+    pub timers: HashMap<String, f32>,
+}
+
+impl SkillCooldowns {
+    pub fn is_ready(&self, skill: &str) -> bool {
+        self.timers.get(skill).map_or(true, |timer| *timer <= 0.0)
+    }
+}
+
+/// Навык, который сущность применяет в данный момент
+#[derive(Component, Clone, Debug)]
+pub struct CastingSkill {
+    pub skill:  String,
+    pub timer:  f32,
+    pub target: Vec2,
+}
+
+/// Сущность находится в рывке, ввод движения игнорируется (0 - Оставшееся время)
+#[derive(Component, Clone, Debug)]
+pub struct Dashing(pub f32);
+
+/// Минимальная длительность рывка, в течение которой движение не перезаписывает скорость
+const DASH_DURATION: f32 = 0.15;
+
+/// Ивент применения навыка (0 - Заклинатель | 1 - Индекс слота | 2 - Точка цели)
+#[derive(Event)]
+pub struct UseSkill(pub Entity, pub usize, pub Vec2);
+
+/// Ивент рывка (0 - Сущность | 1 - Направление | 2 - Импульс | 3 - Время неуязвимости)
 #[derive(Event)]
-pub struct DeshEvent(pub Entity);
+pub struct DeshEvent(pub Entity, pub Vec2, pub f32, pub f32);
+
+/// Проверка возможности применения навыка из слота
+pub fn skill_ready(
+    registry:   &Registry,
+    slots:      &SkillSlots,
+    cooldowns:  &SkillCooldowns,
+    energy:     &Energy,
+    slot:       usize,
+) -> bool {
+    if let Some(Some(skill)) = slots.slots.get(slot) {
+        if let Some(info) = registry.get_skill_info(skill) {
+            return cooldowns.is_ready(skill) && energy.value >= info.cost;
+        }
+    }
+    false
+}
+
+fn regen_energy(
+    mut energy: Query<&mut Energy>,
+        clock:  Res<WorldInfo>,
+) {
+    for mut energy in &mut energy {
+        if energy.value < energy.max {
+            energy.value = (energy.value + energy.regen * clock.delta()).min(energy.max);
+        }
+    }
+}
+
+fn tick_cooldowns(
+    mut cooldowns:  Query<&mut SkillCooldowns>,
+        clock:      Res<WorldInfo>,
+) {
+    for mut cooldowns in &mut cooldowns {
+        if cooldowns.timers.is_empty() {
+            continue;
+        }
+
+        for timer in cooldowns.timers.values_mut() {
+            *timer -= clock.delta();
+        }
+        cooldowns.timers.retain(|_, timer| *timer > 0.0);
+    }
+}
+
+fn tick_invincibility(
+    mut commands:   Commands,
+    mut entities:   Query<(Entity, &mut InvincibilityCooldown)>,
+        clock:      Res<WorldInfo>,
+) {
+    for (entity, mut cooldown) in &mut entities {
+        cooldown.0 -= clock.delta();
+        if cooldown.0 <= 0.0 {
+            commands.entity(entity).remove::<InvincibilityCooldown>();
+        }
+    }
+}
+
+fn tick_dashing(
+    mut commands:   Commands,
+    mut entities:   Query<(Entity, &mut Dashing)>,
+        clock:      Res<WorldInfo>,
+) {
+    for (entity, mut dashing) in &mut entities {
+        dashing.0 -= clock.delta();
+        if dashing.0 <= 0.0 {
+            commands.entity(entity).remove::<Dashing>();
+        }
+    }
+}
+
+/// Проверка условий и начало применения навыка (списание ресурса, перезарядка, время каста)
+fn use_skill(
+    mut commands:   Commands,
+    mut casters:    Query<(&SkillSlots, &mut SkillCooldowns, &mut Energy), Without<CastingSkill>>,
+    mut event:      EventReader<UseSkill>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if let Ok((slots, mut cooldowns, mut energy)) = casters.get_mut(event.0) {
+            if !skill_ready(&registry, slots, &cooldowns, &energy, event.1) {
+                continue;
+            }
+
+            if let Some(Some(skill)) = slots.slots.get(event.1) {
+                if let Some(info) = registry.get_skill_info(skill) {
+                    energy.value -= info.cost;
+                    cooldowns.timers.insert(skill.clone(), info.cooldown);
+
+                    commands.entity(event.0).insert(CastingSkill {
+                        skill:  skill.clone(),
+                        timer:  info.cast_time,
+                        target: event.2,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Отсчёт времени каста и применение эффекта навыка
+fn process_casting(
+    mut commands:   Commands,
+    mut casters:    Query<(Entity, &Transform, &mut CastingSkill, &mut EntityBase, Option<&MaxHealth>, Option<&EntityNeutrality>)>,
+        entities:   Query<(Entity, &Transform, Option<&EntityNeutrality>), Without<CastingSkill>>,
+    mut dash:       EventWriter<DeshEvent>,
+    mut hits:       EventWriter<DamageObject>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        clock:      Res<WorldInfo>,
+) {
+    for (caster, transform, mut casting, mut caster_base, bonus, caster_faction) in &mut casters {
+        casting.timer -= clock.delta();
+        if casting.timer > 0.0 {
+            continue;
+        }
+
+        commands.entity(caster).remove::<CastingSkill>();
+
+        let Some(info) = registry.get_skill_info(&casting.skill) else {
+            continue;
+        };
+
+        let origin = transform.translation.truncate();
+        let direction = (casting.target - origin).normalize_or_zero();
+
+        match &info.effect {
+            SkillEffect::Dash { impulse, iframes } => {
+                dash.send(DeshEvent(caster, direction, *impulse, *iframes));
+            },
+            SkillEffect::AreaDamage { radius, damage } => {
+                // Область задевает только противников заклинателя
+                for (entity, target_transform, target_faction) in &entities {
+                    if !is_opponent(caster_faction.copied(), target_faction.copied())
+                        || origin.distance(target_transform.translation.truncate()) > *radius
+                    {
+                        continue;
+                    }
+
+                    hits.send(DamageObject(target_transform.translation.truncate().as_ivec2(), *damage, Some(caster), Some(entity)));
+                }
+            },
+            SkillEffect::Heal { amount } => {
+                let max = max_health(&registry, &caster_base, bonus);
+                caster_base.health.0 = (caster_base.health.0 + amount).min(max);
+            },
+            SkillEffect::Projectile { speed, lifetime, damage, texture } => {
+                let Some(item) = registry.get_item_info(texture) else {
+                    warn!("Error - Текстура снаряда {} не найдена в реестре предметов.", texture);
+                    continue;
+                };
+
+                if let Some(sprite) = registry.get_item_texture(&item.id_texture, &atlas, AtlasType::Items) {
+                    commands.spawn((
+                        SpriteSheetBundle {
+                            texture: sprite.texture,
+                            atlas: sprite.atlas,
+                            transform: Transform {
+                                translation: origin.extend(0.6),
+                                rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
+                                scale: Vec3::splat(0.5),
+                            },
+                            ..default()
+                        },
+                        Name::new("Projectile"),
+                        Bullet {
+                            lifetime:   *lifetime,
+                            speed:      *speed,
+                            direction,
+                            damage:     *damage,
+                            owner:      Some(caster),
+                        }
+                    ));
+                }
+            },
+        }
+    }
+}
+
+/// Противники - враждебная сущность и не враждебная (сущность без фракции - игрок)
+pub fn is_opponent(caster: Option<EntityNeutrality>, target: Option<EntityNeutrality>) -> bool {
+    let hostile = |faction: Option<EntityNeutrality>| faction == Some(EntityNeutrality::Hostile);
+    hostile(caster) != hostile(target)
+}
 
 fn dash_skill(
-    mut entity: Query<(
-        &EntityBase, 
-        &mut Velocity, 
-        &mut Transform
+    mut commands:   Commands,
+    mut entity:     Query<(
+        &EntityBase,
+        &mut Velocity
     )>,
-    mut event: EventReader<DeshEvent>
+    mut event:      EventReader<DeshEvent>
 ) {
     if event.is_empty() {
         return;
     }
 
     for event in event.read() {
-        if let Ok(entity) = entity.get_mut(event.0) {
+        if let Ok((_, mut velocity)) = entity.get_mut(event.0) {
+            if event.1 == Vec2::ZERO {
+                continue;
+            }
+
+            velocity.linvel = event.1 * event.2;
+            commands.entity(event.0).insert(Dashing(DASH_DURATION.max(event.3)));
 
+            if event.3 > 0.0 {
+                commands.entity(event.0).insert(InvincibilityCooldown(event.3));
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -3,62 +3,361 @@ use bevy::prelude::*;
 
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 
-use crate::core::Attributes::AttributeChangeEvent;
+use crate::core::{
+    Attributes::{
+        AttributeChangeEvent,
+        ItemAttributes,
+        MaxHealth,
+        XpRateBonus
+    },
+    Entity::EntityBase,
+    resource::Registry::Registry,
+    ContainerSystem::EquipmentAttributes,
+    AppState
+};
 
-#[derive(Component, Debug)]
+//
+//
+//
+
+pub fn stats_plugin(app: &mut App) {
+    app
+        // Init Register
+        .register_type::<Level>()
+        .register_type::<LearnedSkills>()
+        // Init Resources
+        .init_resource::<LevelCurve>()
+        // Init Events
+        .add_event::<ExperienceGain>()
+        .add_event::<LevelUpEvent>()
+        .add_event::<SpendSkillPoint>()
+        .add_event::<AttributeChangeEvent>()
+        // Init Systems
+        .add_systems(OnEnter(AppState::Game), load_level_curve)
+        .add_systems(Update,
+            (
+                apply_experience,
+                handle_level_up,
+                spend_skill_point,
+                apply_skill_attributes,
+                apply_attributes,
+                send_attribute_event_on_stats_update
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+// ==============================
+// Level
+// ==============================
+
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
 pub struct Level {
     pub level: u8,
     pub xp: u32,
     pub next_level_xp: u32,
 }
 
+/// Кривая опыта по умолчанию, используется если модуль не определил свою.
+///
+/// Элемент под индексом `n` - кол-во опыта необходимое для перехода с уровня `n` на следующий.
 pub const LEVEL_REQ_XP: [u32; 10] = [60, 100, 150, 200, 350, 625, 920, 1250, 1770, 2500];
 
+/// Ресурс с актуальной кривой опыта, заполняется из реестра при входе в игру
+#[derive(Resource, Clone, Debug)]
+pub struct LevelCurve {
+    pub xp:                     Vec<u32>,
+    pub skill_points_per_level: u8,
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        Self {
+            xp:                     LEVEL_REQ_XP.to_vec(),
+            skill_points_per_level: 1,
+        }
+    }
+}
+
+impl LevelCurve {
+    /// Максимально достижимый уровень
+    pub fn max_level(&self) -> u8 {
+        self.xp.len().min(u8::MAX as usize) as u8
+    }
+
+    /// Кол-во опыта необходимое для перехода с уровня `level` на следующий
+    pub fn required_xp(&self, level: u8) -> u32 {
+        self.xp.get(level as usize)
+            .or(self.xp.last())
+            .copied()
+            .unwrap_or(u32::MAX)
+    }
+}
+
 impl Level {
     pub fn new(level: u8) -> Self {
+        Self::with_curve(level, &LevelCurve::default())
+    }
+
+    pub fn with_curve(level: u8, curve: &LevelCurve) -> Self {
         Level {
             level,
             xp: 0,
-            next_level_xp: LEVEL_REQ_XP[if level >= LEVEL_REQ_XP.len() as u8 {
-                LEVEL_REQ_XP.len() - 1
-            } else {
-                level as usize
-            }],
+            next_level_xp: curve.required_xp(level),
         }
     }
 
-    pub fn add_xp(&mut self, xp: u32) {
-        self.xp += xp;
-        if self.xp >= self.next_level_xp {
+    pub fn is_max(&self, curve: &LevelCurve) -> bool {
+        self.level >= curve.max_level()
+    }
+
+    /// Добавление опыта.
+    ///
+    /// Возвращает кол-во полученных уровней, за раз может быть получено несколько уровней.
+    /// На максимальном уровне опыт копится до порога и дальше не растёт.
+    pub fn add_xp(&mut self, xp: u32, curve: &LevelCurve) -> u8 {
+        let mut gained = 0;
+
+        self.next_level_xp = curve.required_xp(self.level);
+        self.xp = self.xp.saturating_add(xp);
+
+        while !self.is_max(curve) && self.xp >= self.next_level_xp {
+            self.xp -= self.next_level_xp;
             self.level += 1;
-            self.xp = self.xp - self.next_level_xp;
-            self.next_level_xp = LEVEL_REQ_XP[self.level as usize];
+            gained += 1;
+            self.next_level_xp = curve.required_xp(self.level);
         }
-        println!("EXP: {:?} LEVEL: {:?}", self.xp, self.level);
+
+        if self.is_max(curve) {
+            self.xp = self.xp.min(self.next_level_xp);
+        }
+
+        gained
     }
 }
 
-#[derive(Component, Clone, Debug)]
+/// Заполнение кривой опыта из реестра (запись `default` в `Defs/progression`)
+fn load_level_curve(
+    mut curve:      ResMut<LevelCurve>,
+        registry:   Res<Registry>,
+) {
+    if let Some(info) = registry.get_progression_info("default") {
+        if info.xp_curve.is_empty() {
+            warn!("Error - Кривая опыта пуста, используется кривая по умолчанию.");
+            return;
+        }
+
+        curve.xp = info.xp_curve.clone();
+        curve.skill_points_per_level = info.skill_points_per_level;
+    }
+}
+
+// ==============================
+// Experience
+// ==============================
+
+/// Источник получаемого опыта
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XpSource {
+    Kill,
+    Craft,
+    Build,
+    Harvest,
+    Other
+}
+
+/// Ивент получения опыта (0 - Получатель | 1 - Базовое кол-во опыта | 2 - Источник)
+#[derive(Event)]
+pub struct ExperienceGain(pub Entity, pub u32, pub XpSource);
+
+/// Ивент повышения уровня (0 - Сущность | 1 - Новый уровень | 2 - Кол-во полученных уровней)
+#[derive(Event)]
+pub struct LevelUpEvent(pub Entity, pub u8, pub u8);
+
+/// Начисление опыта с учётом бонуса `XpRateBonus` (в процентах)
+fn apply_experience(
+    mut entities:   Query<(&mut Level, Option<&XpRateBonus>)>,
+    mut event:      EventReader<ExperienceGain>,
+    mut level_up:   EventWriter<LevelUpEvent>,
+        curve:      Res<LevelCurve>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if let Ok((mut level, bonus)) = entities.get_mut(event.0) {
+            let rate = 100 + bonus.map_or(0, |bonus| bonus.0);
+            let xp = (event.1 as i64 * rate.max(0) as i64 / 100) as u32;
+
+            let gained = level.add_xp(xp, &curve);
+            if gained > 0 {
+                info!("Level up: {:?} -> {} ({:?})", event.0, level.level, event.2);
+                level_up.send(LevelUpEvent(event.0, level.level, gained));
+            }
+        }
+    }
+}
+
+// ==============================
+// Skill Points
+// ==============================
+
+#[derive(Component, Clone, Debug, Default)]
 pub struct SkillPoints {
     pub count: u8,
 }
 
 pub fn handle_level_up(
-    mut player: Query<(&Level, &mut SkillPoints), Changed<Level>>,
-    mut next_level: Local<u8>,
+    mut entities:   Query<&mut SkillPoints>,
+    mut event:      EventReader<LevelUpEvent>,
+        curve:      Res<LevelCurve>,
 ) {
-    for (player_level, mut sp) in player.iter_mut() {
-        if player_level.level == 1 {
-            *next_level = 2;
-            return;
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if let Ok(mut sp) = entities.get_mut(event.0) {
+            sp.count = sp.count.saturating_add(event.2.saturating_mul(curve.skill_points_per_level));
+        }
+    }
+}
+
+/// Характеристика на которую можно потратить очко навыков
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatType {
+    Str,
+    Dex,
+    End,
+    Int
+}
+
+/// Цель траты очков навыков
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkillPointTarget {
+    Stat(StatType),
+    Skill(String)
+}
+
+/// Ивент траты очков навыков (0 - Сущность | 1 - Цель)
+#[derive(Event)]
+pub struct SpendSkillPoint(pub Entity, pub SkillPointTarget);
+
+/// Изученные узлы дерева навыков
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component)]
+pub struct LearnedSkills {
+    pub skills: Vec<String>,
+}
+
+impl LearnedSkills {
+    pub fn contains(&self, id_name: &str) -> bool {
+        self.skills.iter().any(|skill| skill == id_name)
+    }
+}
+
+/// Суммарные атрибуты, полученные от изученных узлов дерева навыков
+#[derive(Component, Clone, Debug, Default)]
+pub struct SkillAttributes(pub ItemAttributes);
+
+fn spend_skill_point(
+    mut entities:   Query<(&mut SkillPoints, &mut Stats, &mut LearnedSkills)>,
+    mut event:      EventReader<SpendSkillPoint>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if let Ok((mut sp, mut stats, mut learned)) = entities.get_mut(event.0) {
+            match &event.1 {
+                SkillPointTarget::Stat(stat) => {
+                    if sp.count == 0 {
+                        continue;
+                    }
+
+                    sp.count -= 1;
+                    match stat {
+                        StatType::Str => stats.str += 1,
+                        StatType::Dex => stats.dex += 1,
+                        StatType::End => stats.end += 1,
+                        StatType::Int => stats.int += 1,
+                    }
+                },
+                SkillPointTarget::Skill(id_name) => {
+                    let Some(node) = registry.get_skill_node_info(id_name) else {
+                        warn!("Error - Узел дерева навыков {} не найден в реестре.", id_name);
+                        continue;
+                    };
+
+                    if learned.contains(id_name) || sp.count < node.cost {
+                        continue;
+                    }
+
+                    if !node.requires.iter().all(|req| learned.contains(req)) {
+                        info!("Skill {} - не изучены необходимые узлы {:?}", id_name, node.requires);
+                        continue;
+                    }
+
+                    sp.count -= node.cost;
+                    learned.skills.push(id_name.clone());
+                }
+            }
         }
-        if player_level.level == *next_level {
-            sp.count += 1;
-            *next_level += 1;
+    }
+}
+
+/// Пересчёт атрибутов от дерева навыков при изменении списка изученных узлов
+fn apply_skill_attributes(
+    mut commands:   Commands,
+        entities:   Query<(Entity, &LearnedSkills), Changed<LearnedSkills>>,
+        registry:   Res<Registry>,
+) {
+    for (entity, learned) in entities.iter() {
+        let attributes = learned.skills.iter()
+            .filter_map(|skill| registry.get_skill_node_info(skill))
+            .fold(ItemAttributes::default(), |acc, node| acc.combine(&node.attributes));
+
+        commands.entity(entity).insert(SkillAttributes(attributes));
+    }
+}
+
+/// Применение суммарных атрибутов (дерево навыков + экипировка) в виде компонентов
+fn apply_attributes(
+    mut commands:   Commands,
+    mut entities:   Query<
+            (Entity, Option<&SkillAttributes>, Option<&EquipmentAttributes>, Option<&MaxHealth>, Option<&mut EntityBase>),
+            Or<(Changed<SkillAttributes>, Changed<EquipmentAttributes>)>
+        >,
+) {
+    for (entity, skills, equipment, max_health, entity_base) in &mut entities {
+        let attributes = skills.map_or(ItemAttributes::default(), |skills| skills.0.clone())
+            .combine(&equipment.map_or(ItemAttributes::default(), |equipment| equipment.0.clone()));
+
+        // Изменение бонуса `MaxHealth` сразу меняет и текущее здоровье, но не убивает сущность
+        if let Some(mut entity_base) = entity_base {
+            let delta = attributes.health - max_health.map_or(0, |bonus| bonus.0);
+            if delta != 0 {
+                entity_base.health.0 = (entity_base.health.0 + delta as f32).max(1.0);
+            }
         }
+
+        attributes.add_attribute_components(&mut commands.entity(entity));
     }
 }
 
+/// Максимальное здоровье сущности: значение из реестра с учётом бонуса `MaxHealth`
+pub fn max_health(registry: &Registry, entity_base: &EntityBase, bonus: Option<&MaxHealth>) -> f32 {
+    registry.get_entity_info(&entity_base.id_name).map_or(f32::MAX, |info| info.health)
+        + bonus.map_or(0.0, |bonus| bonus.0 as f32)
+}
+
 // Stats
 
 #[derive(Component, InspectorOptions, Clone, Debug, Reflect)]
@@ -85,7 +384,7 @@ pub fn send_attribute_event_on_stats_update(
     mut att_event: EventWriter<AttributeChangeEvent>,
         stats: Query<&Stats, Changed<Stats>>,
 ) {
-    if stats.get_single().is_ok() {
+    if !stats.is_empty() {
         att_event.send(AttributeChangeEvent);
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,23 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
 use crate::core::{
     resource::{
         graphic::Atlas::{
             AtlasType,
             AtlasRes,
         }, 
-        Registry::Registry
+        Registry::{
+            Registry,
+            ItemRegistry
+        }
     }, 
     world::chunk::Chunk::Chunk, 
     Entity::EntityBase, 
@@ -18,6 +28,7 @@ use crate::core::{
     },
     Weapon::Gun,
     ContainerSystem::Inventory,
+    LightSystem::LightSource,
     AppState,
 };
 
@@ -53,13 +64,36 @@ pub struct ItemEntity {
     pub id_name:    String,
     pub id_source:  Option<String>,
     pub item_type:  ItemType,
-    pub durability: Option<usize>,
+    pub instance:   ItemInstance,
     pub stack_size: Option<usize>,
     pub stackable:  Option<ItemStackType>,
+    pub mass:       f32,    // Масса единицы
+    pub volume:     f32,    // Объём единицы
     pub count:      usize
 }
 
 impl ItemEntity {
+    /// Новый предмет из реестра с состоянием по умолчанию
+    pub fn from_info(info: &ItemRegistry, count: usize) -> Self {
+        Self::with_instance(info, count, ItemInstance::new(info))
+    }
+
+    /// Предмет из реестра с заданным состоянием экземпляра
+    pub fn with_instance(info: &ItemRegistry, count: usize, instance: ItemInstance) -> Self {
+        Self {
+            name:       info.id_name.clone(),
+            id_name:    info.id_name.clone(),
+            id_source:  info.id_source.clone(),
+            item_type:  info.item_type.clone(),
+            instance,
+            stack_size: info.stack_size.clone(),
+            stackable:  info.stackable.clone(),
+            mass:       info.get_mass(),
+            volume:     info.get_volume(),
+            count,
+        }
+    }
+
     pub fn check_stackable(&self) -> bool {
         if let Some(stack) = self.stackable {
             return stack.is_stackable()
@@ -68,15 +102,76 @@ impl ItemEntity {
     }
 }
 
+/// Состояние конкретного экземпляра предмета.
+///
+/// Предметы с разным состоянием не объединяются в стаки.
+#[derive(Default, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemInstance {
+    pub durability:     Option<usize>,              // Текущая прочность
+    pub custom_name:    Option<String>,             // Имя, данное игроком
+    pub tags:           HashMap<String, String>,    // Произвольные метаданные
+    pub ammo:           Option<usize>,              // Заряженные боеприпасы
+}
+
+impl ItemInstance {
+    pub fn new(info: &ItemRegistry) -> Self {
+        Self {
+            durability: info.get_base_durability(),
+            ..default()
+        }
+    }
+
+    /// Износ предмета, возвращает `true` если предмет сломался
+    pub fn wear(&mut self, amount: usize) -> bool {
+        if let Some(durability) = &mut self.durability {
+            *durability = durability.saturating_sub(amount);
+            return *durability == 0;
+        }
+        false
+    }
+
+    /// Отображаемое имя предмета
+    pub fn display_name<'a>(&'a self, default: &'a str) -> &'a str {
+        self.custom_name.as_deref().unwrap_or(default)
+    }
+}
+
 //
 //
 //
 
-/// Событие спавна предмета
+/// Событие спавна предмета (0 - id предмета | 1 - Под-тайл | 2 - Кол-во | 3 - Состояние экземпляра, `None` - новый предмет)
 #[derive(Event)]
-pub struct ItemSpawn(pub String, pub IVec2, pub usize);
+pub struct ItemSpawn(pub String, pub IVec2, pub usize, pub Option<ItemInstance>);
+
+/// Наибольшее удаление (в под-тайлах) при поиске свободного места для предмета
+pub const ITEM_DROP_RADIUS: i32 = 64;
+
+/// Под-тайлы на границе квадрата с центром `center` и радиусом `radius`
+fn ring(center: IVec2, radius: i32) -> impl Iterator<Item = IVec2> {
+    (-radius..=radius)
+        .flat_map(move |y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+        .filter(move |offset| offset.x.abs().max(offset.y.abs()) == radius)
+        .map(move |offset| center + offset)
+}
+
+/// Докладывание предметов в стак при совпадении предмета и состояния
+fn merge_into(stack: &mut ItemEntity, item: &mut ItemEntity) {
+    if !stack.check_stackable() || stack.id_name != item.id_name || stack.instance != item.instance {
+        return;
+    }
+    let space = stack.stack_size.map_or(item.count, |stack_size| stack_size.saturating_sub(stack.count));
+    let moved = space.min(item.count);
+    stack.count += moved;
+    item.count -= moved;
+}
 
 /// Функция отвечающая за спавн предмета при вызове события спавна.
+///
+/// Если под-тайл занят, предмет добавляется в лежащий там стак (при совпадении состояния)
+/// либо ложится на ближайший свободный под-тайл, поиск идёт кольцами от под-тайла.
+/// Стаки этого кадра спавнятся после разбора всех событий, поэтому несколько событий в один под-тайл объединяются.
 pub fn spawn_item(
     mut commands:   Commands,
         registry:   Res<Registry>,
@@ -89,102 +184,88 @@ pub fn spawn_item(
         return;
     }
 
+    // Новые стаки кадра по под-тайлам
+    let mut pending: Vec<(IVec2, ItemEntity)> = Vec::new();
+    let mut pending_at: HashMap<IVec2, usize> = HashMap::new();
+
     for event in event.read() {
-        if !chunk_res.objects_ex.contains_key(&event.1) {
-            if let Some(info) = registry.get_item_info(&event.0) {
-                match info.item_type {
-
-                    ItemType::Item(_) | ItemType::None => {
-                        if let Some(sprite) = registry.get_item_texture(&info.id_texture, &atlas, AtlasType::Items) {
-                            let entity = commands
-                                .spawn((
-                                    ItemEntity {
-                                        name:       info.id_name.clone(),
-                                        id_name:    info.id_name.clone(),
-                                        id_source:  info.id_source.clone(),
-                                        item_type:  info.item_type.clone(),
-                                        durability: info.durability.clone(),
-                                        stack_size: info.stack_size.clone(),
-                                        stackable:  info.stackable.clone(),
-                                        count:      event.2,
-                                    },
-                                    SpriteSheetBundle {
-                                        texture: sprite.texture,
-                                        atlas: sprite.atlas,
-                                        transform: Transform {
-                                            translation: Vec3::new(event.1.x as f32 * 8. + 4., event.1.y as f32 * 8. + 4., 0.3),
-                                            scale: Vec3::new(0.5, 0.5, 0.0),
-                                            ..default()
-                                        },
-                                        ..default()
-                                    },
-                                    Name::new(info.id_name.clone())
-                                )).id();
-
-                            chunk_res.objects_ex.insert(event.1, entity);
-                        }
-                    },
+        let Some(info) = registry.get_item_info(&event.0) else {
+            warn!("В регистре не найден предмет - {}", &event.0);
+            continue;
+        };
 
-                    ItemType::Weapon(_) => {
-                        if let Some(sprite) = registry.get_item_texture(&info.id_texture, &atlas, AtlasType::Weapon) {
-                            let entity = commands
-                                .spawn((
-                                    ItemEntity {
-                                        name:       info.id_name.clone(),
-                                        id_name:    info.id_name.clone(),
-                                        id_source:  info.id_source.clone(),
-                                        item_type:  info.item_type.clone(),
-                                        durability: info.durability.clone(),
-                                        stack_size: info.stack_size.clone(),
-                                        stackable:  info.stackable.clone(),
-                                        count:      event.2,
-                                    },
-                                    SpriteSheetBundle {
-                                        texture: sprite.texture,
-                                        atlas: sprite.atlas,
-                                        transform: Transform {
-                                            translation: Vec3::new(event.1.x as f32 * 8. + 4., event.1.y as f32 * 8. + 4., 0.3),
-                                            scale: Vec3::new(0.5, 0.5, 0.0),
-                                            ..default()
-                                        },
-                                        ..default()
-                                    },
-                                    Name::new(info.id_name.clone())
-                                )).id();
-                            
-                            if let Some(var) = info.range_info {
-                                commands.entity(entity).insert(Gun {
-                                    shoot_cooldown:     var.0,
-                                    bullet_lifetime:    var.1,
-                                    shoot_timer:        var.2
-                                });
-                            }
-
-                            chunk_res.objects_ex.insert(event.1, entity);
-                        }
+        let mut item = ItemEntity::with_instance(
+            info,
+            event.2,
+            event.3.clone().unwrap_or_else(|| ItemInstance::new(info))
+        );
+
+        // Объединение с лежащим или уже добавленным в этом кадре стаком
+        if let Some(index) = pending_at.get(&event.1) {
+            merge_into(&mut pending[*index].1, &mut item);
+        } else if let Some(sub_obj_entity) = chunk_res.objects_ex.get(&event.1) {
+            if let Ok((_, mut stack)) = items.get_mut(*sub_obj_entity) {
+                merge_into(&mut stack, &mut item);
+            }
+        }
+
+        if item.count == 0 {
+            continue;
+        }
+
+        let Some(position) = (0..=ITEM_DROP_RADIUS)
+            .flat_map(|radius| ring(event.1, radius))
+            .find(|position| !chunk_res.objects_ex.contains_key(position) && !pending_at.contains_key(position))
+        else {
+            warn!("Нет места для предмета {} около {}", &event.0, event.1);
+            continue;
+        };
+
+        pending_at.insert(position, pending.len());
+        pending.push((position, item));
+    }
+
+    for (position, item) in pending {
+        let Some(info) = registry.get_item_info(&item.id_name) else {
+            continue;
+        };
+
+        let atlas_type = match info.item_type {
+            ItemType::Item(_) | ItemType::None  => AtlasType::Items,
+            ItemType::Weapon(_)                 => AtlasType::Weapon,
+            ItemType::Tool(_)                   => AtlasType::Tools,
+        };
+
+        if let Some(sprite) = registry.get_item_texture(&info.id_texture, &atlas, atlas_type) {
+            let entity = commands
+                .spawn((
+                    item,
+                    SpriteSheetBundle {
+                        texture: sprite.texture,
+                        atlas: sprite.atlas,
+                        transform: Transform {
+                            translation: Vec3::new(position.x as f32 * 8. + 4., position.y as f32 * 8. + 4., 0.3),
+                            scale: Vec3::new(0.5, 0.5, 0.0),
+                            ..default()
+                        },
+                        ..default()
                     },
+                    Name::new(info.id_name.clone())
+                )).id();
 
-                    ItemType::Tool(_) => {
-                        
-                    }
-                }
+            if let (ItemType::Weapon(_), Some(var)) = (info.item_type, info.range_info) {
+                commands.entity(entity).insert(Gun {
+                    shoot_cooldown:     var.0,
+                    bullet_lifetime:    var.1,
+                    shoot_timer:        var.2
+                });
             }
-        } else {
-            if let Some(sub_obj_entity) = chunk_res.objects_ex.get(&event.1) {
-                if let Ok(mut entity) = items.get_mut(*sub_obj_entity) {
-                    if entity.1.check_stackable() {
-                        if let Some(stack_size) = entity.1.stack_size {
-                            if (entity.1.count + event.2) > stack_size {
-                                entity.1.count = stack_size;
-                            } else {
-                                entity.1.count = entity.1.count + event.2;
-                            }
-                        } else {
-                            entity.1.count += event.2;
-                        }
-                    }
-                }
+
+            if let Some(light) = &info.light {
+                commands.entity(entity).insert(LightSource::from_info(light));
             }
+
+            chunk_res.objects_ex.insert(position, entity);
         }
     }
 }
@@ -215,26 +296,25 @@ pub fn take_item(
                 // Если есть источник, то проверяем расстояние до него
                 if let Ok(mut item) = item_q.get_mut(source) {
                     if customer.1.interaction_radius > Vec3::distance(item.1.translation, customer.1.position.0.extend(0.5)) {
-                        println!("{:?}", item.2);
-                        if customer.0.add(&mut item.2) {
-                            println!("{:?}", item.2);
-                            if item.2.count == 0 {
-                                chunk.remove_sub_object_ex(item.0);
-                                commands.entity(item.0).despawn_recursive();
-                            }
+                        if let Err(reason) = customer.0.add(&mut item.2) {
+                            info!("Inventory - {} не поднят полностью: {}", item.2.id_name, reason);
+                        }
+                        if item.2.count == 0 {
+                            chunk.remove_sub_object_ex(item.0);
+                            commands.entity(item.0).despawn_recursive();
                         }
                     }
                 }
             } else {
                 for mut item in &mut item_q {
                     if customer.1.interaction_radius > Vec3::distance(item.1.translation, customer.1.position.0.extend(0.5)) {
-                        if customer.0.add(&mut item.2) {
-                            if item.2.count == 0 {
-                                chunk.remove_sub_object_ex(item.0);
-                                commands.entity(item.0).despawn_recursive();
-                            }
-                        } else {
-                            continue;
+                        let added = customer.0.add(&mut item.2);
+                        if item.2.count == 0 {
+                            chunk.remove_sub_object_ex(item.0);
+                            commands.entity(item.0).despawn_recursive();
+                        }
+                        if let Err(reason) = added {
+                            info!("Inventory - {} не поднят полностью: {}", item.2.id_name, reason);
                         }
                     }
                 }
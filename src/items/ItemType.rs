@@ -25,6 +25,22 @@ impl ItemSizeType {
             ItemSizeType::Big   => (1.0, (16.0, 8.0))
         }
     }
+
+    /// Масса единицы предмета, если не указана в определении
+    pub fn default_mass(&self) -> f32 {
+        match self {
+            ItemSizeType::Small => 0.5,
+            ItemSizeType::Big   => 5.0
+        }
+    }
+
+    /// Объём единицы предмета, если не указан в определении (большой предмет занимает как 4 малых)
+    pub fn default_volume(&self) -> f32 {
+        match self {
+            ItemSizeType::Small => 1.0,
+            ItemSizeType::Big   => 4.0
+        }
+    }
 }
 
 #[derive(InspectorOptions, Debug, Default, PartialEq, Eq, Clone, Copy, Hash, Deserialize, Component, Reflect, Serialize)]
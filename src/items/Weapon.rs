@@ -3,9 +3,13 @@ use bevy::prelude::*;
 
 use crate::core::{
     PlayerSystem::PlayerAttach,
+    world::World::WorldInfo,
     resource::{
         Registry::Registry,
-        graphic::Atlas::AtlasRes
+        graphic::Atlas::{
+            AtlasRes,
+            AtlasType
+        }
     },
     EntityAnimation::EntityDirectionState,
     UserSystem::{
@@ -16,13 +20,20 @@ use crate::core::{
     Missile::{
         Bullet, 
         BULLET_LIFETIME, 
-        BULLET_SPEED
+        BULLET_SPEED,
+        BULLET_DAMAGE
+    },
+    ContainerSystem::{
+        Inventory,
+        Equipment,
+        EquipSlot,
+        WearItem
     },
-    ContainerSystem::Inventory,
     ItemType::{
         ItemType,
         Item
-    }
+    },
+    Attributes::Attack
 };
 
 #[derive(Component)]
@@ -32,17 +43,20 @@ pub struct Gun {
     pub shoot_timer:    f32, // время до возможности выстрела
 }
 
+/// Управление надетым огнестрельным оружием (спрайт экипировки в правой руке игрока)
 pub fn gun_controls(
     mut commands: Commands,
     mut gun_query: Query<(
         &mut Gun,
         &mut Transform,
+        &GlobalTransform,
         &mut Sprite,
-        &mut PlayerAttach,
+        &Parent,
     )>,
-    mut user_container: Query<&mut Inventory, With<UserControl>>,
+    mut user_container: Query<(&mut Inventory, &mut Equipment, Option<&Attack>), With<UserControl>>,
+    mut wear_event:     EventWriter<WearItem>,
     cursor:             Res<CursorPosition>,
-    time:               Res<Time>,
+    clock:              Res<WorldInfo>,
     _buttons:           Res<ButtonInput<MouseButton>>,
     _keyboard_input:    Res<ButtonInput<KeyCode>>,
     atlas:              Res<AtlasRes>,
@@ -52,21 +66,25 @@ pub fn gun_controls(
         return;
     }
 
-    let mut container = user_container.single_mut();
+    for (mut gun_controller, mut transform, global_transform, mut sprite, parent) in gun_query.iter_mut() {
+        // Оружие управляется только игроком, который его держит
+        let Ok((mut container, mut equipment, attack)) = user_container.get_mut(parent.get()) else {
+            continue;
+        };
 
-    for (mut gun_controller, mut transform, mut sprite, mut attach) in gun_query.iter_mut() {
-        gun_controller.shoot_timer -= time.delta_seconds();
+        gun_controller.shoot_timer -= clock.delta();
 
         let cursor_pos = cursor.0;
+        let gun_pos = global_transform.translation();
 
         let diff = Vec2::new(
-            cursor_pos.x - transform.translation.x,
-            cursor_pos.y - transform.translation.y,
+            cursor_pos.x - gun_pos.x,
+            cursor_pos.y - gun_pos.y,
         );
         let angle = diff.y.atan2(diff.x);
         transform.rotation = Quat::from_axis_angle(Vec3::new(0., 0., 1.), angle);
 
-        if cursor_pos.x > transform.translation.x {
+        if cursor_pos.x > gun_pos.x {
             sprite.flip_y = false
         } else {
             sprite.flip_y = true
@@ -74,12 +92,19 @@ pub fn gun_controls(
 
         if gun_controller.shoot_timer <= 0. {
             if _buttons.pressed(MouseButton::Right) {
-                attach.offset = Vec2::new(0., -2.);
                 if _buttons.pressed(MouseButton::Left) {
                     
                     let mut ammo_found = false;
 
-                    if container.take(("bullet".to_string(), 1)) {
+                    // Сначала тратятся заряженные в оружие боеприпасы, затем из инвентаря
+                    if let Some(gun) = equipment.bypass_change_detection().get_mut(EquipSlot::RightHand) {
+                        if let Some(ammo) = gun.instance.ammo.as_mut().filter(|ammo| **ammo > 0) {
+                            *ammo -= 1;
+                            ammo_found = true;
+                        }
+                    }
+
+                    if !ammo_found && container.take(("bullet".to_string(), 1)) {
                         ammo_found = true;
                     }
 
@@ -87,12 +112,15 @@ pub fn gun_controls(
                         return;
                     }
 
-                    let mut spawn_transform = Transform::from_scale(Vec3::splat(1.0));
-                    spawn_transform.translation = transform.translation;
+                    let mut spawn_transform = Transform::from_scale(Vec3::splat(0.5));
+                    spawn_transform.translation = gun_pos;
                     spawn_transform.rotation = Quat::from_axis_angle(Vec3::new(0., 0., 1.), angle);
                     gun_controller.shoot_timer = gun_controller.shoot_cooldown;
+                    wear_event.send(WearItem(parent.get(), EquipSlot::RightHand, 1));
 
-                    if let Some(sprite) = register.get_test("bullet_p", &atlas) {
+                    if let Some(sprite) = register.get_item_info("bullet")
+                        .and_then(|info| register.get_item_texture(&info.id_texture, &atlas, AtlasType::Items))
+                    {
                         let sprite_ex = (sprite.texture, sprite.atlas);
                         commands
                             .spawn(SpriteSheetBundle {
@@ -103,16 +131,17 @@ pub fn gun_controls(
                             })
                             .insert(Name::new("Bullet"))
                             .insert(Bullet {
-                                lifetime:   BULLET_LIFETIME,
+                                lifetime:   gun_controller.bullet_lifetime.min(BULLET_LIFETIME),
                                 speed:      BULLET_SPEED,
                                 direction:  diff.normalize(),
+                                // Атрибуты надетого оружия усиливают выстрел
+                                damage:     BULLET_DAMAGE + attack.map_or(0.0, |attack| attack.0 as f32),
+                                owner:      Some(parent.get()),
                             });
                     } else {
                         println!("error")
                     }
                 }
-            } else {
-                attach.offset = Vec2::new(0., -3.);
             }
         }
     }
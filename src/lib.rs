@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+// Определение модулей
+
+pub mod components;
+pub mod entities;
+pub mod items;
+pub mod objects;
+pub mod systems;
+pub mod util;
+
+/// Совокупность основных модулей, именуемое как `core` , для предоставления быстрого доступа ко всем модулям
+pub mod core {
+    #![allow(non_snake_case)]
+    pub use crate::AppState;
+
+    pub use crate::components::*;
+    pub use crate::entities::*;
+    pub use crate::items::*;
+    pub use crate::objects::*;
+    pub use crate::systems::*;
+
+    pub use crate::util::*;
+}
+
+/// Состояние приложения
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum AppState {
+    Start,              // Инициализация программы
+    #[default]
+    ResourceCheck,      // Этап проверки ресурсов
+    ResourceLoading,    // Этап загрузки ресурсов
+    LoadingError,       // Этап вывода ошибок загрузки ресурсов
+    MainMenu,           // Этап главного меню
+    LoadingInGame,      // Этап загрузки мира
+    Game,               // Этап игрового процесса
+    Pause,              // Пауза
+    SavingGame,         // Этап сохранения данных мира (при сохранении мир находится в состоянии Pause, может быть обратно переведён в game)
+    Finished,           // Этап полного сохранения данных и переход в главное меню/закрытие программы
+}
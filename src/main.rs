@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::{
     audio::{
         AudioPlugin, 
@@ -12,35 +14,15 @@ use bevy::{
     prelude::*
 };
 
-// Определение модулей
-
-mod components;
-mod entities;
-mod items;
-mod objects;
-mod systems;
-mod util;
-
-/// Совокупность основных модулей, именуемое как `core` , для предоставления быстрого доступа ко всем модулям
-mod core {
-    #![allow(non_snake_case)]
-    pub use crate::AppState;
-
-    pub use crate::components::*;
-    pub use crate::entities::*;
-    pub use crate::items::*;
-    pub use crate::objects::*;
-    pub use crate::systems::*;
-
-    pub use crate::util::*;
-}
-
-use crate::core::{
-    resource::ResourcePlugin,
-    world::World::WorldSystem,
-    Camera::CameraController,
-    interface::UIPlugin,
-    UserSystem::UserPlugin
+use sint_et::{
+    core::{
+        resource::ResourcePlugin,
+        world::World::WorldSystem,
+        Camera::CameraController,
+        interface::UIPlugin,
+        UserSystem::UserPlugin
+    },
+    AppState
 };
 
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
@@ -61,8 +43,10 @@ fn main() {
         // Есть возможность изменения размера окна
         // [Test] Включена Вертикальная синхронизация (у меня 144 герц) [AutoVsync]
         // Установлен nearest фильтр, чтобы спрайты были несглаженные.
+        // Изменения файлов модулей отслеживаются для перезагрузки определений и текстур
         .register_asset_source("Data", AssetSource::build()
             .with_reader(|| Box::new(FileAssetReader::new("Data")))
+            .with_watcher(AssetSource::get_default_watcher("Data".to_string(), Duration::from_millis(300)))
         )
         .add_plugins(DefaultPlugins
                 .set(
@@ -128,18 +112,3 @@ fn setup(mut _commands: Commands) {
         ));
     }
 }
-
-/// Состояние приложения
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
-pub enum AppState {
-    Start,              // Инициализация программы
-    #[default]
-    ResourceCheck,      // Этап проверки ресурсов
-    ResourceLoading,    // Этап загрузки ресурсов
-    MainMenu,           // Этап главного меню
-    LoadingInGame,      // Этап загрузки мира
-    Game,               // Этап игрового процесса
-    Pause,              // Пауза
-    SavingGame,         // Этап сохранения данных мира (при сохранении мир находится в состоянии Pause, может быть обратно переведён в game)
-    Finished,           // Этап полного сохранения данных и переход в главное меню/закрытие программы
-}
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Sensor;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    resource::{
+        graphic::Atlas::{
+            AtlasRes,
+            AtlasType
+        },
+        Registry::DoorRegistry
+    },
+    world::{
+        World::WorldInfo,
+        Grid::{
+            Grid,
+            DoorPassage
+        }
+    },
+    entities::ai::Path::AiPath,
+    Entity::EntityBase,
+    EntityType::EntityNeutrality,
+    Object::{
+        EntityObject,
+        Footprint,
+        direction_rotation
+    },
+    interact::Interact::DoorToggle,
+    UserSystem::UserControl,
+    AppState
+};
+
+//
+//
+//
+
+pub fn door_plugin(app: &mut App) {
+    app.add_systems(Update,
+        (
+            toggle_door,
+            ai_open_doors,
+            auto_close_doors,
+            apply_door_state,
+            remove_door_passage
+        ).chain().run_if(in_state(AppState::Game))
+    );
+}
+
+//
+//
+//
+
+/// Дистанция, на которой сущность с путём открывает дверь
+pub const DOOR_OPEN_DISTANCE: f32 = 20.0;
+/// Дистанция, на которой сущность не даёт двери закрыться
+pub const DOOR_BLOCK_DISTANCE: f32 = 10.0;
+
+/// Состояние двери
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DoorState {
+    Open,
+    #[default]
+    Closed,
+    Locked,
+}
+
+/// Компонент двери, открытая дверь не имеет коллизии и проходима в `Grid`
+#[derive(Component, Clone, Debug)]
+pub struct Door {
+    pub state:          DoorState,
+    pub lockable:       bool,                       // Закрытая дверь запирается
+    pub faction:        Option<EntityNeutrality>,   // Фракция, которая может открыть запертую дверь
+    pub auto_close:     Option<f32>,                // Закрывается сама через n секунд
+    pub open_time:      f32,                        // Сколько дверь уже открыта
+    pub texture_closed: String,
+    pub texture_open:   Option<String>,
+}
+
+impl Door {
+    pub fn from_info(id_texture: &str, info: &DoorRegistry) -> Self {
+        Self {
+            state:          if info.locked { DoorState::Locked } else { DoorState::Closed },
+            lockable:       info.locked,
+            faction:        info.faction,
+            auto_close:     info.auto_close,
+            open_time:      0.0,
+            texture_closed: id_texture.to_string(),
+            texture_open:   info.id_texture_open.clone(),
+        }
+    }
+
+    /// Может ли сущность данной фракции открыть дверь
+    pub fn can_pass(&self, faction: Option<EntityNeutrality>) -> bool {
+        self.state != DoorState::Locked || (self.faction.is_some() && self.faction == faction)
+    }
+
+    pub fn open(&mut self) {
+        self.state = DoorState::Open;
+        self.open_time = 0.0;
+    }
+
+    pub fn close(&mut self) {
+        self.state = if self.lockable { DoorState::Locked } else { DoorState::Closed };
+        self.open_time = 0.0;
+    }
+
+    /// Проходимость двери для поиска пути
+    pub fn passage(&self, entity: Entity) -> DoorPassage {
+        DoorPassage {
+            entity,
+            open:       self.state == DoorState::Open,
+            locked:     self.state == DoorState::Locked,
+            faction:    self.faction,
+        }
+    }
+}
+
+/// Открытие и закрытие двери сущностью
+fn toggle_door(
+    mut doors:  Query<&mut Door>,
+    mut event:  EventReader<DoorToggle>,
+        actors: Query<Option<&EntityNeutrality>>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok(mut door) = doors.get_mut(event.0) else {
+            continue;
+        };
+        let faction = actors.get(event.1).ok().flatten().copied();
+
+        match door.state {
+            DoorState::Open => door.close(),
+            _ if door.can_pass(faction) => door.open(),
+            _ => info!("Door - {:?} заперта для {:?}", event.0, faction),
+        }
+    }
+}
+
+/// Сущности, идущие по пути, открывают двери перед собой
+fn ai_open_doors(
+    mut doors:      Query<(&mut Door, &Transform)>,
+        walkers:    Query<(&AiPath, &Transform, Option<&EntityNeutrality>), Without<UserControl>>,
+) {
+    for (path, transform, faction) in &walkers {
+        if path.locations.is_empty() {
+            continue;
+        }
+
+        for (mut door, door_transform) in &mut doors {
+            if door.state != DoorState::Open
+                && door.can_pass(faction.copied())
+                && door_transform.translation.truncate().distance(transform.translation.truncate()) <= DOOR_OPEN_DISTANCE
+            {
+                door.open();
+            }
+        }
+    }
+}
+
+/// Автоматическое закрытие двери, если в проёме никого нет
+fn auto_close_doors(
+    mut doors:      Query<(&mut Door, &Transform)>,
+        entities:   Query<&Transform, With<EntityBase>>,
+        clock:      Res<WorldInfo>,
+) {
+    for (mut door, door_transform) in &mut doors {
+        let Some(auto_close) = door.auto_close else {
+            continue;
+        };
+        if door.state != DoorState::Open {
+            continue;
+        }
+
+        let blocked = entities.iter().any(|transform| {
+            transform.translation.truncate().distance(door_transform.translation.truncate()) <= DOOR_BLOCK_DISTANCE
+        });
+
+        // Таймер не должен вызывать `Changed<Door>`, только смена состояния
+        if blocked {
+            door.bypass_change_detection().open_time = 0.0;
+            continue;
+        }
+
+        door.bypass_change_detection().open_time += clock.delta();
+        if door.open_time >= auto_close {
+            door.close();
+        }
+    }
+}
+
+/// Обновление коллизии, спрайта и проходимости двери при изменении состояния
+fn apply_door_state(
+    mut commands:   Commands,
+    mut doors:      Query<(Entity, &Door, &EntityObject, &Footprint, &mut Transform, &mut TextureAtlas, &mut Sprite), Changed<Door>>,
+    mut grid:       ResMut<Grid>,
+        atlas:      Res<AtlasRes>,
+) {
+    for (entity, door, object, footprint, mut transform, mut texture, mut sprite) in &mut doors {
+        let open = door.state == DoorState::Open;
+
+        if open {
+            commands.entity(entity).insert(Sensor);
+        } else {
+            commands.entity(entity).remove::<Sensor>();
+        }
+
+        for tile in footprint.tiles() {
+            grid.doors.insert(tile, door.passage(entity));
+        }
+
+        // Открытая дверь без своей текстуры становится полупрозрачной
+        let texture_name = match (&door.texture_open, open) {
+            (Some(texture_open), true) => texture_open,
+            _ => &door.texture_closed,
+        };
+        if let Some((atlas_texture, _)) = atlas.get_texture(AtlasType::Objects, texture_name) {
+            *texture = atlas_texture;
+        }
+        sprite.color.set_a(if open && door.texture_open.is_none() { 0.4 } else { 1.0 });
+
+        // Дверь, повёрнутая на запад или восток, стоит вертикально
+        transform.rotation = direction_rotation(object.direction);
+    }
+}
+
+/// Удаление разрушенных дверей из `Grid`
+fn remove_door_passage(
+    mut removed:    RemovedComponents<Door>,
+    mut grid:       ResMut<Grid>,
+) {
+    for entity in removed.read() {
+        grid.doors.retain(|_, passage| passage.entity != entity);
+    }
+}
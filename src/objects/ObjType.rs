@@ -1,13 +1,29 @@
 #![allow(unused)]
-use std::default;
+use std::{
+    default,
+    time::Duration
+};
 
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 
 use crate::core::{
-    resource::graphic::Atlas::TestTextureAtlas,
+    resource::{
+        graphic::Atlas::AtlasRes,
+        Registry::{
+            Registry,
+            PlantRegistry,
+            OBJECT_TILE_TEXELS
+        },
+        SpriteLayer
+    },
+    world::Grid::Grid,
+    Object::Footprint,
+    TimeCycle::Season,
     AppState,
     ItemType::{
         ItemType,
+        Tool
     }
 };
 
@@ -15,6 +31,9 @@ use serde::{Serialize, Deserialize};
 
 use bevy_inspector_egui::InspectorOptions;
 
+/// Как часто загруженное растение пересчитывает свой рост (в секундах)
+pub const PLANT_UPDATE_TIME: f32 = 1.0;
+
 #[derive(Serialize, Deserialize)]
 pub enum ObjectSizeType {
     Small,
@@ -35,89 +54,105 @@ pub enum WorldObject {
 pub struct Collision; 
 
 impl WorldObject {
-    pub fn spawn(self, commands: &mut Commands, graphics: &TestTextureAtlas, position: Vec2) {
-        // -> Entity {
-        // let sprite = graphics
-        //     .item_map
-        //     .get(&self)
-        //     .expect(&format!("No graphic for object {:?}", self))
-        //     .clone();
-
-        // let item = commands
-        //     .spawn_bundle(SpriteSheetBundle {
-        //         texture: graphics.image.clone(),
-        //         atlas: graphics.layout.clone(),
-        //         transform: Transform {
-        //             translation: position.extend(0.0),
-        //             ..Default::default()
-        //         },
-        //         ..Default::default()
-        //     })
-        //     .insert(Name::new("GroundItem"))
-        //     .insert(self)
-        //     .id();
-
-        // if let Some(harvest) = self.as_harvest() {
-        //     commands.entity(item).insert(harvest);
-        // }
-
-        // if let Some(pickup) = self.as_pickup() {
-        //     commands.entity(item).insert(pickup);
-        // }
-
-        // if self.grows_into().is_some() {
-        //     commands.entity(item).insert(GrowthTimer {
-        //         timer: Timer::from_seconds(3.0, false),
-        //     });
-        // }
-
-        // item
+    /// Спавн растения на клетке по его состоянию, `None` - клетка занята или нет текстуры стадии
+    pub fn spawn(
+        self,
+        commands:   &mut Commands,
+        registry:   &Registry,
+        atlas:      &AtlasRes,
+        grid:       &mut Grid,
+        tile:       IVec2,
+        state:      &PlantState,
+    ) -> Option<Entity> {
+        let WorldObject::Plant(_) = self else {
+            return None;
+        };
+        let info = registry.get_plant_info(&state.id_name)?;
+        let Some(sprite) = registry.get_plant_texture(info, state.stage, atlas) else {
+            warn!("В атласе не была найдена текстура стадии {} для - {}", state.stage, &state.id_name);
+            return None;
+        };
+
+        let footprint = Footprint::single(tile);
+        if !grid.can_place(&footprint) {
+            return None;
+        }
+
+        let entity = commands.spawn((
+            PlantObject {
+                id_name:    state.id_name.clone(),
+                tile,
+            },
+            self,
+            SpriteSheetBundle {
+                texture: sprite.texture,
+                atlas: sprite.atlas,
+                transform: Transform {
+                    translation:    footprint.center().extend(0.8),
+                    scale:          Vec3::splat(16.0 / OBJECT_TILE_TEXELS as f32),
+                    ..default()
+                },
+                ..default()
+            },
+            footprint,
+            GrowthTimer::default(),
+            SpriteLayer::Object,
+            Name::new(state.id_name.clone())
+        )).id();
+
+        if let Some(collision) = &info.collision {
+            commands.entity(entity).insert((RigidBody::Fixed, Collider::cuboid(collision.x, collision.y)));
+        }
+
+        if let Some(harvest) = self.as_harvest(info, state.stage) {
+            commands.entity(entity).insert(harvest);
+        }
+
+        if !grid.place_object(entity, &footprint) {
+            commands.entity(entity).despawn();
+            return None;
+        }
+        Some(entity)
     }
 
+    /// Смена спрайта растения на текущую стадию, созревшее растение становится `Harvestable`
     pub fn grow(
         self,
-        commands: &mut Commands,
-        graphics: &TestTextureAtlas,
-        ent: Entity,
-        transform: &Transform,
-    ) { // -> Entity {
-         // if let Some(new_object) = self.grows_into() {
-         //     commands.entity(ent).despawn_recursive();
-         //     new_object.spawn(commands, graphics, transform.translation.truncate())
-         //     //println!("{:?} grew into a beautiful {:?}", self, self.grows_into());
-         // } else {
-         //     ent
-         // }
+        commands:   &mut Commands,
+        registry:   &Registry,
+        atlas:      &AtlasRes,
+        ent:        Entity,
+        info:       &PlantRegistry,
+        state:      &PlantState,
+    ) {
+        if let Some(sprite) = registry.get_plant_texture(info, state.stage, atlas) {
+            commands.entity(ent).insert((sprite.texture, sprite.atlas));
+        }
+
+        match self.as_harvest(info, state.stage) {
+            Some(harvest) => commands.entity(ent).insert(harvest),
+            None => commands.entity(ent).remove::<Harvestable>(),
+        };
     }
 
-    /// TODO it would be great to describe this outside of code, in a config or something
-    pub fn grows_into(&self) -> Option<WorldObject> {
+    /// Следующая стадия роста, `None` - растение выросло
+    pub fn grows_into(&self, info: &PlantRegistry, stage: usize) -> Option<usize> {
         match self {
-            // WorldObject::DeadSapling => Some(WorldObject::Sapling),
-            // WorldObject::PluckedGrass => Some(WorldObject::Grass),
-            // WorldObject::GrowingTree => Some(WorldObject::Tree),
+            WorldObject::Plant(_) => (stage + 1 < info.stages.len()).then_some(stage + 1),
             _ => None,
         }
     }
 
-    /// TODO it would be great to describe this outside of code, in a config or something
-    pub fn as_harvest(&self) -> Option<Harvestable> {
+    /// Урожай растения на данной стадии, `None` - собирать нечего
+    pub fn as_harvest(&self, info: &PlantRegistry, stage: usize) -> Option<Harvestable> {
         match self {
-            // WorldObject::Sapling => Some(Harvestable {
-            //     item: ItemType::Twig,
-            //     tool_required: None,
-            //     drops: Some(WorldObject::DeadSapling),
-            // }),
-            // WorldObject::Grass => Some(Harvestable {
-            //     item: ItemType::Grass,
-            //     tool_required: None,
-            //     drops: Some(WorldObject::PluckedGrass),
-            // }),
-            // WorldObject::Tree => Some(Harvestable {
-            //     item: ItemType::Wood,
-            //     tool_required: Some(Tool::Axe),
-            //     drops: Some(WorldObject::Stump),
-            // }),
+            WorldObject::Plant(_) => {
+                let harvest = info.harvest.as_ref()?;
+                (stage >= info.harvest_stage()?).then(|| Harvestable {
+                    tool:   harvest.tool,
+                    work:   harvest.work,
+                })
+            },
             _ => None,
         }
     }
@@ -134,7 +169,7 @@ impl WorldObject {
 }
 
 /// Перечисление того, что является растением
-#[derive(InspectorOptions, Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize, Component)]
+#[derive(InspectorOptions, Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize, Serialize, Component)]
 pub enum PlantType {
     Grass,
     Tree,
@@ -142,16 +177,86 @@ pub enum PlantType {
     Flower,
 }
 
+/// Растение в мире (состояние роста хранится в `PlantSystem::Plants` по клетке)
+#[derive(Component, Clone, Debug)]
+pub struct PlantObject {
+    pub id_name:    String,
+    pub tile:       IVec2,
+}
+
+/// Состояние роста растения, переживает выгрузку чанка
+#[derive(Clone, Debug)]
+pub struct PlantState {
+    pub id_name:        String,
+    pub stage:          usize,
+    pub progress:       f32,            // Прогресс текущей стадии в секундах мира
+    pub last_update:    f64,            // Время мира, до которого рост уже посчитан
+    pub designated:     bool,           // Растение отмечено для сбора рабочими
+    pub entity:         Option<Entity>, // Сущность растения, пока его чанк загружен
+}
+
+impl PlantState {
+    pub fn new(id_name: String, now: f64) -> Self {
+        Self {
+            id_name,
+            stage:          0,
+            progress:       0.0,
+            last_update:    now,
+            designated:     false,
+            entity:         None,
+        }
+    }
+
+    /// Рост за время, прошедшее с прошлого обновления, возвращает смену стадии
+    ///
+    /// `seasons` - это время, разбитое по временам года, каждый отрезок растёт со скоростью своего времени года
+    pub fn advance(&mut self, info: &PlantRegistry, now: f64, seasons: &[(Season, f32)], light: f32) -> bool {
+        let before = self.stage;
+        let plant = WorldObject::Plant(info.plant_type);
+
+        self.last_update = now;
+        self.progress += seasons.iter().map(|(season, elapsed)| elapsed.max(0.0) * info.growth_rate(*season, light)).sum::<f32>();
+
+        while let Some(next) = plant.grows_into(info, self.stage) {
+            let duration = info.stages[self.stage].duration;
+            if self.progress < duration {
+                break;
+            }
+            self.progress -= duration;
+            self.stage = next;
+        }
+        if plant.grows_into(info, self.stage).is_none() {
+            self.progress = 0.0;
+        }
+
+        self.stage != before
+    }
+}
+
 /// Структура для того, что может быть собрано
-#[derive(Component)]
+#[derive(Component, Clone, Copy, Debug)]
 pub struct Harvestable {
-    //pub(crate) item: ItemType,
-    //pub(crate) tool_required: Option<Tool>,
-    pub(crate) drops: Option<WorldObject>,
+    pub tool:   Option<Tool>,   // Инструмент, без которого урожай не собрать
+    pub work:   f32,            // Время сбора рабочим в секундах
 }
 
-#[derive(Component, Reflect, Default)]
+/// Период пересчёта роста загруженного растения
+#[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct GrowthTimer {
     timer: Timer,
 }
+
+impl Default for GrowthTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(PLANT_UPDATE_TIME, TimerMode::Repeating),
+        }
+    }
+}
+
+impl GrowthTimer {
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta).just_finished()
+    }
+}
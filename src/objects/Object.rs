@@ -5,18 +5,27 @@ use crate::core::{
     resource::{
         graphic::{
             Atlas::AtlasRes, Connect::ConnectedObject
-        }, Registry::Registry, SpriteLayer
+        }, Registry::{
+            Registry,
+            ObjectRegistry
+        }, SpriteLayer
     }, 
     world::{
         World::*,
-        chunk::Chunk::Chunk, 
         Grid::*
     }, 
     Entity::{
         Health,
         Position
     }, 
-    ObjectAnimation::ObjectDirectionState
+    ObjectAnimation::ObjectDirectionState,
+    ContainerSystem::Inventory,
+    interact::Interact::Interactable,
+    LightSystem::{
+        LightOccluder,
+        LightSource
+    },
+    Door::Door
 };
 
 // ====================
@@ -45,6 +54,67 @@ impl Default for EntityObject {
     }
 }
 
+/// Клетки, занимаемые объектом (0 - Якорь, нижняя левая клетка | 1 - Размер в клетках с учётом поворота)
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Footprint(pub IVec2, pub IVec2);
+
+impl Footprint {
+    /// Объект, повёрнутый на запад или восток, меняет ширину и высоту местами
+    pub fn new(anchor: IVec2, size: IVec2, direction: ObjectDirectionState) -> Self {
+        match direction {
+            ObjectDirectionState::West | ObjectDirectionState::East => Self(anchor, IVec2::new(size.y, size.x)),
+            _ => Self(anchor, size),
+        }
+    }
+
+    pub fn single(tile: IVec2) -> Self {
+        Self(tile, IVec2::ONE)
+    }
+
+    /// Все клетки объекта
+    pub fn tiles(&self) -> impl Iterator<Item = IVec2> {
+        let Self(anchor, size) = *self;
+        (0..size.x).flat_map(move |x| (0..size.y).map(move |y| anchor + IVec2::new(x, y)))
+    }
+
+    /// Центр объекта в глобальных координатах
+    pub fn center(&self) -> Vec2 {
+        (self.0 * 16).as_vec2() + (self.1 * 16).as_vec2() / 2.0
+    }
+
+    pub fn contains(&self, tile: IVec2) -> bool {
+        tile.cmpge(self.0).all() && tile.cmplt(self.0 + self.1).all()
+    }
+
+    /// Расстояние от точки до ближайшего края объекта (внутри объекта - 0)
+    pub fn distance(&self, point: Vec2) -> f32 {
+        let min = (self.0 * 16).as_vec2();
+        let max = ((self.0 + self.1) * 16).as_vec2();
+        point.clamp(min, max).distance(point)
+    }
+}
+
+/// Поворот спрайта и коллизии объекта по направлению
+pub fn direction_rotation(direction: ObjectDirectionState) -> Quat {
+    match direction {
+        ObjectDirectionState::West | ObjectDirectionState::East => Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+        _ => Quat::IDENTITY,
+    }
+}
+
+/// Положение, поворот и масштаб спрайта объекта на его клетках
+/// 
+/// Спрайт растягивается на все клетки объекта (до поворота)
+pub fn object_transform(info: &ObjectRegistry, footprint: &Footprint, direction: ObjectDirectionState) -> Transform {
+    let scale = (info.footprint() * 16).as_vec2() / Vec2::new(info.size.x.max(1) as f32, info.size.y.max(1) as f32);
+
+    Transform {
+        translation:    footprint.center().extend(0.8),
+        rotation:       direction_rotation(direction),
+        scale:          scale.extend(1.0),
+    }
+}
+
 // ====================
 // Entity Persistent Object
 // ====================
@@ -64,7 +134,7 @@ impl Default for PersistentObject {
             id_name:    "Object".to_string(),
             health:     Health(2.),
             position:   Position(Vec2::ZERO),
-            direction:  ConnectedObject::South
+            direction:  ConnectedObject::None
         }
     }
 }
@@ -73,9 +143,9 @@ impl Default for PersistentObject {
 // Entity Objects Spawner
 // ======================
 
-/// Событие спавна объекта
+/// Событие спавна объекта (0 - Объект | 1 - Глобальные координаты якорной клетки | 2 - Направление)
 #[derive(Event)]
-pub struct ObjectSpawn(pub String, pub IVec2);
+pub struct ObjectSpawn(pub String, pub IVec2, pub ObjectDirectionState);
 
 /// Функция отвечающая за спавн объекта при вызове события спавна.
 pub fn spawn_object(
@@ -90,58 +160,102 @@ pub fn spawn_object(
     }
 
     for event in event.read() {
-        if let Some(info) = registry.get_object_info(&event.0) {
-            if let Some(sprite) = registry.get_object_texture(&info.id_texture, &atlas) {
-                let coord = WorldSystem::get_currect_chunk_tile(event.1);
-
-                let entity = commands.spawn((
-                    EntityObject {
-                        id_name: info.id_name.clone(),
-                        health:  Health(info.health.clone() as f32), 
-                        ..default()
-                    },
-                    SpriteSheetBundle {
-                        texture: sprite.texture,
-                        atlas: sprite.atlas,
-                        transform: Transform {
-                            translation:    Vec3::new(coord.x as f32 * 16.0 + 8.0, coord.y as f32 * 16.0 + 8.0, 0.8), // Откорректировать
-                            scale:          Vec3::splat(0.5),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    SpriteLayer::Object,
-                    RigidBody::Fixed,
-                    Collider::cuboid(info.collision.x, info.collision.y),
-                    Name::new(info.id_name.clone())
-                )).id();
-
-                if !grid.add_object_to_chunk(entity, event.1) {
-                    // println!("Object {} been deleted, due to an installation error!", &event.0);
-                    commands.entity(entity).despawn();
-                }
-            } else {
-                warn!("В атласе не была найдена текстура для - {}", &event.0);
-                break;
-            }
-        } else {
-            warn!("В регистре не найден объект - {}", &event.0);
-            break;
-        }
+        place_object(&mut commands, &registry, &atlas, &mut grid, &event.0, event.1, event.2);
     }
 }
 
-#[allow(unused)]
-/// Событие спавна постоянного объекта
+/// Спавн объекта с занятием клеток сетки в этом же вызове.
+///
+/// `None` - объект не найден в реестре или атласе, либо его клетки заняты
+pub fn place_object(
+    commands:   &mut Commands,
+    registry:   &Registry,
+    atlas:      &AtlasRes,
+    grid:       &mut Grid,
+    id_name:    &str,
+    position:   IVec2,
+    direction:  ObjectDirectionState,
+) -> Option<Entity> {
+    let Some(info) = registry.get_object_info(id_name) else {
+        warn!("В регистре не найден объект - {}", id_name);
+        return None;
+    };
+    let Some(sprite) = registry.get_object_texture(&info.id_texture, atlas) else {
+        warn!("В атласе не была найдена текстура для - {}", id_name);
+        return None;
+    };
+
+    let coord = WorldSystem::get_currect_chunk_tile(position);
+    let footprint = Footprint::new(coord, info.footprint(), direction);
+
+    // Все клетки объекта должны быть свободны
+    if !grid.can_place(&footprint) {
+        info!("Object {} не помещается на {:?}", id_name, coord);
+        return None;
+    }
+
+    let entity = commands.spawn((
+        EntityObject {
+            id_name:    info.id_name.clone(),
+            health:     Health(info.health.clone() as f32), 
+            direction,
+            ..default()
+        },
+        SpriteSheetBundle {
+            texture: sprite.texture,
+            atlas: sprite.atlas,
+            transform: object_transform(info, &footprint, direction),
+            ..default()
+        },
+        footprint,
+        SpriteLayer::Object,
+        RigidBody::Fixed,
+        Collider::cuboid(info.collision.x, info.collision.y),
+        Name::new(info.id_name.clone())
+    )).id();
+
+    // Объект-хранилище получает собственный инвентарь
+    if let Some(container) = &info.container {
+        commands.entity(entity).insert(
+            Inventory::with_capacity(container.capacity).with_limits(container.mass, container.volume)
+        );
+    }
+
+    if !info.interactions.is_empty() {
+        commands.entity(entity).insert(Interactable(info.interactions.clone()));
+    }
+
+    if let Some(door) = &info.door {
+        commands.entity(entity).insert(Door::from_info(&info.id_texture, door));
+    }
+
+    if info.opaque {
+        commands.entity(entity).insert(LightOccluder);
+    }
+
+    if let Some(light) = &info.light {
+        commands.entity(entity).insert(LightSource::from_info(light));
+    }
+
+    if !grid.place_object(entity, &footprint) {
+        // println!("Object {} been deleted, due to an installation error!", &event.0);
+        commands.entity(entity).despawn();
+        return None;
+    }
+    Some(entity)
+}
+
+/// Событие спавна постоянного объекта (соединяющегося с соседями, как стены)
 #[derive(Event)]
 pub struct PersistentObjectSpawn(pub String, pub IVec2);
 
-#[allow(unused)]
 /// Функция отвечающая за спавн постоянного объекта при вызове события спавна.
+/// 
+/// Вариант соединения выставляется в `Connect::update_connected_objects`
 pub fn spawn_persistent_object(
     mut commands:   Commands,
         registry:   Res<Registry>,
-    mut chunk_res:  ResMut<Chunk>,
+    mut grid:       ResMut<Grid>,
         atlas:      Res<AtlasRes>,
     mut event:      EventReader<PersistentObjectSpawn>
 ) {
@@ -150,34 +264,44 @@ pub fn spawn_persistent_object(
     }
 
     for event in event.read() {
-        if !chunk_res.objects.contains_key(&event.1) {
-            if let Some(info) = registry.get_object_ct_info(&event.0) {
-                if let Some(sprite) = registry.get_object_ct_texture(&info.id_texture, &atlas) {
-                    let entity = commands
-                        .spawn((
-                            PersistentObject {
-                                id_name: info.id_name.clone(),
-                                ..default()
-                            },
-                            SpriteSheetBundle {
-                                texture: sprite.texture,
-                                atlas: sprite.atlas,
-                                transform: Transform {
-                                    translation:    Vec3::new(event.1.x as f32 * 16. + 8., event.1.y as f32 * 3162. + 8., 0.8), // Откорректировать
-                                    scale:          Vec3::splat(0.5),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            SpriteLayer::Object,
-                            RigidBody::Fixed,
-                            Collider::cuboid(info.collision.x, info.collision.y),
-                            Name::new(info.id_name.clone())
-                        )).id();
-                    
-                    chunk_res.objects.insert(event.1, entity);
-                }
-            }
+        if grid.check_exist_object(&event.1) {
+            continue;
+        }
+
+        let Some(info) = registry.get_object_ct_info(&event.0) else {
+            warn!("В регистре не найден объект - {}", &event.0);
+            continue;
+        };
+        let Some(sprite) = registry.get_object_ct_texture(&info.id_texture, &atlas) else {
+            warn!("В атласе не была найдена текстура для - {}", &event.0);
+            continue;
+        };
+        let coord = WorldSystem::get_currect_chunk_tile(event.1);
+
+        let entity = commands
+            .spawn((
+                PersistentObject {
+                    id_name: info.id_name.clone(),
+                    health:  Health(info.durability.unwrap_or(2) as f32),
+                    ..default()
+                },
+                SpriteSheetBundle {
+                    texture: sprite.texture,
+                    atlas: sprite.atlas,
+                    transform: Transform {
+                        translation:    Vec3::new(coord.x as f32 * 16. + 8., coord.y as f32 * 16. + 8., 0.8),
+                        ..default()
+                    },
+                    ..default()
+                },
+                SpriteLayer::Object,
+                RigidBody::Fixed,
+                Collider::cuboid(info.collision.x, info.collision.y),
+                Name::new(info.id_name.clone())
+            )).id();
+
+        if !grid.add_object_to_chunk(entity, event.1) {
+            commands.entity(entity).despawn();
         }
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-#[derive(Component, Default, Debug, Reflect, PartialEq)]
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+#[derive(Component, Default, Debug, Reflect, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ObjectDirectionState {
     #[default]
     South,
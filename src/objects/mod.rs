@@ -1,4 +1,5 @@
 #![allow(non_snake_case)]
+pub mod Door;
 pub mod ObjectAnimation;
 pub mod Object;
 pub mod ObjType;
\ No newline at end of file
@@ -0,0 +1,500 @@
+use bevy::prelude::*;
+
+use bevy_egui::EguiContexts;
+
+use crate::core::{
+    resource::{
+        graphic::Atlas::AtlasRes,
+        Registry::Registry
+    },
+    world::{
+        World::{
+            WorldSystem,
+            WorldInfo
+        },
+        Grid::Grid
+    },
+    Craft::CraftIngredient,
+    ContainerSystem::Inventory,
+    Entity::EntityBase,
+    Item::ItemSpawn,
+    entities::ai::Job::{
+        CancelJob,
+        JobBoard,
+        JobKind,
+        PostJob
+    },
+    Object::{
+        EntityObject,
+        Footprint,
+        object_transform,
+        place_object
+    },
+    ObjectAnimation::ObjectDirectionState,
+    UserSystem::{
+        CursorMode,
+        CursorPosition,
+        UserControl
+    },
+    stats::{
+        ExperienceGain,
+        XpSource
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn build_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<BuildPlacer>()
+        // Init Events
+        .add_event::<BuildWork>()
+        .add_event::<BlueprintCancel>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                build_input,
+                update_build_ghosts,
+                player_build,
+                apply_build_work,
+                cancel_blueprint
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Минимальный объём работы над чертежом в секундах
+pub const BUILD_MIN_WORK: f32 = 0.1;
+/// Цвет призрака, который можно поставить
+pub const GHOST_VALID_COLOR: Color = Color::rgba(0.4, 1.0, 0.4, 0.5);
+/// Цвет призрака, который нельзя поставить
+pub const GHOST_INVALID_COLOR: Color = Color::rgba(1.0, 0.3, 0.3, 0.5);
+/// Цвет чертежа
+pub const BLUEPRINT_COLOR: Color = Color::rgba(0.5, 0.7, 1.0, 0.5);
+
+/// Объект, выбранный для строительства в `CursorMode::Build`
+#[derive(Resource, Default)]
+pub struct BuildPlacer {
+    pub object:     Option<String>,
+    pub direction:  ObjectDirectionState,
+    pub drag_start: Option<IVec2>,  // Клетка, с которой начато протягивание
+}
+
+impl BuildPlacer {
+    /// Выбор объекта и переход в режим строительства
+    pub fn select(&mut self, object: &str, cursor_mode: &mut CursorMode) {
+        self.object = Some(object.to_string());
+        self.drag_start = None;
+        *cursor_mode = CursorMode::Build;
+    }
+
+    pub fn rotate(&mut self) {
+        self.direction = match self.direction {
+            ObjectDirectionState::South => ObjectDirectionState::West,
+            ObjectDirectionState::West  => ObjectDirectionState::North,
+            ObjectDirectionState::North => ObjectDirectionState::East,
+            ObjectDirectionState::East  => ObjectDirectionState::South,
+        };
+    }
+}
+
+/// Полупрозрачный призрак строящегося объекта под курсором
+#[derive(Component)]
+pub struct BuildGhost;
+
+/// Чертёж объекта, становится объектом после доставки материалов и работы
+#[derive(Component, Clone, Debug)]
+pub struct Blueprint {
+    pub object:     String,
+    pub direction:  ObjectDirectionState,
+    pub materials:  Vec<CraftIngredient>,   // Ещё не доставленные материалы
+    pub delivered:  Vec<CraftIngredient>,   // Доставленные материалы (возвращаются при отмене)
+    pub work:       f32,                    // Оставшаяся работа в секундах
+    pub total:      f32,
+    pub xp:         u32,
+}
+
+impl Blueprint {
+    /// Все материалы доставлены
+    pub fn supplied(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Прогресс строительства от 0 до 1
+    pub fn progress(&self) -> f32 {
+        (1.0 - self.work / self.total).clamp(0.0, 1.0)
+    }
+
+    /// Доставка материалов из инвентаря, забирается сколько есть
+    pub fn deliver(&mut self, inventory: &mut Inventory) {
+        for material in self.materials.iter_mut() {
+            let count = inventory.count_of(&material.item).min(material.count);
+            if count == 0 || inventory.remove_count(&material.item, count).is_err() {
+                continue;
+            }
+
+            material.count -= count;
+            match self.delivered.iter_mut().find(|delivered| delivered.item == material.item) {
+                Some(delivered) => delivered.count += count,
+                None => self.delivered.push(CraftIngredient { item: material.item.clone(), count }),
+            }
+        }
+        self.materials.retain(|material| material.count > 0);
+    }
+}
+
+/// Ивент работы над чертежом за текущий кадр (0 - Строитель | 1 - Чертёж)
+#[derive(Event)]
+pub struct BuildWork(pub Entity, pub Entity);
+
+/// Ивент отмены чертежа, доставленные материалы выпадают на землю (0 - Чертёж)
+#[derive(Event)]
+pub struct BlueprintCancel(pub Entity);
+
+/// Якорные клетки объектов при протягивании от `start` до `end`
+///
+/// Линия идёт по преобладающей оси, прямоугольник - только по периметру
+pub fn build_positions(start: IVec2, end: IVec2, step: IVec2, rectangle: bool) -> Vec<IVec2> {
+    let axis = |from: i32, to: i32, step: i32| -> Vec<i32> {
+        let count = (to - from).abs() / step.max(1);
+        let sign = if to < from { -1 } else { 1 };
+        (0..=count).map(|index| from + index * step * sign).collect()
+    };
+
+    if !rectangle {
+        let delta = end - start;
+        return match delta.x.abs() >= delta.y.abs() {
+            true  => axis(start.x, end.x, step.x).into_iter().map(|x| IVec2::new(x, start.y)).collect(),
+            false => axis(start.y, end.y, step.y).into_iter().map(|y| IVec2::new(start.x, y)).collect(),
+        };
+    }
+
+    let xs = axis(start.x, end.x, step.x);
+    let ys = axis(start.y, end.y, step.y);
+    let mut positions = Vec::new();
+
+    for (ix, x) in xs.iter().enumerate() {
+        for (iy, y) in ys.iter().enumerate() {
+            if ix == 0 || iy == 0 || ix == xs.len() - 1 || iy == ys.len() - 1 {
+                positions.push(IVec2::new(*x, *y));
+            }
+        }
+    }
+    positions
+}
+
+/// Создание чертежа на его клетках, чертёж занимает клетки, чтобы на них нельзя было поставить другой объект
+pub fn spawn_blueprint(
+    commands:   &mut Commands,
+    grid:       &mut Grid,
+    registry:   &Registry,
+    atlas:      &AtlasRes,
+    blueprint:  Blueprint,
+    footprint:  Footprint,
+) -> Option<Entity> {
+    if !grid.can_place(&footprint) {
+        return None;
+    }
+
+    let info = registry.get_object_info(&blueprint.object)?;
+    let mut sprite = registry.get_object_texture(&info.id_texture, atlas)?;
+    sprite.sprite.color = BLUEPRINT_COLOR;
+    sprite.transform = object_transform(info, &footprint, blueprint.direction);
+
+    let name = Name::new(format!("blueprint_{}", blueprint.object));
+    let entity = commands.spawn((blueprint, sprite, footprint, name)).id();
+
+    grid.place_object(entity, &footprint);
+    Some(entity)
+}
+
+/// Текущие якорные клетки призраков под курсором
+fn preview_positions(placer: &BuildPlacer, cursor: Vec2, step: IVec2, rectangle: bool) -> Vec<IVec2> {
+    let tile = WorldSystem::get_currect_chunk_tile(cursor.as_ivec2());
+    build_positions(placer.drag_start.unwrap_or(tile), tile, step, rectangle)
+}
+
+/// Управление режимом строительства
+///
+/// ЛКМ - поставить (с протягиванием линией, с Shift - прямоугольником), ПКМ - отменить протягивание или чертёж
+/// (по готовому объекту - пометить на разбор), R - повернуть, Esc - выйти из режима
+fn build_input(
+    mut commands:       Commands,
+    mut cursor_mode:    ResMut<CursorMode>,
+    mut placer:         ResMut<BuildPlacer>,
+    mut grid:           ResMut<Grid>,
+    mut cancel_event:   EventWriter<BlueprintCancel>,
+    mut post_job:       EventWriter<PostJob>,
+    mut cancel_job:     EventWriter<CancelJob>,
+    mut contexts:       EguiContexts,
+        blueprints:     Query<Entity, With<Blueprint>>,
+        objects:        Query<(), With<EntityObject>>,
+        board:          Res<JobBoard>,
+        cursor:         Res<CursorPosition>,
+        mouse_input:    Res<ButtonInput<MouseButton>>,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+        atlas:          Res<AtlasRes>,
+        registry:       Res<Registry>,
+) {
+    if *cursor_mode != CursorMode::Build {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        placer.object = None;
+        placer.drag_start = None;
+        *cursor_mode = CursorMode::None;
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        placer.rotate();
+    }
+
+    // Нажатия по окнам интерфейса не ставят чертежи
+    if contexts.ctx_mut().is_pointer_over_area() && placer.drag_start.is_none() {
+        return;
+    }
+
+    if mouse_input.just_pressed(MouseButton::Right) {
+        if placer.drag_start.take().is_none() {
+            // Отмена поставленного чертежа под курсором, у готового объекта - пометка на разбор или её снятие
+            match grid.object_at(&cursor.0.as_ivec2()) {
+                Some(blueprint) if blueprints.contains(blueprint) => {
+                    cancel_event.send(BlueprintCancel(blueprint));
+                },
+                Some(object) if objects.contains(object) => {
+                    if board.find(JobKind::Deconstruct, object).is_some() {
+                        cancel_job.send(CancelJob(JobKind::Deconstruct, object));
+                    } else {
+                        post_job.send(PostJob(JobKind::Deconstruct, object, None));
+                    }
+                },
+                _ => {},
+            }
+        }
+        return;
+    }
+
+    let Some(object) = placer.object.clone() else {
+        return;
+    };
+    let Some(info) = registry.get_object_info(&object) else {
+        return;
+    };
+    let Some(recipe) = registry.get_construction_recipe(&object) else {
+        warn!("Build - у объекта {} нет рецепта постройки", object);
+        placer.object = None;
+        return;
+    };
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        placer.drag_start = Some(WorldSystem::get_currect_chunk_tile(cursor.0.as_ivec2()));
+    }
+
+    if !mouse_input.just_released(MouseButton::Left) || placer.drag_start.is_none() {
+        return;
+    }
+
+    let step = Footprint::new(IVec2::ZERO, info.footprint(), placer.direction).1;
+    let rectangle = keyboard_input.pressed(KeyCode::ShiftLeft);
+
+    for anchor in preview_positions(&placer, cursor.0, step, rectangle) {
+        let footprint = Footprint::new(anchor, info.footprint(), placer.direction);
+        let work = recipe.craft_time.max(BUILD_MIN_WORK);
+        spawn_blueprint(&mut commands, &mut grid, &registry, &atlas, Blueprint {
+            object:     object.clone(),
+            direction:  placer.direction,
+            materials:  recipe.request.clone(),
+            delivered:  Vec::new(),
+            work,
+            total:      work,
+            xp:         recipe.xp,
+        }, footprint);
+    }
+
+    placer.drag_start = None;
+}
+
+/// Пересоздание призраков при смене объекта, поворота или клеток и их окраска по возможности постройки
+fn update_build_ghosts(
+    mut commands:   Commands,
+    mut ghosts:         Query<(Entity, &Footprint, &mut Sprite), With<BuildGhost>>,
+    mut last:           Local<Option<(String, ObjectDirectionState, Vec<IVec2>)>>,
+        cursor_mode:    Res<CursorMode>,
+        placer:         Res<BuildPlacer>,
+        cursor:         Res<CursorPosition>,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+        grid:           Res<Grid>,
+        atlas:          Res<AtlasRes>,
+        registry:       Res<Registry>,
+) {
+    let info = placer.object.as_ref()
+        .filter(|_| *cursor_mode == CursorMode::Build)
+        .and_then(|object| registry.get_object_info(object));
+
+    let current = info.map(|info| {
+        let step = Footprint::new(IVec2::ZERO, info.footprint(), placer.direction).1;
+        let positions = preview_positions(&placer, cursor.0, step, keyboard_input.pressed(KeyCode::ShiftLeft));
+        (info.id_name.clone(), placer.direction, positions)
+    });
+
+    if *last != current {
+        for (ghost, _, _) in &ghosts {
+            commands.entity(ghost).despawn();
+        }
+
+        if let (Some(info), Some((_, direction, positions))) = (info, &current) {
+            for anchor in positions {
+                let footprint = Footprint::new(*anchor, info.footprint(), *direction);
+                let Some(mut sprite) = registry.get_object_texture(&info.id_texture, &atlas) else {
+                    break;
+                };
+                sprite.sprite.color = if grid.can_place(&footprint) { GHOST_VALID_COLOR } else { GHOST_INVALID_COLOR };
+                sprite.transform = object_transform(info, &footprint, *direction);
+                sprite.transform.translation.z = 0.9;
+
+                commands.spawn((BuildGhost, sprite, footprint, Name::new("build_ghost")));
+            }
+        }
+
+        *last = current;
+        return;
+    }
+
+    for (_, footprint, mut sprite) in &mut ghosts {
+        let color = if grid.can_place(footprint) { GHOST_VALID_COLOR } else { GHOST_INVALID_COLOR };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+/// Игрок строит ближайший чертёж, пока удерживает C
+fn player_build(
+        user:           Query<(Entity, &EntityBase, &Transform), With<UserControl>>,
+        blueprints:     Query<(Entity, &Footprint), With<Blueprint>>,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut work_event:     EventWriter<BuildWork>,
+) {
+    if !keyboard_input.pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    let Ok((player, base, transform)) = user.get_single() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+
+    let nearest = blueprints.iter()
+        .map(|(entity, footprint)| (entity, footprint.center().distance(position)))
+        .filter(|(_, distance)| *distance <= base.interaction_radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((blueprint, _)) = nearest {
+        work_event.send(BuildWork(player, blueprint));
+    }
+}
+
+/// Доставка материалов и работа над чертежом, готовый чертёж заменяется объектом
+///
+/// Объект ставится сразу на освобождённые клетки, если поставить его не удалось - материалы возвращаются
+fn apply_build_work(
+    mut commands:   Commands,
+    mut blueprints: Query<(&mut Blueprint, &Footprint)>,
+    mut builders:   Query<(&EntityBase, &Transform, Option<&mut Inventory>)>,
+    mut event:      EventReader<BuildWork>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+    mut xp_event:   EventWriter<ExperienceGain>,
+    mut grid:       ResMut<Grid>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        clock:      Res<WorldInfo>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((mut blueprint, footprint)) = blueprints.get_mut(event.1) else {
+            continue;
+        };
+        let Ok((base, transform, inventory)) = builders.get_mut(event.0) else {
+            continue;
+        };
+
+        // Чертёж уже достроен в этом кадре
+        if blueprint.work <= 0.0 {
+            continue;
+        }
+        if footprint.center().distance(transform.translation.truncate()) > base.interaction_radius {
+            continue;
+        }
+
+        if !blueprint.supplied() {
+            if let Some(mut inventory) = inventory {
+                blueprint.deliver(&mut inventory);
+            }
+            if !blueprint.supplied() {
+                continue;
+            }
+        }
+
+        blueprint.work -= clock.delta();
+        if blueprint.work > 0.0 {
+            continue;
+        }
+
+        grid.remove_object(event.1, footprint);
+        commands.entity(event.1).despawn_recursive();
+
+        if place_object(&mut commands, &registry, &atlas, &mut grid, &blueprint.object, footprint.0 * 16, blueprint.direction).is_none() {
+            warn!("Build - {} не удалось поставить, материалы возвращены", blueprint.object);
+            refund_materials(&blueprint, footprint, &mut spawn_i);
+            continue;
+        }
+
+        if blueprint.xp > 0 {
+            xp_event.send(ExperienceGain(event.0, blueprint.xp, XpSource::Build));
+        }
+    }
+}
+
+/// Доставленные на чертёж материалы выкладываются у его центра
+fn refund_materials(blueprint: &Blueprint, footprint: &Footprint, spawn_i: &mut EventWriter<ItemSpawn>) {
+    let origin = WorldSystem::get_currect_chunk_subtile(footprint.center().as_ivec2());
+    for (index, material) in blueprint.delivered.iter().enumerate() {
+        spawn_i.send(ItemSpawn(material.item.clone(), origin + IVec2::new(index as i32 % 3 - 1, 0), material.count, None));
+    }
+}
+
+/// Отмена чертежа с возвратом доставленных материалов
+fn cancel_blueprint(
+    mut commands:   Commands,
+    mut event:      EventReader<BlueprintCancel>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+    mut grid:       ResMut<Grid>,
+        blueprints: Query<(&Blueprint, &Footprint)>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((blueprint, footprint)) = blueprints.get(event.0) else {
+            continue;
+        };
+
+        refund_materials(blueprint, footprint, &mut spawn_i);
+
+        grid.remove_object(event.0, footprint);
+        commands.entity(event.0).despawn_recursive();
+    }
+}
@@ -11,21 +11,45 @@ use std::{
 use bevy_inspector_egui::prelude::ReflectInspectorOptions;
 use bevy_inspector_egui::InspectorOptions;
 
+use serde::{
+    Deserialize,
+    Serialize
+};
+
 use crate::core::{
     interface::game_ui::{
             Info::{
                 cursor_grab,
                 hover_item
             }, Inventory::{
-                inventory_click_item, inventory_update, toggle_inventory_open, toggle_inventory_open_event_send, InventoryDisplayToggleEvent
+                inventory_click_item, inventory_update, toggle_inventory_open, toggle_inventory_open_event_send, close_distant_storage, InventoryDisplayToggleEvent, FocusedStorage
             }
         },
     AppState,
-    Item::ItemEntity,
+    Item::{
+        ItemEntity,
+        ItemInstance,
+        ItemSpawn
+    },
+    world::World::WorldSystem,
+    Entity::EntityBase,
     ItemType::{
         ItemStackType,
         ItemType
-    }
+    },
+    resource::{
+        SpriteLayer,
+        Registry::{
+            Registry,
+            ContainerRegistry
+        },
+        graphic::Atlas::{
+            AtlasRes,
+            AtlasType
+        }
+    },
+    Attributes::ItemAttributes,
+    Weapon::Gun,
 };
 
 pub struct ContainerPlugin<I: ItemTypeEx> {
@@ -39,8 +63,12 @@ impl<I: ItemTypeEx> Plugin for ContainerPlugin<I> {
             .register_type::<Inventory>()
             // Init Resource
             .init_resource::<CursorContainer>()
+            .init_resource::<FocusedStorage>()
             // Reg Events
             .add_event::<InventoryDisplayToggleEvent>()
+            .add_event::<EquipItem>()
+            .add_event::<UnequipItem>()
+            .add_event::<WearItem>()
             // .add_event::<ItemPickUpEvent>()
             // .add_event::<ItemDropEvent>()
             // Systems
@@ -60,6 +88,20 @@ impl<I: ItemTypeEx> Plugin for ContainerPlugin<I> {
                     inventory_update::<I>.after(toggle_inventory_open::<I>)
                 ).run_if(in_state(AppState::Game))
             )
+            // Экипировка
+            .add_systems(Update,
+                (
+                    equip_item,
+                    unequip_item,
+                    wear_equipment,
+                    update_equipment_attributes,
+                    update_equipment_visual,
+                    update_carry_capacity,
+                    update_encumbrance
+                ).chain().run_if(in_state(AppState::Game))
+            )
+            // Хранилища
+            .add_systems(Update, close_distant_storage.run_if(in_state(AppState::Game)))
         ;
     }
 }
@@ -84,13 +126,481 @@ pub struct ItemStack {
 // Equipment
 // ==============================
 
-#[allow(unused)]
-#[derive(Debug, Default, Clone, Component)]
+/// Слот экипировки, допустимые слоты предмета задаются в его определении (`equip.slots`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Helmet,
+    Glasses,
+    Mask,
+    Chest,
+    Shirt,
+    Back,
+    Gloves,
+    ArmBand,
+    LeftHand,
+    RightHand,
+    Boots
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 11] = [
+        EquipSlot::Helmet, EquipSlot::Glasses, EquipSlot::Mask,
+        EquipSlot::Chest, EquipSlot::Shirt, EquipSlot::Back,
+        EquipSlot::Gloves, EquipSlot::ArmBand, EquipSlot::LeftHand, EquipSlot::RightHand,
+        EquipSlot::Boots
+    ];
+
+    /// Смещение спрайта надетого предмета относительно сущности
+    pub fn visual_offset(&self) -> Vec2 {
+        match self {
+            EquipSlot::Helmet | EquipSlot::Glasses | EquipSlot::Mask => Vec2::new(0.0, 0.0),
+            EquipSlot::Chest | EquipSlot::Shirt | EquipSlot::Back => Vec2::new(0.0, -1.0),
+            EquipSlot::Gloves | EquipSlot::ArmBand => Vec2::new(0.0, -2.0),
+            EquipSlot::LeftHand => Vec2::new(-4.0, -2.0),
+            EquipSlot::RightHand => Vec2::new(4.0, -2.0),
+            EquipSlot::Boots => Vec2::new(0.0, -5.0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Component, Serialize, Deserialize)]
 pub struct Equipment {
-    pub head:   [Option<Entity>; 3], // Шлем | Очки | Маска
-    pub body:   [Option<Entity>; 3], // Нагрудник | Майка | Штаны | Пояс | Рюкзак | Наплечная сумка    
-    pub hands:  [Option<Entity>; 4], // Hands | ArmBand | Weapon left | Weapon right (Если оружие двуручное, то оно технически занимает два слота)
-    pub legs:    Option<Entity>       // Ботинки   
+    pub head:   [Option<Slot>; 3], // Шлем | Очки | Маска
+    pub body:   [Option<Slot>; 3], // Нагрудник или Майка | Рюкзак или Наплечная сумка
+    pub hands:  [Option<Slot>; 4], // Hands | ArmBand | Weapon left | Weapon right (Если оружие двуручное, то оно технически занимает два слота)
+    pub legs:    Option<Slot>,      // Ботинки
+    pub two_handed: bool,           // Предмет в правой руке занимает и левую
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipSlot) -> &Option<Slot> {
+        match slot {
+            EquipSlot::Helmet       => &self.head[0],
+            EquipSlot::Glasses      => &self.head[1],
+            EquipSlot::Mask         => &self.head[2],
+            EquipSlot::Chest        => &self.body[0],
+            EquipSlot::Shirt        => &self.body[1],
+            EquipSlot::Back         => &self.body[2],
+            EquipSlot::Gloves       => &self.hands[0],
+            EquipSlot::ArmBand      => &self.hands[1],
+            EquipSlot::LeftHand     => &self.hands[2],
+            EquipSlot::RightHand    => &self.hands[3],
+            EquipSlot::Boots        => &self.legs,
+        }
+    }
+
+    pub fn get_mut(&mut self, slot: EquipSlot) -> &mut Option<Slot> {
+        match slot {
+            EquipSlot::Helmet       => &mut self.head[0],
+            EquipSlot::Glasses      => &mut self.head[1],
+            EquipSlot::Mask         => &mut self.head[2],
+            EquipSlot::Chest        => &mut self.body[0],
+            EquipSlot::Shirt        => &mut self.body[1],
+            EquipSlot::Back         => &mut self.body[2],
+            EquipSlot::Gloves       => &mut self.hands[0],
+            EquipSlot::ArmBand      => &mut self.hands[1],
+            EquipSlot::LeftHand     => &mut self.hands[2],
+            EquipSlot::RightHand    => &mut self.hands[3],
+            EquipSlot::Boots        => &mut self.legs,
+        }
+    }
+
+    /// Свободен ли слот (левая рука занята, если в правой двуручный предмет)
+    pub fn is_free(&self, slot: EquipSlot) -> bool {
+        if slot == EquipSlot::LeftHand && self.two_handed {
+            return false;
+        }
+        self.get(slot).is_none()
+    }
+
+    /// Все надетые предметы
+    pub fn iter_some(&self) -> impl Iterator<Item = (EquipSlot, &Slot)> + '_ {
+        EquipSlot::ALL.into_iter().filter_map(|slot| self.get(slot).as_ref().map(|item| (slot, item)))
+    }
+
+    /// Надеть предмет в слот, возвращает снятые предметы, которые занимали нужные слоты
+    pub fn equip(&mut self, slot: EquipSlot, item: Slot, two_handed: bool) -> Vec<Slot> {
+        let mut displaced: Vec<Slot> = Vec::new();
+
+        if two_handed {
+            displaced.extend(self.unequip(EquipSlot::LeftHand));
+            displaced.extend(self.unequip(EquipSlot::RightHand));
+            self.two_handed = true;
+            *self.get_mut(EquipSlot::RightHand) = Some(item);
+        } else {
+            // Предмет в руку снимает двуручное оружие
+            if slot == EquipSlot::LeftHand && self.two_handed {
+                displaced.extend(self.unequip(EquipSlot::RightHand));
+            }
+            displaced.extend(self.unequip(slot));
+            *self.get_mut(slot) = Some(item);
+        }
+
+        displaced
+    }
+
+    /// Снять предмет со слота
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<Slot> {
+        if slot == EquipSlot::RightHand {
+            self.two_handed = false;
+        }
+        if slot == EquipSlot::LeftHand && self.two_handed {
+            return self.unequip(EquipSlot::RightHand);
+        }
+        self.get_mut(slot).take()
+    }
+}
+
+/// Суммарные атрибуты, получаемые от надетых предметов
+#[derive(Component, Clone, Debug, Default)]
+pub struct EquipmentAttributes(pub ItemAttributes);
+
+/// Спрайт надетого предмета, дочерний элемент сущности
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EquipmentVisual(pub EquipSlot);
+
+/// Ивент экипировки предмета из инвентаря (0 - Сущность | 1 - Индекс слота инвентаря | 2 - Желаемый слот экипировки)
+#[derive(Event)]
+pub struct EquipItem(pub Entity, pub usize, pub Option<EquipSlot>);
+
+/// Ивент снятия предмета в инвентарь (0 - Сущность | 1 - Слот экипировки)
+#[derive(Event)]
+pub struct UnequipItem(pub Entity, pub EquipSlot);
+
+fn equip_item(
+    mut entities:   Query<(&mut Inventory, &mut Equipment)>,
+    mut event:      EventReader<EquipItem>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((mut inventory, mut equipment)) = entities.get_mut(event.0) else {
+            continue;
+        };
+
+        let Some(Some(item)) = inventory.get_slot(event.1).cloned() else {
+            continue;
+        };
+
+        let Some(equip) = registry.get_item_info(&item.id_name).and_then(|info| info.equip.as_ref()) else {
+            info!("Equip - Предмет {} нельзя надеть", item.id_name);
+            continue;
+        };
+
+        // Выбор слота: указанный, первый свободный из допустимых, либо первый допустимый (замена)
+        let target = if equip.two_handed {
+            Some(EquipSlot::RightHand)
+        } else {
+            match event.2 {
+                Some(slot) => equip.slots.contains(&slot).then_some(slot),
+                None => equip.slots.iter().copied()
+                    .find(|slot| equipment.is_free(*slot))
+                    .or(equip.slots.first().copied()),
+            }
+        };
+
+        let Some(target) = target else {
+            info!("Equip - Предмет {} не подходит для слота {:?}", item.id_name, event.2);
+            continue;
+        };
+
+        // Проверка места под снимаемые предметы до изменения инвентаря
+        let mut preview = equipment.clone();
+        let mut preview_inv = inventory.clone();
+        preview_inv.add_to_slot(event.1, None);
+        let displaced = preview.equip(target, item, equip.two_handed);
+        if displaced.into_iter().any(|slot| preview_inv.insert_slot(slot).is_err()) {
+            info!("Equip - Нет места в инвентаре под снимаемые предметы");
+            continue;
+        }
+
+        *equipment = preview;
+        *inventory = preview_inv;
+    }
+}
+
+fn unequip_item(
+    mut entities:   Query<(&mut Inventory, &mut Equipment)>,
+    mut event:      EventReader<UnequipItem>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((mut inventory, mut equipment)) = entities.get_mut(event.0) else {
+            continue;
+        };
+
+        // Снятие проверяется на копиях: при отказе инвентаря (место, масса, объём) экипировка не меняется
+        let mut preview = equipment.clone();
+        let mut preview_inv = inventory.clone();
+        let Some(item) = preview.unequip(event.1) else {
+            continue;
+        };
+        if let Err(item) = preview_inv.insert_slot(item) {
+            info!("Unequip - Нет места в инвентаре под {}", item.display_name());
+            continue;
+        }
+
+        *equipment = preview;
+        *inventory = preview_inv;
+    }
+}
+
+/// Ивент износа надетого предмета (0 - Сущность | 1 - Слот экипировки | 2 - Величина износа)
+#[derive(Event)]
+pub struct WearItem(pub Entity, pub EquipSlot, pub usize);
+
+/// Износ надетых предметов, сломанный предмет снимается и уничтожается.
+///
+/// Снятие меняет `Equipment`, поэтому спрайт и атрибуты предмета убираются в `update_equipment_visual` и `update_equipment_attributes`
+fn wear_equipment(
+    mut entities:   Query<&mut Equipment>,
+    mut event:      EventReader<WearItem>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok(mut equipment) = entities.get_mut(event.0) else {
+            continue;
+        };
+
+        // Износ не должен перестраивать спрайты и атрибуты, пока предмет цел
+        let Some(item) = equipment.bypass_change_detection().get_mut(event.1) else {
+            continue;
+        };
+
+        if item.instance.wear(event.2) {
+            if let Some(item) = equipment.unequip(event.1) {
+                info!("Item - {} сломался", item.display_name());
+            }
+        }
+    }
+}
+
+/// Пересчёт атрибутов от надетых предметов
+fn update_equipment_attributes(
+    mut commands:   Commands,
+        entities:   Query<(Entity, &Equipment), Changed<Equipment>>,
+        registry:   Res<Registry>,
+) {
+    for (entity, equipment) in &entities {
+        let attributes = equipment.iter_some()
+            .filter_map(|(_, item)| registry.get_item_info(&item.id_name))
+            .filter_map(|info| info.equip.as_ref())
+            .fold(ItemAttributes::default(), |acc, equip| acc.combine(&equip.attributes));
+
+        commands.entity(entity).insert(EquipmentAttributes(attributes));
+    }
+}
+
+/// Перестроение спрайтов надетых предметов при изменении экипировки
+fn update_equipment_visual(
+    mut commands:   Commands,
+        entities:   Query<(Entity, &Equipment, Option<&Children>), Changed<Equipment>>,
+        visuals:    Query<Entity, With<EquipmentVisual>>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+) {
+    for (entity, equipment, children) in &entities {
+        if let Some(children) = children {
+            for child in children.iter().filter(|child| visuals.contains(**child)) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        for (slot, item) in equipment.iter_some() {
+            let Some(info) = registry.get_item_info(&item.id_name) else {
+                continue;
+            };
+
+            let atlas_type = match info.item_type {
+                ItemType::Weapon(_) => AtlasType::Weapon,
+                ItemType::Tool(_)   => AtlasType::Tools,
+                _                   => AtlasType::Items,
+            };
+            let texture = info.equip.as_ref()
+                .and_then(|equip| equip.id_texture.clone())
+                .unwrap_or(info.id_texture.clone());
+
+            if let Some(sprite) = registry.get_item_texture(&texture, &atlas, atlas_type) {
+                commands.entity(entity).with_children(|parent| {
+                    let mut visual = parent.spawn((
+                        SpriteSheetBundle {
+                            texture: sprite.texture,
+                            atlas: sprite.atlas,
+                            transform: Transform {
+                                translation: slot.visual_offset().extend(0.1),
+                                scale: Vec3::splat(0.5),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        SpriteLayer::EntityPart,
+                        EquipmentVisual(slot),
+                        Name::new(format!("Equipment {:?}", slot)),
+                    ));
+
+                    // Огнестрельное оружие в руке управляется через gun_controls
+                    if let (EquipSlot::RightHand, Some(var)) = (slot, info.range_info) {
+                        visual.insert(Gun {
+                            shoot_cooldown:     var.0,
+                            bullet_lifetime:    var.1,
+                            shoot_timer:        var.2
+                        });
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Базовая вместимость инвентаря без учёта надетых предметов
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CarryCapacity {
+    pub slots:      usize,
+    pub max_mass:   Option<f32>,
+    pub max_volume: Option<f32>,
+}
+
+impl Default for CarryCapacity {
+    fn default() -> Self {
+        Self {
+            slots:      Inventory::DEFAULT_CAPACITY,
+            max_mass:   Some(40.0),
+            max_volume: Some(24.0),
+        }
+    }
+}
+
+/// Пересчёт вместимости инвентаря от надетых хранилищ (рюкзак, сумка).
+///
+/// Предметы, не поместившиеся после уменьшения вместимости, выбрасываются под сущность.
+fn update_carry_capacity(
+    mut entities:   Query<(&Transform, &Equipment, &mut Inventory, Option<&CarryCapacity>), Changed<Equipment>>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+        registry:   Res<Registry>,
+) {
+    for (transform, equipment, mut inventory, base) in &mut entities {
+        let base = base.copied().unwrap_or_default();
+        let containers: Vec<&ContainerRegistry> = equipment.iter_some()
+            .filter_map(|(_, item)| registry.get_item_info(&item.id_name))
+            .filter_map(|info| info.container.as_ref())
+            .collect();
+
+        let capacity = base.slots + containers.iter().map(|container| container.capacity).sum::<usize>();
+        let max_volume = base.max_volume
+            .map(|volume| volume + containers.iter().filter_map(|container| container.volume).sum::<f32>());
+
+        inventory.max_mass = base.max_mass;
+        inventory.max_volume = max_volume;
+
+        let mut overflow = if capacity != inventory.len() {
+            inventory.resize(capacity)
+        } else {
+            Vec::new()
+        };
+
+        // После снятия рюкзака содержимое может не пройти по объёму
+        while inventory.max_volume.is_some_and(|max| inventory.total_volume() > max + f32::EPSILON) {
+            let Some(index) = (0..inventory.len()).rev().find(|index| inventory[*index].is_some()) else {
+                break;
+            };
+            if let Some(slot) = inventory.get_slot_mut(index).and_then(|slot| slot.take()) {
+                overflow.push(slot);
+            }
+        }
+
+        drop_slots(overflow, transform.translation.truncate().as_ivec2(), &mut spawn_i);
+    }
+}
+
+/// Состояние перегруза от массы содержимого инвентаря
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum EncumbranceState {
+    #[default]
+    Normal,
+    Burdened,
+    Overloaded,
+}
+
+impl EncumbranceState {
+    /// Состояние по доле заполнения предельной массы
+    pub fn from_load(load: f32) -> Self {
+        if load > 0.85 {
+            EncumbranceState::Overloaded
+        } else if load > 0.5 {
+            EncumbranceState::Burdened
+        } else {
+            EncumbranceState::Normal
+        }
+    }
+
+    /// Множитель скорости передвижения
+    pub fn speed_multiplier(&self) -> f32 {
+        match self {
+            EncumbranceState::Normal        => 1.0,
+            EncumbranceState::Burdened      => 0.75,
+            EncumbranceState::Overloaded    => 0.5,
+        }
+    }
+}
+
+/// Перегруз сущности, `base_speed` - скорость без учёта перегруза
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+pub struct Encumbrance {
+    pub state:      EncumbranceState,
+    pub base_speed: (f32, f32, f32),
+}
+
+/// Пересчёт перегруза и скорости сущности при изменении инвентаря
+fn update_encumbrance(
+    mut commands:   Commands,
+    mut entities:   Query<(Entity, &Inventory, &mut EntityBase, Option<&mut Encumbrance>), Changed<Inventory>>,
+) {
+    for (entity, inventory, mut entity_base, encumbrance) in &mut entities {
+        let load = inventory.max_mass.map_or(0.0, |max| if max > 0.0 { inventory.total_mass() / max } else { 1.0 });
+        let state = EncumbranceState::from_load(load);
+
+        let base_speed = match encumbrance {
+            Some(mut encumbrance) => {
+                if encumbrance.state == state {
+                    continue;
+                }
+                encumbrance.state = state;
+                encumbrance.base_speed
+            },
+            None => {
+                let base_speed = (entity_base.speed.0, entity_base.speed.1, entity_base.speed.2);
+                commands.entity(entity).insert(Encumbrance { state, base_speed });
+                base_speed
+            }
+        };
+
+        let multiplier = state.speed_multiplier();
+        entity_base.speed.0 = base_speed.0 * multiplier;
+        entity_base.speed.1 = base_speed.1 * multiplier;
+        entity_base.speed.2 = base_speed.2 * multiplier;
+    }
+}
+
+/// Выброс предметов на землю вокруг точки (по соседним под-тайлам, чтобы предметы не накладывались)
+pub fn drop_slots(slots: Vec<Slot>, position: IVec2, spawn_i: &mut EventWriter<ItemSpawn>) {
+    for (index, slot) in slots.into_iter().enumerate() {
+        spawn_i.send(ItemSpawn(slot.id_name, drop_subtile(position, index), slot.count, Some(slot.instance)));
+    }
+}
+
+/// Под-тайл для `index`-го выброшенного вокруг точки предмета
+pub fn drop_subtile(position: IVec2, index: usize) -> IVec2 {
+    let origin = WorldSystem::get_currect_chunk_subtile(position);
+    origin + IVec2::new(index as i32 % 3 - 1, index as i32 / 3 % 3 - 1)
 }
 
 // ==============================
@@ -108,41 +618,30 @@ pub struct CursorContainer {
 
 pub trait ItemTypeEx: Component + Copy + Clone + Eq + Hash + Debug + Default {}
 
-#[derive(Debug, Clone, Component, InspectorOptions, Reflect)]
+#[derive(Debug, Clone, Component, InspectorOptions, Reflect, Serialize, Deserialize)]
 #[reflect(Component, InspectorOptions)]
 pub struct Inventory {
     items: Vec<Option<Slot>>,
+    pub max_mass:   Option<f32>,    // Предельная масса содержимого (None - без ограничения)
+    pub max_volume: Option<f32>,    // Предельный объём содержимого (None - без ограничения)
 }
 
-#[derive(Debug, Clone, Component, InspectorOptions, Reflect)]
+#[derive(Debug, Clone, Component, InspectorOptions, Reflect, Serialize, Deserialize)]
 #[reflect(Component, InspectorOptions)]
 pub struct Slot {
     pub name:       String,
     pub id_name:    String,
     pub id_source:  Option<String>,
     pub item_type:  ItemType,
-    pub durability: Option<usize>,
+    pub instance:   ItemInstance,
     pub stack_size: Option<usize>,
     pub stackable:  Option<ItemStackType>,
+    pub mass:       f32,    // Масса единицы
+    pub volume:     f32,    // Объём единицы
     pub count:      usize,
 }
 
 impl Slot {
-    fn add_to_exist(&mut self, count: usize) -> usize {
-        if self.check_stackable() {
-            if let Some(stack_size) = self.stack_size {
-                let remaining = (self.count + count).saturating_sub(stack_size);
-                self.count = stack_size;
-                remaining
-            } else {
-                self.count += count;
-                0
-            }
-        } else {
-            0
-        }
-    }
-
     pub fn check_stackable(&self) -> bool {
         if let Some(stack) = self.stackable {
             return stack.is_stackable()
@@ -153,6 +652,91 @@ impl Slot {
     pub fn is_full(&self) -> bool {
         self.stack_size.map_or(true, |stack_size| self.count == stack_size)
     }
+
+    /// Можно ли объединить стаки (тот же предмет с тем же состоянием экземпляра)
+    pub fn same_item(&self, other: &Slot) -> bool {
+        self.id_name == other.id_name
+            && self.item_type == other.item_type
+            && self.instance == other.instance
+    }
+
+    /// Отображаемое имя (с учётом имени, данного игроком)
+    pub fn display_name(&self) -> &str {
+        self.instance.display_name(&self.name)
+    }
+
+    /// Сколько ещё предметов помещается в стак
+    pub fn space_left(&self) -> usize {
+        if !self.check_stackable() {
+            return 0;
+        }
+        self.stack_size.map_or(usize::MAX - self.count, |stack_size| stack_size.saturating_sub(self.count))
+    }
+}
+
+/// Ошибка операции с инвентарём, при ошибке инвентарь остаётся без изменений
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryError {
+    /// Индекс слота за пределами инвентаря
+    OutOfRange(usize),
+    /// В слоте нет предмета
+    EmptySlot(usize),
+    /// Предмет не складывается в стаки
+    NotStackable,
+    /// Разные предметы нельзя объединить
+    Mismatch,
+    /// Нет свободного места
+    NoSpace,
+    /// Недопустимое кол-во для операции
+    InvalidAmount,
+    /// Недостаточно предметов
+    NotEnough,
+}
+
+impl std::fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryError::OutOfRange(index)   => write!(f, "slot {} is out of range", index),
+            InventoryError::EmptySlot(index)    => write!(f, "slot {} is empty", index),
+            InventoryError::NotStackable        => write!(f, "item is not stackable"),
+            InventoryError::Mismatch            => write!(f, "items are different"),
+            InventoryError::NoSpace             => write!(f, "no space left"),
+            InventoryError::InvalidAmount       => write!(f, "invalid amount"),
+            InventoryError::NotEnough           => write!(f, "not enough items"),
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {}
+
+/// Причина, по которой предмет не был добавлен в инвентарь (полностью или частично)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddRejection {
+    /// Нет свободных слотов
+    NoSlot,
+    /// Превышена предельная масса
+    TooHeavy,
+    /// Превышен предельный объём
+    TooBulky,
+}
+
+impl std::fmt::Display for AddRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddRejection::NoSlot    => write!(f, "no free slot"),
+            AddRejection::TooHeavy  => write!(f, "too heavy"),
+            AddRejection::TooBulky  => write!(f, "too bulky"),
+        }
+    }
+}
+
+impl std::error::Error for AddRejection {}
+
+/// Порядок сортировки инвентаря
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    ByType,
+    ByName,
 }
 
 impl Default for Inventory {
@@ -167,73 +751,131 @@ impl Inventory {
     // Установка размера инвентаря по умолчанию
     pub fn with_capacity(cap: usize) -> Self {
         Self {
-            items: vec![None; cap],
+            items:      vec![None; cap],
+            max_mass:   None,
+            max_volume: None,
         }
     }
 
-    /// Добавление в первый попавшийся свободный слот
-    pub fn add(&mut self, item: &mut ItemEntity) -> bool {
-        if let Some(slot) = self.items.iter_mut().find(|slot| {
-            if let Some(slot) = slot {
-                slot.id_name == item.id_name
-                    && slot.item_type == item.item_type
-                    && slot.check_stackable()
-                    && !slot.is_full()
-            } else {
-                false
+    /// Установка ограничений по массе и объёму
+    pub fn with_limits(mut self, max_mass: Option<f32>, max_volume: Option<f32>) -> Self {
+        self.max_mass = max_mass;
+        self.max_volume = max_volume;
+        self
+    }
+
+    /// Общая масса содержимого
+    pub fn total_mass(&self) -> f32 {
+        self.iter_some().map(|slot| slot.mass * slot.count as f32).sum()
+    }
+
+    /// Общий объём содержимого
+    pub fn total_volume(&self) -> f32 {
+        self.iter_some().map(|slot| slot.volume * slot.count as f32).sum()
+    }
+
+    /// Сколько единиц предмета с данной массой и объёмом помещается по ограничениям,
+    /// и какое ограничение при этом сработало
+    pub fn units_fit(&self, mass: f32, volume: f32) -> (usize, Option<AddRejection>) {
+        let mut fits = usize::MAX;
+        let mut limit = None;
+
+        if let (Some(max_mass), true) = (self.max_mass, mass > 0.0) {
+            let by_mass = ((max_mass - self.total_mass()) / mass + f32::EPSILON).floor().max(0.0) as usize;
+            if by_mass < fits {
+                fits = by_mass;
+                limit = Some(AddRejection::TooHeavy);
             }
-        }) {
-            if let Some(slot) = slot {
-                let remaining: usize = slot.add_to_exist(item.count);
-                
-                item.count = remaining;
-
-                if remaining > 0 {
-                    if let Some(slot) = self.items.iter_mut().find(|slot| {
-                        if let Some(slot) = slot {
-                            slot.id_name == item.id_name 
-                                && slot.item_type == item.item_type
-                                && slot.check_stackable()
-                                && !slot.is_full()
-                        } else {
-                            false
-                        }
-                    }) {
-                        if let Some(slot) = slot {
-                            let remaining: usize = slot.add_to_exist(remaining);
-                            
-                            item.count = remaining;
-                        }
-                    }
-                }
+        }
+
+        if let (Some(max_volume), true) = (self.max_volume, volume > 0.0) {
+            let by_volume = ((max_volume - self.total_volume()) / volume + f32::EPSILON).floor().max(0.0) as usize;
+            if by_volume < fits {
+                fits = by_volume;
+                limit = Some(AddRejection::TooBulky);
             }
-        } else {
-            if self.add_to_empty_slot(item) {
-                item.count = 0;
+        }
 
-                return true;
+        (fits, limit)
+    }
+
+    /// Добавление предмета: сначала в неполные стаки, затем в свободные слоты.
+    ///
+    /// Не поместившаяся часть остаётся в `item.count`, причина возвращается в ошибке.
+    pub fn add(&mut self, item: &mut ItemEntity) -> Result<(), AddRejection> {
+        if item.count == 0 {
+            return Ok(());
+        }
+
+        let (fits, limit) = self.units_fit(item.mass, item.volume);
+        let mut to_add = item.count.min(fits);
+        let limited = item.count - to_add;
+
+        // Дополнение неполных стаков
+        for slot in self.items.iter_mut().flatten() {
+            if to_add == 0 {
+                break;
+            }
+            if slot.id_name == item.id_name
+                && slot.item_type == item.item_type
+                && slot.instance == item.instance
+            {
+                let moved = to_add.min(slot.space_left());
+                slot.count += moved;
+                to_add -= moved;
             }
         }
-        item.count == 0
+
+        // Свободные слоты
+        while to_add > 0 {
+            let count = if item.check_stackable() {
+                to_add.min(item.stack_size.unwrap_or(to_add))
+            } else {
+                to_add
+            };
+
+            if !self.add_to_empty_slot(item, count) {
+                break;
+            }
+            to_add -= count;
+        }
+
+        item.count = to_add + limited;
+        self.debug_validate();
+
+        if item.count == 0 {
+            Ok(())
+        } else if to_add > 0 {
+            Err(AddRejection::NoSlot)
+        } else {
+            Err(limit.unwrap_or(AddRejection::NoSlot))
+        }
     }
 
-    pub fn add_ex(&mut self, item: ItemEntity) -> bool {
-        // Если слот с таким именем не найден, ищем свободный слот
-        self.add_to_empty_slot(&item)
+    /// Добавление предмета целиком (без частичного поднятия)
+    pub fn add_ex(&mut self, item: ItemEntity) -> Result<(), AddRejection> {
+        let mut preview = self.clone();
+        let mut item = item;
+
+        preview.add(&mut item)?;
+        *self = preview;
+        Ok(())
     }
 
-    /// Добавление в свободный слот
-    fn add_to_empty_slot(&mut self, item: &ItemEntity) -> bool {
+    /// Добавление `count` единиц предмета в свободный слот
+    fn add_to_empty_slot(&mut self, item: &ItemEntity, count: usize) -> bool {
         if let Some(slot) = self.items.iter_mut().find(|slot| slot.is_none()) {
             *slot = Some(Slot {
                 name:       item.name.clone(),
                 id_name:    item.id_name.clone(),
                 id_source:  item.id_source.clone(),
                 item_type:  item.item_type.clone(),
-                durability: item.durability,
+                instance:   item.instance.clone(),
                 stack_size: item.stack_size,
                 stackable:  item.stackable,
-                count:      item.count,
+                mass:       item.mass,
+                volume:     item.volume,
+                count,
             });
 
             return true;
@@ -347,6 +989,360 @@ impl Inventory {
             *s = slot;
         }
     }
+
+    // ==========
+    // Transactions
+    // ==========
+
+    /// Проверка индекса и наличия предмета в слоте
+    fn occupied(&self, index: usize) -> Result<&Slot, InventoryError> {
+        self.items.get(index)
+            .ok_or(InventoryError::OutOfRange(index))?
+            .as_ref()
+            .ok_or(InventoryError::EmptySlot(index))
+    }
+
+    /// Общее кол-во предметов с данным `id_name`
+    pub fn count_of(&self, id_name: &str) -> usize {
+        self.iter_some().filter(|slot| slot.id_name == id_name).map(|slot| slot.count).sum()
+    }
+
+    /// Изъятие `count` предметов с данным `id_name` из любых стаков (всё или ничего)
+    pub fn remove_count(&mut self, id_name: &str, count: usize) -> Result<(), InventoryError> {
+        if self.count_of(id_name) < count {
+            return Err(InventoryError::NotEnough);
+        }
+
+        let mut left = count;
+        for slot in self.items.iter_mut().rev() {
+            if left == 0 {
+                break;
+            }
+            if let Some(item) = slot {
+                if item.id_name == id_name {
+                    let taken = left.min(item.count);
+                    item.count -= taken;
+                    left -= taken;
+
+                    if item.count == 0 {
+                        *slot = None;
+                    }
+                }
+            }
+        }
+
+        self.debug_validate();
+        Ok(())
+    }
+
+    /// Отделение `amount` предметов из стака в первый свободный слот, возвращает индекс нового слота
+    pub fn split(&mut self, index: usize, amount: usize) -> Result<usize, InventoryError> {
+        let slot = self.occupied(index)?;
+        if !slot.check_stackable() {
+            return Err(InventoryError::NotStackable);
+        }
+        if amount == 0 || amount >= slot.count {
+            return Err(InventoryError::InvalidAmount);
+        }
+
+        let target = self.items.iter().position(|s| s.is_none()).ok_or(InventoryError::NoSpace)?;
+        let mut part = slot.clone();
+        part.count = amount;
+
+        if let Some(slot) = &mut self.items[index] {
+            slot.count -= amount;
+        }
+        self.items[target] = Some(part);
+
+        self.debug_validate();
+        Ok(target)
+    }
+
+    /// Извлечение `amount` предметов из стака (весь стак, если `amount` не меньше его размера)
+    pub fn split_off(&mut self, index: usize, amount: usize) -> Result<Slot, InventoryError> {
+        let slot = self.occupied(index)?;
+        if amount == 0 {
+            return Err(InventoryError::InvalidAmount);
+        }
+        if amount >= slot.count {
+            return self.items[index].take().ok_or(InventoryError::EmptySlot(index));
+        }
+        if !slot.check_stackable() {
+            return Err(InventoryError::NotStackable);
+        }
+
+        let mut part = slot.clone();
+        part.count = amount;
+        if let Some(slot) = &mut self.items[index] {
+            slot.count -= amount;
+        }
+
+        self.debug_validate();
+        Ok(part)
+    }
+
+    /// Перенос предметов из стака `from` в стак `to`, возвращает перенесённое кол-во.
+    ///
+    /// Не поместившиеся предметы остаются в `from`.
+    pub fn merge(&mut self, from: usize, to: usize) -> Result<usize, InventoryError> {
+        if from == to {
+            return Err(InventoryError::InvalidAmount);
+        }
+
+        let source = self.occupied(from)?;
+        let target = self.occupied(to)?;
+        if !source.same_item(target) {
+            return Err(InventoryError::Mismatch);
+        }
+        if !target.check_stackable() {
+            return Err(InventoryError::NotStackable);
+        }
+
+        let moved = source.count.min(target.space_left());
+        if moved == 0 {
+            return Err(InventoryError::NoSpace);
+        }
+
+        if let Some(target) = &mut self.items[to] {
+            target.count += moved;
+        }
+        if let Some(source) = &mut self.items[from] {
+            source.count -= moved;
+        }
+        if self.items[from].as_ref().is_some_and(|source| source.count == 0) {
+            self.items[from] = None;
+        }
+
+        self.debug_validate();
+        Ok(moved)
+    }
+
+    /// Помещение слота в стак по индексу (объединение, если предметы совпадают), возвращает остаток.
+    ///
+    /// Остаток также образуется, если не проходят ограничения по массе или объёму.
+    pub fn put_into(&mut self, index: usize, mut slot: Slot) -> Result<Option<Slot>, InventoryError> {
+        let allowed = slot.count.min(self.units_fit(slot.mass, slot.volume).0);
+        let target = self.items.get_mut(index).ok_or(InventoryError::OutOfRange(index))?;
+
+        if let Some(existing) = target.as_mut() {
+            if !existing.same_item(&slot) {
+                return Err(InventoryError::Mismatch);
+            }
+
+            let moved = allowed.min(existing.space_left());
+            if moved == 0 {
+                return Err(InventoryError::NoSpace);
+            }
+
+            existing.count += moved;
+            slot.count -= moved;
+            return Ok((slot.count > 0).then_some(slot));
+        }
+
+        if allowed == 0 {
+            return Err(InventoryError::NoSpace);
+        }
+
+        let mut part = slot.clone();
+        part.count = allowed;
+        slot.count -= allowed;
+        *target = Some(part);
+
+        Ok((slot.count > 0).then_some(slot))
+    }
+
+    /// Замена содержимого слота, возвращает прежнее содержимое
+    pub fn replace_slot(&mut self, index: usize, slot: Slot) -> Result<Option<Slot>, InventoryError> {
+        let previous = self.items.get_mut(index).ok_or(InventoryError::OutOfRange(index))?.take();
+
+        if self.units_fit(slot.mass, slot.volume).0 < slot.count {
+            self.items[index] = previous;
+            return Err(InventoryError::NoSpace);
+        }
+
+        self.items[index] = Some(slot);
+        Ok(previous)
+    }
+
+    /// Обмен содержимого двух слотов
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), InventoryError> {
+        if a >= self.items.len() {
+            return Err(InventoryError::OutOfRange(a));
+        }
+        if b >= self.items.len() {
+            return Err(InventoryError::OutOfRange(b));
+        }
+
+        self.items.swap(a, b);
+        Ok(())
+    }
+
+    /// Объединение всех неполных стаков одинаковых предметов
+    pub fn merge_stacks(&mut self) {
+        for to in 0..self.items.len() {
+            for from in (to + 1)..self.items.len() {
+                // Ошибки здесь ожидаемы (разные предметы, полный стак) и пропускаются
+                let _ = self.merge(from, to);
+            }
+        }
+    }
+
+    /// Сортировка инвентаря, неполные стаки предварительно объединяются, пустые слоты в конце
+    pub fn sort(&mut self, mode: SortMode) {
+        self.merge_stacks();
+
+        let capacity = self.items.len();
+        let mut slots: Vec<Slot> = self.items.drain(..).flatten().collect();
+
+        match mode {
+            SortMode::ByType => slots.sort_by(|a, b| {
+                type_rank(&a.item_type).cmp(&type_rank(&b.item_type))
+                    .then_with(|| format!("{:?}", a.item_type).cmp(&format!("{:?}", b.item_type)))
+                    .then_with(|| a.name.cmp(&b.name))
+                    .then_with(|| b.count.cmp(&a.count))
+            }),
+            SortMode::ByName => slots.sort_by(|a, b| {
+                a.name.cmp(&b.name).then_with(|| b.count.cmp(&a.count))
+            }),
+        }
+
+        self.items = slots.into_iter().map(Some).collect();
+        self.items.resize(capacity, None);
+
+        self.debug_validate();
+    }
+
+    /// Перенос стака в другой инвентарь: сначала дополняются неполные стаки, затем свободный слот.
+    ///
+    /// Возвращает перенесённое кол-во, не поместившиеся предметы остаются в исходном слоте.
+    pub fn quick_move(&mut self, index: usize, other: &mut Inventory) -> Result<usize, InventoryError> {
+        let mut slot = self.occupied(index)?.clone();
+        let total = slot.count;
+
+        // Часть стака, которая не проходит по массе или объёму, сразу остаётся на месте
+        let allowed = total.min(other.units_fit(slot.mass, slot.volume).0);
+        let mut budget = allowed;
+
+        for target in other.items.iter_mut().flatten() {
+            if budget == 0 {
+                break;
+            }
+            if target.same_item(&slot) {
+                let moved = budget.min(target.space_left());
+                target.count += moved;
+                budget -= moved;
+            }
+        }
+
+        if budget > 0 {
+            if let Some(target) = other.items.iter_mut().find(|s| s.is_none()) {
+                let mut part = slot.clone();
+                part.count = budget;
+                *target = Some(part);
+                budget = 0;
+            }
+        }
+
+        let moved = allowed - budget;
+        if moved == 0 {
+            return Err(InventoryError::NoSpace);
+        }
+
+        slot.count -= moved;
+        self.items[index] = (slot.count > 0).then_some(slot);
+
+        self.debug_validate();
+        other.debug_validate();
+        Ok(moved)
+    }
+
+    /// Проверка инвариантов: нет пустых стаков и стаков больше `stack_size`
+    fn debug_validate(&self) {
+        for slot in self.iter_some() {
+            debug_assert!(slot.count > 0, "Inventory - пустой стак {}", slot.id_name);
+            debug_assert!(
+                !slot.check_stackable() || slot.stack_size.map_or(true, |stack_size| slot.count <= stack_size),
+                "Inventory - стак {} превышает stack_size", slot.id_name
+            );
+        }
+    }
+
+    /// Изъятие `item.count` предметов, совпадающих с `item` (тот же предмет с тем же состоянием экземпляра).
+    ///
+    /// Возвращает изъятые части стаков, их может быть меньше, если совпадающих предметов не хватает
+    pub fn take_matching(&mut self, item: &Slot) -> Vec<Slot> {
+        let mut left = item.count;
+        let mut taken = Vec::new();
+
+        for slot in self.items.iter_mut() {
+            if left == 0 {
+                break;
+            }
+            let Some(existing) = slot.as_mut().filter(|existing| existing.same_item(item)) else {
+                continue;
+            };
+
+            let count = left.min(existing.count);
+            let mut part = existing.clone();
+            part.count = count;
+            existing.count -= count;
+            left -= count;
+
+            if existing.count == 0 {
+                *slot = None;
+            }
+            taken.push(part);
+        }
+
+        self.debug_validate();
+        taken
+    }
+
+    /// Извлечение всех предметов, вместимость сохраняется
+    pub fn take_all(&mut self) -> Vec<Slot> {
+        self.items.iter_mut().filter_map(|slot| slot.take()).collect()
+    }
+
+    /// Изменение вместимости.
+    ///
+    /// При уменьшении предметы из убираемых слотов переносятся в свободные,
+    /// не поместившиеся возвращаются.
+    pub fn resize(&mut self, capacity: usize) -> Vec<Slot> {
+        if capacity >= self.items.len() {
+            self.items.resize(capacity, None);
+            return Vec::new();
+        }
+
+        let removed: Vec<Slot> = self.items.drain(capacity..).flatten().collect();
+
+        removed.into_iter()
+            .filter_map(|slot| self.insert_slot(slot).err())
+            .collect()
+    }
+
+    /// Помещение слота целиком в первый свободный слот, возвращает индекс либо слот обратно
+    pub fn insert_slot(&mut self, slot: Slot) -> Result<usize, Slot> {
+        if self.units_fit(slot.mass, slot.volume).0 < slot.count {
+            return Err(slot);
+        }
+
+        if let Some(index) = self.items.iter().position(|s| s.is_none()) {
+            self.items[index] = Some(slot);
+            Ok(index)
+        } else {
+            Err(slot)
+        }
+    }
+}
+
+/// Порядок типов при сортировке: оружие, инструменты, предметы, прочее
+fn type_rank(item_type: &ItemType) -> u8 {
+    match item_type {
+        ItemType::Weapon(_) => 0,
+        ItemType::Tool(_)   => 1,
+        ItemType::Item(_)   => 2,
+        ItemType::None      => 3,
+    }
 }
 
 impl Index<usize> for Inventory {
@@ -356,4 +1352,148 @@ impl Index<usize> for Inventory {
     fn index(&self, index: usize) -> &Self::Output {
         &self.items[index]
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng
+    };
+
+    use super::*;
+
+    /// Кол-во случайных последовательностей и операций в каждой
+    const RUNS: u64 = 200;
+    const STEPS: usize = 300;
+
+    /// Предмет для тестов: `stack_size` 1 - не складывается в стаки
+    fn item(id_name: &str, stack_size: usize, durability: Option<usize>) -> Slot {
+        Slot {
+            name:       id_name.to_string(),
+            id_name:    id_name.to_string(),
+            id_source:  None,
+            item_type:  ItemType::None,
+            instance:   ItemInstance { durability, ..default() },
+            stack_size: Some(stack_size),
+            stackable:  Some(if stack_size > 1 { ItemStackType::Scalable } else { ItemStackType::Fixed }),
+            mass:       1.0,
+            volume:     1.0,
+            count:      1,
+        }
+    }
+
+    /// Случайный стак: разные размеры стаков, одинаковые `id_name` с разным состоянием и нескладываемый предмет
+    fn random_slot(rng: &mut StdRng) -> Slot {
+        let mut slot = match rng.gen_range(0..5) {
+            0 => item("stone", 10, None),
+            1 => item("plank", 3, None),
+            2 => item("arrow", 7, Some(1)),
+            3 => item("arrow", 7, Some(2)),
+            _ => item("sword", 1, Some(50)),
+        };
+        slot.count = rng.gen_range(1..=slot.stack_size.unwrap_or(1));
+        slot
+    }
+
+    fn random_inventory(rng: &mut StdRng, capacity: usize) -> Inventory {
+        let mut inventory = Inventory::with_capacity(capacity);
+        for index in 0..capacity {
+            if rng.gen_bool(0.6) {
+                inventory.items[index] = Some(random_slot(rng));
+            }
+        }
+        inventory
+    }
+
+    fn totals(inventories: &[&Inventory]) -> HashMap<String, usize> {
+        let mut totals = HashMap::new();
+        for slot in inventories.iter().flat_map(|inventory| inventory.iter_some()) {
+            *totals.entry(slot.id_name.clone()).or_insert(0) += slot.count;
+        }
+        totals
+    }
+
+    fn assert_valid(inventory: &Inventory, capacity: usize, step: &str) {
+        assert_eq!(inventory.len(), capacity, "{}: вместимость изменилась", step);
+        for slot in inventory.iter_some() {
+            assert!(slot.count > 0, "{}: пустой стак {}", step, slot.id_name);
+            assert!(
+                slot.stack_size.map_or(true, |stack_size| slot.count <= stack_size),
+                "{}: стак {} ({}) превышает stack_size", step, slot.id_name, slot.count
+            );
+        }
+    }
+
+    /// Случайные последовательности split / merge / swap / sort / quick_move между двумя инвентарями:
+    /// стаки не превышают `stack_size`, пустых стаков нет, кол-во каждого `id_name` сохраняется,
+    /// а при ошибке инвентари не меняются
+    #[test]
+    fn random_operations_keep_invariants() {
+        for seed in 0..RUNS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut a = random_inventory(&mut rng, 8);
+            let mut b = random_inventory(&mut rng, 5);
+            let expected = totals(&[&a, &b]);
+
+            for _ in 0..STEPS {
+                let before = format!("{:?}{:?}", a, b);
+                let (inventory, other) = if rng.gen_bool(0.5) { (&mut a, &mut b) } else { (&mut b, &mut a) };
+                let capacity = inventory.len();
+                let x = rng.gen_range(0..capacity);
+                let y = rng.gen_range(0..capacity);
+
+                let (step, failed) = match rng.gen_range(0..6) {
+                    0 => {
+                        let amount = rng.gen_range(0..8);
+                        (format!("split({}, {})", x, amount), inventory.split(x, amount).is_err())
+                    },
+                    1 => (format!("merge({}, {})", x, y), inventory.merge(x, y).is_err()),
+                    2 => (format!("swap({}, {})", x, y), inventory.swap(x, y).is_err()),
+                    3 => {
+                        let mode = if rng.gen_bool(0.5) { SortMode::ByType } else { SortMode::ByName };
+                        inventory.sort(mode);
+                        (format!("sort({:?})", mode), false)
+                    },
+                    4 => {
+                        inventory.merge_stacks();
+                        ("merge_stacks".to_string(), false)
+                    },
+                    _ => (format!("quick_move({})", x), inventory.quick_move(x, other).is_err()),
+                };
+
+                let step = format!("seed {} {}", seed, step);
+                assert_valid(&a, 8, &step);
+                assert_valid(&b, 5, &step);
+                assert_eq!(totals(&[&a, &b]), expected, "{}: кол-во предметов изменилось", step);
+                if failed {
+                    assert_eq!(format!("{:?}{:?}", a, b), before, "{}: ошибка изменила инвентарь", step);
+                }
+            }
+        }
+    }
+
+    /// Перенос в инвентарь с ограничением массы переносит только допустимую часть стака
+    #[test]
+    fn quick_move_respects_mass_limit() {
+        for seed in 0..RUNS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut a = random_inventory(&mut rng, 8);
+            let mut b = random_inventory(&mut rng, 5);
+            b.max_mass = Some(b.total_mass() + rng.gen_range(0..20) as f32);
+            let expected = totals(&[&a, &b]);
+
+            for index in 0..a.len() {
+                let _ = a.quick_move(index, &mut b);
+
+                let step = format!("seed {} quick_move({})", seed, index);
+                assert_valid(&a, 8, &step);
+                assert_valid(&b, 5, &step);
+                assert!(b.total_mass() <= b.max_mass.unwrap() + f32::EPSILON, "{}: превышена масса", step);
+                assert_eq!(totals(&[&a, &b]), expected, "{}: кол-во предметов изменилось", step);
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    resource::Registry::{
+        Registry,
+        RecipeRegistry
+    },
+    world::World::{
+        WorldSystem,
+        WorldInfo
+    },
+    ContainerSystem::Inventory,
+    Debug::{
+        GameError,
+        GameErrorType
+    },
+    Entity::EntityBase,
+    Item::{
+        ItemEntity,
+        ItemSpawn
+    },
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    interact::Interact::Reserved,
+    stats::{
+        ExperienceGain,
+        XpSource
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn craft_plugin(app: &mut App) {
+    app
+        // Init Events
+        .add_event::<CraftRequest>()
+        .add_event::<CraftCancel>()
+        .add_event::<CraftFinished>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                craft_request,
+                cancel_craft,
+                tick_craft_queue
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Максимальное кол-во крафтов в очереди
+pub const CRAFT_QUEUE_LIMIT: usize = 8;
+
+/// Крафт в очереди
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CraftTask {
+    pub recipe:     String,
+    pub remaining:  f32,    // Оставшееся время в секундах
+    pub total:      f32,
+}
+
+impl CraftTask {
+    /// Прогресс крафта от 0 до 1
+    pub fn progress(&self) -> f32 {
+        if self.total <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining / self.total).clamp(0.0, 1.0)
+    }
+}
+
+/// Очередь крафтов сущности, ингредиенты изымаются при постановке в очередь
+#[derive(Component, Default, Debug)]
+pub struct CraftQueue {
+    pub tasks: VecDeque<CraftTask>,
+}
+
+/// Ивент запроса крафта (0 - Крафтер | 1 - Рецепт)
+#[derive(Event)]
+pub struct CraftRequest(pub Entity, pub String);
+
+/// Ивент отмены крафта, ингредиенты возвращаются (0 - Крафтер | 1 - Индекс в очереди)
+#[derive(Event)]
+pub struct CraftCancel(pub Entity, pub usize);
+
+/// Ивент завершения крафта (0 - Крафтер | 1 - Рецепт)
+#[derive(Event)]
+pub struct CraftFinished(pub Entity, pub String);
+
+fn craft_error(payload: String) -> GameError {
+    GameError::new(GameErrorType::CraftingFailed, payload)
+}
+
+/// Изъятие ингредиентов и износ инструментов (всё или ничего)
+pub fn take_ingredients(inventory: &mut Inventory, recipe: &RecipeRegistry) -> Result<(), GameError> {
+    let mut preview = inventory.clone();
+
+    for ingredient in &recipe.request {
+        preview.remove_count(&ingredient.item, ingredient.count)
+            .map_err(|err| craft_error(format!("{} x{}: {}", ingredient.item, ingredient.count, err)))?;
+    }
+
+    for tool in &recipe.tools {
+        let index = (0..preview.len())
+            .find(|index| matches!(preview.get_slot(*index), Some(Some(slot)) if slot.id_name == tool.item))
+            .ok_or_else(|| craft_error(format!("missing tool {}", tool.item)))?;
+
+        if tool.consumed {
+            preview.remove_count(&tool.item, 1)
+                .map_err(|err| craft_error(format!("{}: {}", tool.item, err)))?;
+        } else if tool.durability_cost > 0 {
+            // Изнашивается один экземпляр из стака, он занимает отдельный слот
+            let mut used = preview.split_off(index, 1)
+                .map_err(|err| craft_error(format!("{}: {}", tool.item, err)))?;
+
+            if used.instance.wear(tool.durability_cost) {
+                info!("Craft - инструмент {} сломался", tool.item);
+            } else if preview.insert_slot(used).is_err() {
+                return Err(craft_error(format!("no space for worn tool {}", tool.item)));
+            }
+        }
+    }
+
+    *inventory = preview;
+    Ok(())
+}
+
+/// Хватает ли ингредиентов и инструментов для рецепта и есть ли рядом нужное рабочее место
+pub fn can_craft(inventory: &Inventory, recipe: &RecipeRegistry, workstation_near: bool) -> bool {
+    recipe.request.iter().all(|ingredient| inventory.count_of(&ingredient.item) >= ingredient.count)
+        && recipe.tools.iter().all(|tool| inventory.count_of(&tool.item) > 0)
+        && workstation_near
+}
+
+/// Есть ли в радиусе от крафтера рабочее место рецепта, не занятое другой сущностью
+pub fn workstation_near<'a>(
+    recipe:     &RecipeRegistry,
+    crafter:    Entity,
+    position:   Vec2,
+    radius:     f32,
+    objects:    impl IntoIterator<Item = (&'a EntityObject, &'a Footprint, Option<&'a Reserved>)>,
+) -> bool {
+    let Some(workstation) = &recipe.workstation else {
+        return true;
+    };
+
+    objects.into_iter().any(|(object, footprint, reserved)| {
+        object.id_name == *workstation
+            && Reserved::allows(reserved, crafter)
+            && footprint.distance(position) <= radius
+    })
+}
+
+/// Добавление предметов в инвентарь, не поместившиеся выпадают рядом с сущностью
+pub fn give_items(
+    inventory:  &mut Inventory,
+    mut item:   ItemEntity,
+    position:   Vec2,
+    spawn_i:    &mut EventWriter<ItemSpawn>,
+) {
+    if let Err(reason) = inventory.add(&mut item) {
+        info!("Craft - {} x{} не поместилось в инвентарь ({})", item.id_name, item.count, reason);
+        spawn_i.send(ItemSpawn(
+            item.id_name,
+            WorldSystem::get_currect_chunk_subtile(position.as_ivec2()),
+            item.count,
+            Some(item.instance)
+        ));
+    }
+}
+
+fn craft_request(
+    mut crafters:   Query<(&mut Inventory, &mut CraftQueue, &EntityBase, &Transform)>,
+    mut event:      EventReader<CraftRequest>,
+        objects:    Query<(&EntityObject, &Footprint, Option<&Reserved>)>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Some(recipe) = registry.get_recipe_info(&event.1).filter(|recipe| !recipe.construction) else {
+            warn!("{}", craft_error(format!("unknown recipe {}", event.1)));
+            continue;
+        };
+        let Ok((mut inventory, mut queue, base, transform)) = crafters.get_mut(event.0) else {
+            continue;
+        };
+
+        if queue.tasks.len() >= CRAFT_QUEUE_LIMIT {
+            warn!("{}", craft_error("craft queue is full".to_string()));
+            continue;
+        }
+
+        if !workstation_near(recipe, event.0, transform.translation.truncate(), base.interaction_radius, objects.iter()) {
+            warn!("{}", craft_error(format!("{} requires workstation {:?}", recipe.id_name, recipe.workstation)));
+            continue;
+        }
+
+        match take_ingredients(&mut inventory, recipe) {
+            Ok(()) => queue.tasks.push_back(CraftTask {
+                recipe:     recipe.id_name.clone(),
+                remaining:  recipe.craft_time,
+                total:      recipe.craft_time,
+            }),
+            Err(err) => warn!("{}", err),
+        }
+    }
+}
+
+fn cancel_craft(
+    mut crafters:   Query<(&mut Inventory, &mut CraftQueue, &Transform)>,
+    mut event:      EventReader<CraftCancel>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((mut inventory, mut queue, transform)) = crafters.get_mut(event.0) else {
+            continue;
+        };
+        let Some(task) = queue.tasks.remove(event.1) else {
+            continue;
+        };
+        let Some(recipe) = registry.get_recipe_info(&task.recipe) else {
+            continue;
+        };
+
+        // Возвращаются только ингредиенты, износ инструментов не восстанавливается
+        for ingredient in &recipe.request {
+            if let Some(info) = registry.get_item_info(&ingredient.item) {
+                give_items(&mut inventory, ItemEntity::from_info(info, ingredient.count), transform.translation.truncate(), &mut spawn_i);
+            }
+        }
+    }
+}
+
+fn tick_craft_queue(
+    mut crafters:   Query<(Entity, &mut Inventory, &mut CraftQueue, &Transform)>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+    mut xp_event:   EventWriter<ExperienceGain>,
+    mut finished:   EventWriter<CraftFinished>,
+        registry:   Res<Registry>,
+        clock:      Res<WorldInfo>,
+) {
+    for (entity, mut inventory, mut queue, transform) in &mut crafters {
+        let Some(task) = queue.tasks.front_mut() else {
+            continue;
+        };
+
+        task.remaining -= clock.delta();
+        if task.remaining > 0.0 {
+            continue;
+        }
+
+        let Some(task) = queue.tasks.pop_front() else {
+            continue;
+        };
+        let Some(recipe) = registry.get_recipe_info(&task.recipe) else {
+            continue;
+        };
+
+        if let Some(info) = registry.get_item_info(&recipe.result.output) {
+            give_items(&mut inventory, ItemEntity::from_info(info, recipe.result.count), transform.translation.truncate(), &mut spawn_i);
+        }
+
+        if recipe.xp > 0 {
+            xp_event.send(ExperienceGain(entity, recipe.xp, XpSource::Craft));
+        }
+        finished.send(CraftFinished(entity, task.recipe));
+    }
+}
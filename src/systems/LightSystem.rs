@@ -0,0 +1,417 @@
+use std::collections::HashSet;
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            AsBindGroup,
+            BlendComponent,
+            BlendFactor,
+            BlendOperation,
+            BlendState,
+            Extent3d,
+            RenderPipelineDescriptor,
+            ShaderRef,
+            SpecializedMeshPipelineError,
+            TextureDimension,
+            TextureFormat
+        },
+        texture::ImageSampler
+    },
+    sprite::{
+        Material2d,
+        Material2dKey,
+        Material2dPlugin,
+        MaterialMesh2dBundle,
+        Mesh2dHandle
+    }
+};
+
+use crate::core::{
+    resource::Registry::{
+        Registry,
+        LightRegistry
+    },
+    world::{
+        World::WorldInfo,
+        Grid::{
+            Grid,
+            CHUNK_SIZE
+        }
+    },
+    ContainerSystem::Equipment,
+    WeatherSystem::Weather,
+    Object::{
+        Footprint,
+        PersistentObject
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn light_plugin(app: &mut App) {
+    app
+        // Init Plugins
+        .add_plugins(Material2dPlugin::<LightMaterial>::default())
+        // Init Resources
+        .init_resource::<LightMap>()
+        // Init Systems
+        .add_systems(OnEnter(AppState::Game), spawn_light_overlay)
+        .add_systems(Update,
+            (
+                sync_equipped_lights,
+                update_light_map,
+                draw_light_map
+            ).chain().run_if(in_state(AppState::Game))
+        )
+        .add_systems(OnExit(AppState::Game), despawn_light_overlay);
+}
+
+//
+//
+//
+
+/// Шейдер слоя освещения
+pub const LIGHT_SHADER: &str = "core/shaders/light.wgsl";
+/// Слой освещения рисуется поверх всех спрайтов мира
+pub const LIGHT_LAYER_Z: f32 = 10.0;
+/// Оттенок ночного освещения
+pub const NIGHT_TINT: Vec3 = Vec3::new(0.55, 0.65, 1.0);
+/// Доля освещения от времени суток, проходящая под крышу
+pub const ROOF_DAYLIGHT: f32 = 0.5;
+/// Интервал пересчёта карты освещения в секундах (мерцание, движение источников, двери)
+pub const LIGHT_UPDATE_TIME: f32 = 0.1;
+
+/// Источник света
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LightSource {
+    pub radius:     f32,    // Радиус в клетках
+    pub color:      Vec3,
+    pub intensity:  f32,
+    pub flicker:    f32,
+}
+
+impl LightSource {
+    pub fn from_info(info: &LightRegistry) -> Self {
+        Self {
+            radius:     info.radius,
+            color:      Vec3::new(info.color.0, info.color.1, info.color.2),
+            intensity:  info.intensity,
+            flicker:    info.flicker.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Яркость с учётом мерцания, фаза своя у каждого источника
+    pub fn brightness(&self, seed: f32, time: f32) -> f32 {
+        if self.flicker <= 0.0 {
+            return self.intensity;
+        }
+        let noise = ((time * 11.0 + seed).sin() * (time * 7.3 + seed * 1.7).sin()) * 0.5 + 0.5;
+        self.intensity * (1.0 - self.flicker * noise)
+    }
+}
+
+/// Объект загораживает свет на своих клетках (закрытая дверь, стена)
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LightOccluder;
+
+/// Освещённость по клеткам загруженных чанков
+#[derive(Resource, Default)]
+pub struct LightMap {
+    pub origin:     IVec2,      // Нижняя левая клетка карты
+    pub size:       UVec2,      // Размер в клетках
+    pub ambient:    Vec3,       // Освещение от времени суток
+        levels:     Vec<Vec3>,
+        image:      Handle<Image>,
+}
+
+impl LightMap {
+    fn index(&self, tile: IVec2) -> Option<usize> {
+        let local = tile - self.origin;
+        if local.x < 0 || local.y < 0 || local.x >= self.size.x as i32 || local.y >= self.size.y as i32 {
+            return None;
+        }
+        Some((local.y as u32 * self.size.x + local.x as u32) as usize)
+    }
+
+    /// Цвет освещения клетки, вне загруженных чанков - только время суток
+    pub fn color_at(&self, tile: IVec2) -> Vec3 {
+        self.index(tile)
+            .and_then(|index| self.levels.get(index).copied())
+            .unwrap_or(self.ambient)
+    }
+
+    /// Уровень освещённости клетки (0 - 1)
+    pub fn level_at(&self, tile: IVec2) -> f32 {
+        self.color_at(tile).max_element().min(1.0)
+    }
+
+    /// Уровень освещённости по глобальным координатам
+    pub fn level_at_position(&self, position: Vec2) -> f32 {
+        self.level_at(Grid::tile_of(position.as_ivec2()))
+    }
+}
+
+/// Материал слоя освещения: карта освещения умножается на цвет мира
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct LightMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub lightmap: Handle<Image>,
+}
+
+impl Material2d for LightMaterial {
+    fn fragment_shader() -> ShaderRef {
+        LIGHT_SHADER.into()
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout:    &MeshVertexBufferLayout,
+        _key:       Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let target = descriptor.fragment.as_mut()
+            .and_then(|fragment| fragment.targets.get_mut(0))
+            .and_then(|target| target.as_mut());
+
+        if let Some(target) = target {
+            target.blend = Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::Zero,
+                    operation:  BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Прямоугольник слоя освещения
+#[derive(Component)]
+pub struct LightOverlay;
+
+fn light_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width:                  size.x.max(1),
+            height:                 size.y.max(1),
+            depth_or_array_layers:  1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::linear();
+    image
+}
+
+fn spawn_light_overlay(
+    mut commands:   Commands,
+    mut light_map:  ResMut<LightMap>,
+    mut images:     ResMut<Assets<Image>>,
+    mut meshes:     ResMut<Assets<Mesh>>,
+    mut materials:  ResMut<Assets<LightMaterial>>,
+) {
+    light_map.image = images.add(light_image(UVec2::ONE));
+    light_map.size = UVec2::ZERO;
+
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh:       Mesh2dHandle(meshes.add(Rectangle::new(1.0, 1.0))),
+            material:   materials.add(LightMaterial { lightmap: light_map.image.clone() }),
+            transform:  Transform::from_xyz(0.0, 0.0, LIGHT_LAYER_Z),
+            ..default()
+        },
+        LightOverlay,
+        Name::new("Light Overlay")
+    ));
+}
+
+fn despawn_light_overlay(
+    mut commands:   Commands,
+    mut light_map:  ResMut<LightMap>,
+        overlay:    Query<Entity, With<LightOverlay>>,
+) {
+    for entity in &overlay {
+        commands.entity(entity).despawn_recursive();
+    }
+    *light_map = LightMap::default();
+}
+
+/// Надетый светящийся предмет делает сущность источником света
+fn sync_equipped_lights(
+    mut commands:   Commands,
+        entities:   Query<(Entity, &Equipment), Changed<Equipment>>,
+        registry:   Res<Registry>,
+) {
+    for (entity, equipment) in &entities {
+        let light = equipment.iter_some()
+            .find_map(|(_, slot)| registry.get_item_info(&slot.id_name).and_then(|info| info.light));
+
+        match light {
+            Some(light) => { commands.entity(entity).insert(LightSource::from_info(&light)); },
+            None        => { commands.entity(entity).remove::<LightSource>(); },
+        }
+    }
+}
+
+/// Проходит ли свет от клетки источника до клетки цели, сама цель может быть стеной
+fn is_visible(occluders: &HashSet<IVec2>, from: IVec2, to: IVec2) -> bool {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut tile = from;
+
+    while tile != to {
+        let doubled = error * 2;
+        if doubled > -delta.y {
+            error -= delta.y;
+            tile.x += step.x;
+        }
+        if doubled < delta.x {
+            error += delta.x;
+            tile.y += step.y;
+        }
+        if tile != to && occluders.contains(&tile) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Пересчёт освещения: время суток с погодой и крышами, точечные источники с тенями от стен
+///
+/// Карта пересчитывается раз в `LIGHT_UPDATE_TIME`, появление и удаление источника пересчитывает её сразу
+fn update_light_map(
+    mut light_map:  ResMut<LightMap>,
+    mut timer:      Local<f32>,
+    mut removed:    RemovedComponents<LightSource>,
+        lights:     Query<(Entity, &LightSource, &GlobalTransform)>,
+        added:      Query<(), Added<LightSource>>,
+        occluders:  Query<&Footprint, With<LightOccluder>>,
+        walls:      Query<&Transform, With<PersistentObject>>,
+        grid:       Res<Grid>,
+        registry:   Res<Registry>,
+        weather:    Res<Weather>,
+        clock:      Res<WorldInfo>,
+        time:       Res<Time>,
+) {
+    *timer += time.delta_seconds();
+    let forced = !added.is_empty() || removed.read().count() > 0;
+    if *timer < LIGHT_UPDATE_TIME && !forced {
+        return;
+    }
+    *timer = 0.0;
+
+    let daylight = clock.daylight() * (1.0 - weather.darkness(&registry));
+    light_map.ambient = NIGHT_TINT.lerp(Vec3::ONE, daylight) * daylight;
+
+    // Карта покрывает прямоугольник загруженных чанков
+    let chunk_tiles = CHUNK_SIZE / 16;
+    let (min, max) = grid.chunks.keys().fold((IVec2::MAX, IVec2::MIN), |(min, max), chunk| (min.min(*chunk), max.max(*chunk)));
+    if min.x > max.x {
+        light_map.size = UVec2::ZERO;
+        light_map.levels.clear();
+        return;
+    }
+    light_map.origin = min * chunk_tiles;
+    light_map.size = ((max - min + 1) * chunk_tiles).as_uvec2();
+
+    let ambient = light_map.ambient;
+    let count = (light_map.size.x * light_map.size.y) as usize;
+    light_map.levels.clear();
+    light_map.levels.resize(count, ambient);
+    for tile in &grid.roofs {
+        if let Some(index) = light_map.index(*tile) {
+            light_map.levels[index] = ambient * ROOF_DAYLIGHT;
+        }
+    }
+
+    // Открытые двери свет пропускают
+    let mut blocked: HashSet<IVec2> = occluders.iter()
+        .flat_map(|footprint| footprint.tiles())
+        .filter(|tile| grid.doors.get(tile).map_or(true, |door| !door.open))
+        .collect();
+    blocked.extend(walls.iter().map(|transform| Grid::tile_of(transform.translation.truncate().as_ivec2())));
+
+    let elapsed = time.elapsed_seconds();
+    for (entity, light, transform) in &lights {
+        let center = Grid::tile_of(transform.translation().truncate().as_ivec2());
+        let brightness = light.brightness(entity.index() as f32, elapsed);
+        let radius = light.radius.ceil() as i32;
+
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                let tile = center + IVec2::new(x, y);
+                let distance = Vec2::new(x as f32, y as f32).length();
+                if distance > light.radius {
+                    continue;
+                }
+                let Some(index) = light_map.index(tile) else {
+                    continue;
+                };
+                if !is_visible(&blocked, center, tile) {
+                    continue;
+                }
+
+                let falloff = (1.0 - distance / light.radius.max(0.01)).powi(2);
+                light_map.levels[index] += light.color * brightness * falloff;
+            }
+        }
+    }
+}
+
+/// Загрузка карты освещения в текстуру слоя, только после пересчёта карты
+fn draw_light_map(
+    mut images:     ResMut<Assets<Image>>,
+    mut overlay:    Query<(&mut Transform, &mut Visibility), With<LightOverlay>>,
+        light_map:  Res<LightMap>,
+) {
+    if !light_map.is_changed() {
+        return;
+    }
+
+    let Ok((mut transform, mut visibility)) = overlay.get_single_mut() else {
+        return;
+    };
+    if light_map.size == UVec2::ZERO {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    let Some(image) = images.get_mut(&light_map.image) else {
+        return;
+    };
+
+    let extent = Extent3d {
+        width:                  light_map.size.x,
+        height:                 light_map.size.y,
+        depth_or_array_layers:  1,
+    };
+    if image.texture_descriptor.size != extent {
+        image.resize(extent);
+    }
+
+    // Первая строка текстуры - верхняя строка клеток
+    let width = light_map.size.x as usize;
+    for (row, levels) in light_map.levels.chunks(width).rev().enumerate() {
+        for (column, level) in levels.iter().enumerate() {
+            let color = level.min(Vec3::ONE) * 255.0;
+            let offset = (row * width + column) * 4;
+            image.data[offset..offset + 4].copy_from_slice(&[color.x as u8, color.y as u8, color.z as u8, 255]);
+        }
+    }
+
+    let size = light_map.size.as_vec2() * 16.0;
+    transform.translation = (light_map.origin.as_vec2() * 16.0 + size / 2.0).extend(LIGHT_LAYER_Z);
+    transform.scale = size.extend(1.0);
+}
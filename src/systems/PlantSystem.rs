@@ -0,0 +1,459 @@
+use std::{
+    collections::HashMap,
+    time::Duration
+};
+
+use bevy::prelude::*;
+
+use crate::core::{
+    entities::ai::Job::{
+        JobBoard,
+        JobKind,
+        JobPriority
+    },
+    resource::{
+        graphic::Atlas::AtlasRes,
+        Registry::{
+            Registry,
+            AfterHarvest
+        }
+    },
+    world::{
+        World::{
+            WorldSystem,
+            WorldInfo
+        },
+        Grid::{
+            Grid,
+            get_format_current_chunk
+        }
+    },
+    ContainerSystem::{
+        Inventory,
+        Equipment,
+        EquipSlot,
+        WearItem,
+        drop_slots
+    },
+    Entity::EntityBase,
+    Item::ItemSpawn,
+    ItemType::{
+        ItemType,
+        Tool
+    },
+    Object::Footprint,
+    ObjType::{
+        GrowthTimer,
+        Harvestable,
+        PlantObject,
+        PlantState,
+        WorldObject
+    },
+    stats::{
+        ExperienceGain,
+        XpSource
+    },
+    LightSystem::LightMap,
+    TimeCycle::Season,
+    UserSystem::{
+        CursorPosition,
+        UserControl
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn plant_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<Plants>()
+        .init_resource::<GrowthConditions>()
+        // Init Events
+        .add_event::<PlantSpawn>()
+        .add_event::<HarvestPlant>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                update_growth_conditions,
+                spawn_plant,
+                sync_plants,
+                grow_plants,
+                plant_input,
+                harvest_plant,
+                post_harvest_jobs
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Условия роста растений, обновляются по часам мира
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GrowthConditions {
+    pub time:           f64,        // Время мира в секундах
+    pub season:         Season,
+    pub season_start:   f64,        // Время мира, с которого идёт текущее время года
+    pub season_length:  f64,        // Длительность времени года в секундах мира
+    pub light:          f32,        // Освещённость от времени суток (0 - 1), у клеток - `LightMap`
+    pub average_light:  f32,        // Средняя освещённость за сутки, по ней досчитывается рост выгруженных растений
+}
+
+impl Default for GrowthConditions {
+    fn default() -> Self {
+        Self {
+            time:           0.0,
+            season:         Season::default(),
+            season_start:   0.0,
+            // До первого обновления по часам время года не меняется
+            season_length:  f64::MAX,
+            light:          1.0,
+            average_light:  1.0,
+        }
+    }
+}
+
+impl GrowthConditions {
+    /// Время мира от `from` до текущего, разбитое по временам года (0 - Время года | 1 - Секунды), от раннего к позднему
+    pub fn seasons_since(&self, from: f64) -> Vec<(Season, f32)> {
+        let mut periods = Vec::new();
+        let (mut season, mut start, mut end) = (self.season, self.season_start, self.time);
+
+        while end > from {
+            periods.push((season, (end - start.max(from)) as f32));
+            end = start;
+            start -= self.season_length;
+            season = season.prev();
+        }
+        periods.reverse();
+        periods
+    }
+}
+
+/// Все растения мира по клеткам, включая растения выгруженных чанков
+#[derive(Resource, Default)]
+pub struct Plants(pub HashMap<IVec2, PlantState>);
+
+/// Событие посадки растения (0 - Растение | 1 - Глобальные координаты)
+#[derive(Event)]
+pub struct PlantSpawn(pub String, pub IVec2);
+
+/// Ивент сбора урожая (0 - Сущность | 1 - Растение)
+#[derive(Event)]
+pub struct HarvestPlant(pub Entity, pub Entity);
+
+/// Где у сущности лежит инструмент (0 - Слот экипировки | 1 - Индекс слота инвентаря)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolSource {
+    Equipped(EquipSlot),
+    Carried(usize),
+}
+
+/// Есть ли у сущности инструмент в инвентаре или экипировке
+pub fn has_tool(inventory: Option<&Inventory>, equipment: Option<&Equipment>, tool: Tool) -> bool {
+    find_tool(inventory, equipment, tool).is_some()
+}
+
+/// Экземпляр инструмента, которым работает сущность: сначала надетый, затем из инвентаря
+pub fn find_tool(inventory: Option<&Inventory>, equipment: Option<&Equipment>, tool: Tool) -> Option<ToolSource> {
+    equipped_tool(equipment, tool).map(ToolSource::Equipped).or_else(|| {
+        let inventory = inventory?;
+        (0..inventory.len())
+            .find(|index| matches!(inventory.get_slot(*index), Some(Some(slot)) if slot.item_type == ItemType::Tool(tool)))
+            .map(ToolSource::Carried)
+    })
+}
+
+/// Слот экипировки, в котором надет инструмент
+pub fn equipped_tool(equipment: Option<&Equipment>, tool: Tool) -> Option<EquipSlot> {
+    equipment?.iter_some()
+        .find(|(_, slot)| slot.item_type == ItemType::Tool(tool))
+        .map(|(slot, _)| slot)
+}
+
+/// Износ одного экземпляра инструмента из инвентаря, сломанный уничтожается.
+///
+/// Изношенный экземпляр занимает отдельный слот, если места нет - он выкладывается рядом
+fn wear_carried_tool(inventory: &mut Inventory, index: usize, position: IVec2, spawn_i: &mut EventWriter<ItemSpawn>) {
+    let Ok(mut used) = inventory.split_off(index, 1) else {
+        return;
+    };
+
+    if used.instance.wear(1) {
+        info!("Harvest - {} сломался", used.display_name());
+    } else if let Err(used) = inventory.insert_slot(used) {
+        drop_slots(vec![used], position, spawn_i);
+    }
+}
+
+fn update_growth_conditions(
+    mut conditions: ResMut<GrowthConditions>,
+        clock:      Res<WorldInfo>,
+) {
+    conditions.time = clock.time_inhabited;
+    conditions.season = clock.season();
+    conditions.season_length = clock.season_length.max(1) as f64 * clock.daytime_length as f64;
+    conditions.season_start = clock.time_inhabited - (clock.day_of_season() as f64 + clock.daytime as f64) * clock.daytime_length as f64;
+    conditions.light = clock.daylight();
+    conditions.average_light = WorldInfo::average_daylight();
+}
+
+fn spawn_plant(
+    mut plants:     ResMut<Plants>,
+    mut event:      EventReader<PlantSpawn>,
+        grid:       Res<Grid>,
+        registry:   Res<Registry>,
+        conditions: Res<GrowthConditions>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if registry.get_plant_info(&event.0).is_none() {
+            warn!("В регистре не найдено растение - {}", &event.0);
+            continue;
+        }
+
+        let tile = Grid::tile_of(event.1);
+        if plants.0.contains_key(&tile) || !grid.can_place(&Footprint::single(tile)) {
+            info!("Plant {} не помещается на {:?}", &event.0, tile);
+            continue;
+        }
+
+        plants.0.insert(tile, PlantState::new(event.0.clone(), conditions.time));
+    }
+}
+
+/// Спавн растений загруженных чанков, рост за время выгрузки досчитывается по средней освещённости
+///
+/// Сущности растений удаляются вместе с чанком, состояние остаётся в `Plants`
+fn sync_plants(
+    mut commands:   Commands,
+    mut plants:     ResMut<Plants>,
+    mut grid:       ResMut<Grid>,
+        existing:   Query<(), With<PlantObject>>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        conditions: Res<GrowthConditions>,
+) {
+    let mut removed = Vec::new();
+
+    for (tile, state) in plants.0.iter_mut() {
+        let loaded = grid.chunks.contains_key(&get_format_current_chunk(*tile * 16));
+        if let Some(entity) = state.entity {
+            if loaded && existing.contains(entity) {
+                continue;
+            }
+            state.entity = None;
+        }
+        if !loaded {
+            continue;
+        }
+
+        let Some(info) = registry.get_plant_info(&state.id_name) else {
+            removed.push(*tile);
+            continue;
+        };
+
+        state.advance(info, conditions.time, &conditions.seasons_since(state.last_update), conditions.average_light);
+        state.entity = WorldObject::Plant(info.plant_type).spawn(&mut commands, &registry, &atlas, &mut grid, *tile, state);
+
+        if state.entity.is_none() {
+            info!("Plant {} на {:?} не может вырасти и удалено", &state.id_name, tile);
+            removed.push(*tile);
+        }
+    }
+
+    for tile in removed {
+        plants.0.remove(&tile);
+    }
+}
+
+/// Рост загруженных растений и смена их спрайтов, освещённость берётся по клетке растения
+fn grow_plants(
+    mut commands:   Commands,
+    mut plants:     ResMut<Plants>,
+    mut objects:    Query<(Entity, &PlantObject, &WorldObject, &mut GrowthTimer)>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        conditions: Res<GrowthConditions>,
+        light_map:  Res<LightMap>,
+        clock:      Res<WorldInfo>,
+) {
+    for (entity, plant, object, mut timer) in &mut objects {
+        if !timer.tick(Duration::from_secs_f32(clock.delta())) {
+            continue;
+        }
+
+        let Some(state) = plants.0.get_mut(&plant.tile) else {
+            continue;
+        };
+        let Some(info) = registry.get_plant_info(&state.id_name) else {
+            continue;
+        };
+
+        if state.advance(info, conditions.time, &conditions.seasons_since(state.last_update), light_map.level_at(plant.tile)) {
+            object.grow(&mut commands, &registry, &atlas, entity, info, state);
+        }
+    }
+}
+
+/// Сбор растения под курсором (H), с Shift - отметка растения для сбора рабочими
+fn plant_input(
+    mut plants:         ResMut<Plants>,
+    mut board:          ResMut<JobBoard>,
+    mut harvest_event:  EventWriter<HarvestPlant>,
+        user:           Query<Entity, With<UserControl>>,
+        objects:        Query<(Entity, &PlantObject, &Footprint, Has<Harvestable>)>,
+        cursor:         Res<CursorPosition>,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+
+    let Ok(player) = user.get_single() else {
+        return;
+    };
+    let Some((entity, plant, footprint, ripe)) = objects.iter()
+        .find(|(_, _, footprint, _)| footprint.contains(Grid::tile_of(cursor.0.as_ivec2())))
+    else {
+        return;
+    };
+
+    // Дистанция до игрока проверяется при обработке сбора
+    if !keyboard_input.pressed(KeyCode::ShiftLeft) {
+        harvest_event.send(HarvestPlant(player, entity));
+        return;
+    }
+
+    let Some(state) = plants.0.get_mut(&plant.tile) else {
+        return;
+    };
+    state.designated = !state.designated;
+
+    if !state.designated {
+        board.cancel(JobKind::Harvest, entity);
+    } else if ripe {
+        board.post(JobKind::Harvest, entity, footprint.center(), JobPriority::Normal, None);
+    }
+}
+
+/// Сбор урожая: выпадение добычи, опыт, износ инструмента и правило после сбора
+fn harvest_plant(
+    mut commands:   Commands,
+    mut event:      EventReader<HarvestPlant>,
+    mut plants:     ResMut<Plants>,
+    mut grid:       ResMut<Grid>,
+    mut board:      ResMut<JobBoard>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+    mut xp_event:   EventWriter<ExperienceGain>,
+    mut wear_event: EventWriter<WearItem>,
+        objects:    Query<(&PlantObject, &WorldObject, &Footprint, Option<&Harvestable>)>,
+    mut actors:     Query<(&EntityBase, &Transform, Option<&mut Inventory>, Option<&Equipment>)>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        conditions: Res<GrowthConditions>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let Ok((plant, object, footprint, harvestable)) = objects.get(event.1) else {
+            continue;
+        };
+        let Ok((base, transform, mut inventory, equipment)) = actors.get_mut(event.0) else {
+            continue;
+        };
+
+        let Some(harvestable) = harvestable else {
+            info!("Harvest - {} ещё не созрело", &plant.id_name);
+            continue;
+        };
+        if footprint.center().distance(transform.translation.truncate()) > base.interaction_radius {
+            info!("Harvest - {:?} слишком далеко от {:?}", event.0, event.1);
+            continue;
+        }
+        // Изнашивается тот же экземпляр, по которому прошла проверка, и только после сбора
+        let tool = match harvestable.tool {
+            Some(tool) => match find_tool(inventory.as_deref(), equipment, tool) {
+                Some(source) => Some(source),
+                None => {
+                    info!("Harvest - для {} нужен инструмент {:?}", &plant.id_name, tool);
+                    continue;
+                },
+            },
+            None => None,
+        };
+
+        let Some(info) = registry.get_plant_info(&plant.id_name) else {
+            continue;
+        };
+        let (Some(harvest), Some(state)) = (&info.harvest, plants.0.get_mut(&plant.tile)) else {
+            continue;
+        };
+
+        // Добыча выпадает под растением
+        let subtile = WorldSystem::get_currect_chunk_subtile(footprint.center().as_ivec2());
+        for (index, loot) in harvest.loot.iter().enumerate() {
+            spawn_i.send(ItemSpawn(loot.item.clone(), subtile + IVec2::new(index as i32 % 3 - 1, -2), loot.count, None));
+        }
+        if harvest.xp > 0 {
+            xp_event.send(ExperienceGain(event.0, harvest.xp, XpSource::Harvest));
+        }
+
+        match tool {
+            Some(ToolSource::Equipped(slot)) => {
+                wear_event.send(WearItem(event.0, slot, 1));
+            },
+            Some(ToolSource::Carried(index)) => {
+                if let Some(inventory) = inventory.as_deref_mut() {
+                    wear_carried_tool(inventory, index, transform.translation.truncate().as_ivec2(), &mut spawn_i);
+                }
+            },
+            None => {},
+        }
+
+        board.cancel(JobKind::Harvest, event.1);
+        state.designated = false;
+
+        match &info.after_harvest {
+            AfterHarvest::Regrow(stage) => {
+                state.stage = *stage;
+                state.progress = 0.0;
+                state.last_update = conditions.time;
+                object.grow(&mut commands, &registry, &atlas, event.1, info, state);
+            },
+            // Новое растение появится на освободившейся клетке в `sync_plants`
+            AfterHarvest::Replant(replant) => {
+                *state = PlantState::new(replant.clone(), conditions.time);
+                grid.remove_object(event.1, footprint);
+                commands.entity(event.1).despawn_recursive();
+            },
+            AfterHarvest::Remove => {
+                plants.0.remove(&plant.tile);
+                grid.remove_object(event.1, footprint);
+                commands.entity(event.1).despawn_recursive();
+            },
+        }
+    }
+}
+
+/// Созревшие отмеченные растения получают работу сбора
+fn post_harvest_jobs(
+    mut board:      ResMut<JobBoard>,
+        plants:     Res<Plants>,
+        ripe:       Query<(Entity, &PlantObject, &Footprint), Added<Harvestable>>,
+) {
+    for (entity, plant, footprint) in &ripe {
+        if plants.0.get(&plant.tile).map_or(false, |state| state.designated) {
+            board.post(JobKind::Harvest, entity, footprint.center(), JobPriority::Normal, None);
+        }
+    }
+}
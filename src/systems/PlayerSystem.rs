@@ -5,13 +5,17 @@ use bevy::prelude::*;
 
 use crate::core::{
     entities::EntitySystem::MovementEntity,
-    // Weapon::*,
+    Weapon::gun_controls,
     AppState,
     Entity::{
         EntityBase,
         EntityHead,
     },
     stats::Stats,
+    skills::{
+        UseSkill,
+        SKILL_SLOTS
+    },
     UserSystem::CursorPosition,
     world::World::WorldSystem,
     UserSystem::{
@@ -27,9 +31,19 @@ use crate::core::{
     // world::chunk::Chunk::Chunk,
     ContainerSystem::{
         CursorContainer,
-        // Inventory
+        Equipment,
+        EquipSlot,
+        WearItem
     },
-    interact::Damage::DamageObject
+    Object::EntityObject,
+    interact::{
+        Damage::DamageObject,
+        Interact::{
+            Interactable,
+            InteractionKind,
+            InteractObject
+        }
+    }
 };
 
 pub struct PlayerPlugin;
@@ -43,7 +57,8 @@ impl Plugin for PlayerPlugin {
             .add_systems(Update, 
                 (
                     Self::player_pickup,
-                    Self::item_drop
+                    Self::item_drop,
+                    Self::player_open_storage
                 ).run_if(in_state(AppState::Game))
             )
             // Удар игроков по объекту
@@ -53,11 +68,13 @@ impl Plugin for PlayerPlugin {
                     Self::player_atack
                 ).run_if(in_state(AppState::Game))
             )
-            // [Test] Обновление системы управления оружием
-            // .add_systems(Update, gun_controls.run_if(in_state(AppState::Game)))
+            // Обновление системы управления надетым оружием
+            .add_systems(Update, gun_controls.run_if(in_state(AppState::Game)))
             // [Test] Соединение оружия и игрока
             .add_systems(PostUpdate, attach_objects.run_if(in_state(AppState::Game)))
             .add_systems(Update, Self::head_movement.run_if(in_state(AppState::Game)))
+            // Применение активных навыков игроком
+            .add_systems(Update, Self::player_skills.run_if(in_state(AppState::Game)))
         ;
     }
 }
@@ -122,6 +139,11 @@ impl PlayerPlugin {
         mut cursor_mode:    ResMut<CursorMode>,
             keyboard_input: Res<ButtonInput<KeyCode>>,
     ) {
+        // В режиме строительства R поворачивает объект
+        if *cursor_mode == CursorMode::Build {
+            return;
+        }
+
         if keyboard_input.just_pressed(KeyCode::KeyR) {
             if *cursor_mode != CursorMode::Atack {
                 info!("Combat mode - enabled");
@@ -138,10 +160,11 @@ impl PlayerPlugin {
         // mut chunk_res:      ResMut<Chunk>,
             cursor:         Res<CursorPosition>,
             mouse_input:    Res<ButtonInput<MouseButton>>,
-            user:           Query<(&EntityBase ,&Transform, &Stats), With<UserControl>>,
+            user:           Query<(&EntityBase ,&Transform, &Stats, Entity, Option<&Equipment>), With<UserControl>>,
         //    object:         Query<(Entity, &Transform), With<EntityObject>>,
         // entity: Query<(&mut EntityBase, &Transform), With<EntityBase>>,
-        mut event:          EventWriter<DamageObject>
+        mut event:          EventWriter<DamageObject>,
+        mut wear_event:     EventWriter<WearItem>,
     ) {
         if user.is_empty() {
             return;
@@ -151,13 +174,39 @@ impl PlayerPlugin {
             if mouse_input.just_pressed(MouseButton::Left) {
                 if let Ok(player) = user.get_single() {
                     if player.0.atack_radius > Vec3::distance(cursor.0.extend(0.5), player.1.translation) {
-                        event.send(DamageObject(cursor.0.as_ivec2(), player.2.str as f32));
+                        event.send(DamageObject(cursor.0.as_ivec2(), player.2.str as f32, Some(player.3), None));
+
+                        // Удар предметом в руке изнашивает его
+                        if player.4.is_some_and(|equipment| equipment.get(EquipSlot::RightHand).is_some()) {
+                            wear_event.send(WearItem(player.3, EquipSlot::RightHand, 1));
+                        }
                     }
                 }
             }
         }
     }
 
+    fn player_skills(
+            user:           Query<Entity, With<UserControl>>,
+            cursor:         Res<CursorPosition>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+        mut event:          EventWriter<UseSkill>
+    ) {
+        if user.is_empty() {
+            return;
+        }
+
+        const SLOT_KEYS: [KeyCode; SKILL_SLOTS] = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4];
+
+        if let Ok(player) = user.get_single() {
+            for (slot, key) in SLOT_KEYS.iter().enumerate() {
+                if keyboard_input.just_pressed(*key) {
+                    event.send(UseSkill(player, slot, cursor.0));
+                }
+            }
+        }
+    }
+
     fn player_pickup(
         // mut commands:           Commands,
         // mut chunk_res:          ResMut<Chunk>,
@@ -186,6 +235,33 @@ impl PlayerPlugin {
         }
     }
 
+    /// Открытие хранилища под курсором (в пределах досягаемости игрока)
+    fn player_open_storage(
+            user:           Query<Entity, With<UserControl>>,
+            storages:       Query<(Entity, &Transform, &Interactable), With<EntityObject>>,
+            cursor:         Res<CursorPosition>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+        mut interact_event: EventWriter<InteractObject>,
+    ) {
+        if !keyboard_input.just_pressed(KeyCode::KeyG) {
+            return;
+        }
+
+        let Ok(player) = user.get_single() else {
+            return;
+        };
+
+        // Дистанция до игрока проверяется при обработке взаимодействия
+        for (storage, transform, interactable) in &storages {
+            if 8.0 > Vec2::distance(cursor.0, transform.translation.truncate())
+                && interactable.has(InteractionKind::OpenContainer)
+            {
+                interact_event.send(InteractObject(player, storage, InteractionKind::OpenContainer));
+                return;
+            }
+        }
+    }
+
     fn item_drop(
         mut cursor_c:       ResMut<CursorContainer>,
         mut spawn_i:        EventWriter<ItemSpawn>,
@@ -202,7 +278,7 @@ impl PlayerPlugin {
             let player = user.single();
             if player.0.interaction_radius > Vec3::distance(cursor.0.extend(0.5), player.1.translation) {
                 if let Some(slot) = cursor_c.slot.take() {
-                    spawn_i.send(ItemSpawn(slot.id_name, WorldSystem::get_currect_chunk_subtile(cursor.0.as_ivec2()), slot.count));
+                    spawn_i.send(ItemSpawn(slot.id_name, WorldSystem::get_currect_chunk_subtile(cursor.0.as_ivec2()), slot.count, Some(slot.instance)));
                 }
             }
         }
@@ -0,0 +1,359 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+    VecDeque
+};
+
+use bevy::prelude::*;
+
+use crate::core::{
+    resource::Registry::Registry,
+    world::{
+        Grid::{
+            Grid,
+            get_format_current_chunk
+        },
+        World::WorldInfo
+    },
+    Item::ItemEntity,
+    LightSystem::LightOccluder,
+    Object::{
+        EntityObject,
+        Footprint,
+        PersistentObject
+    },
+    StockpileSystem::Stockpile,
+    AppState
+};
+
+//
+//
+//
+
+pub fn room_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<Rooms>()
+        // Init Events
+        .add_event::<RoomsChanged>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                track_room_walls,
+                rebuild_rooms,
+                update_room_stats,
+                draw_room_overlay
+            ).chain().run_if(in_state(AppState::Game))
+        )
+        .add_systems(OnExit(AppState::Game), reset_rooms);
+}
+
+//
+//
+//
+
+/// Наибольшая комната в клетках, область больше считается улицей
+pub const MAX_ROOM_SIZE: usize = 400;
+/// На каком удалении от стены (в клетках) крыша ещё держится
+pub const ROOF_SUPPORT_RANGE: i32 = 6;
+/// Насколько один лежащий вне склада предмет снижает чистоту комнаты
+pub const ITEM_DIRT: f32 = 0.05;
+/// Период пересчёта показателей комнат в секундах
+pub const ROOM_STATS_INTERVAL: f32 = 1.0;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Показатели комнаты, по ним потребности рабочих получают комфорт
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoomStats {
+    pub beauty:         f32,    // Средняя красота объектов на клетку
+    pub cleanliness:    f32,    // Чистота (0 - 1)
+}
+
+impl Default for RoomStats {
+    fn default() -> Self {
+        Self {
+            beauty:         0.0,
+            cleanliness:    1.0,
+        }
+    }
+}
+
+/// Замкнутая стенами область клеток
+#[derive(Clone, Debug, Default)]
+pub struct Room {
+    pub tiles:  HashSet<IVec2>,
+    pub roofed: usize,          // Клеток под крышей
+    pub stats:  RoomStats,
+}
+
+impl Room {
+    pub fn size(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Доля клеток комнаты под крышей (0 - 1)
+    pub fn roof_coverage(&self) -> f32 {
+        self.roofed as f32 / self.size().max(1) as f32
+    }
+}
+
+/// Комнаты загруженных чанков, пересчитываются только возле изменившихся стен
+#[derive(Resource, Default)]
+pub struct Rooms {
+    pub rooms:      HashMap<u32, Room>,
+    pub overlay:    bool,                       // Отрисовка комнат в отладке
+        by_tile:    HashMap<IVec2, u32>,
+        walls:      HashMap<IVec2, Entity>,     // Клетки стен, ограничивающих комнаты
+        wall_tiles: HashMap<Entity, Vec<IVec2>>,
+        dirty:      HashSet<IVec2>,             // Клетки, у которых сменились стены
+        next_id:    u32,
+}
+
+/// Ивент пересчёта комнат (0 - Новые комнаты)
+#[derive(Event)]
+pub struct RoomsChanged(pub Vec<u32>);
+
+impl Rooms {
+    pub fn room_id_at(&self, tile: IVec2) -> Option<u32> {
+        self.by_tile.get(&tile).copied()
+    }
+
+    pub fn room_at(&self, tile: IVec2) -> Option<&Room> {
+        self.rooms.get(&self.room_id_at(tile)?)
+    }
+
+    /// Показатели комнаты клетки, `None` - клетка на улице
+    pub fn stats_at(&self, tile: IVec2) -> Option<RoomStats> {
+        self.room_at(tile).map(|room| room.stats)
+    }
+
+    pub fn is_wall(&self, tile: IVec2) -> bool {
+        self.walls.contains_key(&tile)
+    }
+
+    fn add_wall(&mut self, entity: Entity, tiles: Vec<IVec2>) {
+        for tile in &tiles {
+            self.walls.insert(*tile, entity);
+            self.dirty.insert(*tile);
+        }
+        self.wall_tiles.insert(entity, tiles);
+    }
+
+    fn remove_wall(&mut self, entity: Entity) {
+        let Some(tiles) = self.wall_tiles.remove(&entity) else {
+            return;
+        };
+        for tile in tiles {
+            if self.walls.get(&tile) == Some(&entity) {
+                self.walls.remove(&tile);
+            }
+            self.dirty.insert(tile);
+        }
+    }
+
+    fn remove_room(&mut self, id: u32, grid: &mut Grid) {
+        let Some(room) = self.rooms.remove(&id) else {
+            return;
+        };
+        for tile in &room.tiles {
+            self.by_tile.remove(tile);
+            grid.roofs.remove(tile);
+        }
+    }
+
+    /// Заливка области от клетки, `Err` - область вышла на улицу или в выгруженный чанк
+    fn flood(&self, start: IVec2, grid: &Grid) -> Result<HashSet<IVec2>, HashSet<IVec2>> {
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(tile) = queue.pop_front() {
+            if !grid.chunks.contains_key(&get_format_current_chunk(tile * 16)) || visited.len() > MAX_ROOM_SIZE {
+                return Err(visited);
+            }
+            for offset in NEIGHBOURS {
+                let next = tile + offset;
+                if !self.is_wall(next) && visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(visited)
+    }
+
+    /// Крыша держится на клетках не дальше `ROOF_SUPPORT_RANGE` от стены
+    fn roof_tiles(&self, tiles: &HashSet<IVec2>) -> Vec<IVec2> {
+        let mut distance: HashMap<IVec2, i32> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        for tile in tiles {
+            if NEIGHBOURS.iter().any(|offset| self.is_wall(*tile + *offset)) {
+                distance.insert(*tile, 1);
+                queue.push_back(*tile);
+            }
+        }
+        while let Some(tile) = queue.pop_front() {
+            let next_distance = distance[&tile] + 1;
+            if next_distance > ROOF_SUPPORT_RANGE {
+                continue;
+            }
+            for offset in NEIGHBOURS {
+                let next = tile + offset;
+                if tiles.contains(&next) && !distance.contains_key(&next) {
+                    distance.insert(next, next_distance);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distance.into_keys().collect()
+    }
+
+    fn add_room(&mut self, tiles: HashSet<IVec2>, grid: &mut Grid) -> u32 {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let roofs = self.roof_tiles(&tiles);
+        grid.roofs.extend(roofs.iter().copied());
+        for tile in &tiles {
+            self.by_tile.insert(*tile, id);
+        }
+
+        self.rooms.insert(id, Room {
+            tiles,
+            roofed: roofs.len(),
+            stats:  RoomStats::default(),
+        });
+        id
+    }
+
+    /// Комнаты у изменившихся клеток распадаются и заливаются заново
+    fn rebuild(&mut self, grid: &mut Grid) -> Vec<u32> {
+        let mut seeds = Vec::new();
+        for tile in std::mem::take(&mut self.dirty) {
+            for seed in NEIGHBOURS.iter().map(|offset| tile + *offset).chain([tile]) {
+                if let Some(id) = self.room_id_at(seed) {
+                    self.remove_room(id, grid);
+                }
+                seeds.push(seed);
+            }
+        }
+
+        let mut created = Vec::new();
+        let mut outdoor = HashSet::new();
+        for seed in seeds {
+            if self.is_wall(seed) || self.by_tile.contains_key(&seed) || outdoor.contains(&seed) {
+                continue;
+            }
+            match self.flood(seed, grid) {
+                Ok(tiles)       => created.push(self.add_room(tiles, grid)),
+                Err(visited)    => outdoor.extend(visited),
+            }
+        }
+        created
+    }
+}
+
+/// Стенами комнат считаются непрозрачные объекты (стены, двери) и соединяющиеся объекты
+fn track_room_walls(
+    mut rooms:          ResMut<Rooms>,
+    mut removed_opaque: RemovedComponents<LightOccluder>,
+    mut removed_walls:  RemovedComponents<PersistentObject>,
+        opaque:         Query<(Entity, &Footprint), Added<LightOccluder>>,
+        walls:          Query<(Entity, &Transform), Added<PersistentObject>>,
+) {
+    for entity in removed_opaque.read().chain(removed_walls.read()) {
+        rooms.remove_wall(entity);
+    }
+
+    for (entity, footprint) in &opaque {
+        rooms.add_wall(entity, footprint.tiles().collect());
+    }
+    for (entity, transform) in &walls {
+        rooms.add_wall(entity, vec![Grid::tile_of(transform.translation.truncate().as_ivec2())]);
+    }
+}
+
+fn rebuild_rooms(
+    mut rooms:      ResMut<Rooms>,
+    mut grid:       ResMut<Grid>,
+    mut event:      EventWriter<RoomsChanged>,
+) {
+    if rooms.dirty.is_empty() {
+        return;
+    }
+
+    let created = rooms.rebuild(&mut grid);
+    event.send(RoomsChanged(created));
+}
+
+/// Красота по объектам внутри комнаты, чистота по предметам, лежащим вне складов
+fn update_room_stats(
+    mut rooms:      ResMut<Rooms>,
+    mut event:      EventReader<RoomsChanged>,
+    mut elapsed:    Local<f32>,
+        objects:    Query<(&EntityObject, &Footprint)>,
+        items:      Query<&Transform, With<ItemEntity>>,
+        stockpiles: Query<&Stockpile>,
+        registry:   Res<Registry>,
+        clock:      Res<WorldInfo>,
+) {
+    *elapsed += clock.delta();
+    if event.is_empty() && *elapsed < ROOM_STATS_INTERVAL {
+        return;
+    }
+    event.clear();
+    *elapsed = 0.0;
+
+    let mut beauty: HashMap<u32, f32> = HashMap::new();
+    for (object, footprint) in &objects {
+        let Some(id) = rooms.room_id_at(footprint.0) else {
+            continue;
+        };
+        if let Some(info) = registry.get_object_info(&object.id_name) {
+            *beauty.entry(id).or_default() += info.beauty;
+        }
+    }
+
+    let mut loose: HashMap<u32, usize> = HashMap::new();
+    for transform in &items {
+        let tile = Grid::tile_of(transform.translation.truncate().as_ivec2());
+        let Some(id) = rooms.room_id_at(tile) else {
+            continue;
+        };
+        if !stockpiles.iter().any(|stockpile| stockpile.area.contains(tile)) {
+            *loose.entry(id).or_default() += 1;
+        }
+    }
+
+    for (id, room) in rooms.rooms.iter_mut() {
+        room.stats = RoomStats {
+            beauty:         beauty.get(id).copied().unwrap_or(0.0) / room.size().max(1) as f32,
+            cleanliness:    (1.0 - loose.get(id).copied().unwrap_or(0) as f32 * ITEM_DIRT).max(0.0),
+        };
+    }
+}
+
+/// Отладочная подсветка комнат, клетки без крыши рисуются бледнее
+fn draw_room_overlay(
+    mut gizmos:     Gizmos,
+        rooms:      Res<Rooms>,
+        grid:       Res<Grid>,
+) {
+    if !rooms.overlay {
+        return;
+    }
+
+    for (id, room) in &rooms.rooms {
+        let hue = (*id as f32 * 137.5) % 360.0;
+        for tile in &room.tiles {
+            let alpha = if grid.is_roofed(*tile) { 0.9 } else { 0.35 };
+            gizmos.rect_2d((*tile * 16 + 8).as_vec2(), 0.0, Vec2::splat(14.0), Color::hsla(hue, 0.8, 0.6, alpha));
+        }
+    }
+}
+
+fn reset_rooms(
+    mut rooms:  ResMut<Rooms>,
+) {
+    *rooms = Rooms::default();
+}
@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use bevy_egui::EguiContexts;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    entities::ai::Job::{
+        JobBoard,
+        JobKind,
+        JobPriority
+    },
+    resource::Registry::{
+        Registry,
+        ItemRegistry
+    },
+    world::{
+        World::{
+            WorldSystem,
+            WorldInfo
+        },
+        Grid::Grid,
+        chunk::Chunk::Chunk
+    },
+    ContainerSystem::Inventory,
+    Item::{
+        ItemEntity,
+        ItemInstance
+    },
+    ItemType::ItemType,
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    UserSystem::{
+        CursorMode,
+        CursorPosition
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn stockpile_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<ZonePlacer>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                zone_input,
+                update_zone_ghost,
+                post_haul_jobs
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Как часто предметы на земле проверяются на перенос в склад (в секундах)
+pub const HAUL_SCAN_TIME: f32 = 2.0;
+/// Цвет зоны склада
+pub const STOCKPILE_COLOR: Color = Color::rgba(0.9, 0.8, 0.3, 0.15);
+/// Цвет зоны склада при выделении
+pub const ZONE_GHOST_COLOR: Color = Color::rgba(0.9, 0.8, 0.3, 0.35);
+
+/// Категория предмета для фильтра склада
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    Item,
+    Weapon,
+    Tool,
+    Other,
+}
+
+impl ItemCategory {
+    pub const ALL: [ItemCategory; 4] = [
+        ItemCategory::Item,
+        ItemCategory::Weapon,
+        ItemCategory::Tool,
+        ItemCategory::Other,
+    ];
+
+    pub fn of(item_type: &ItemType) -> Self {
+        match item_type {
+            ItemType::Item(_)   => ItemCategory::Item,
+            ItemType::Weapon(_) => ItemCategory::Weapon,
+            ItemType::Tool(_)   => ItemCategory::Tool,
+            ItemType::None      => ItemCategory::Other,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ItemCategory::Item      => "Items",
+            ItemCategory::Weapon    => "Weapons",
+            ItemCategory::Tool      => "Tools",
+            ItemCategory::Other     => "Other",
+        }
+    }
+}
+
+/// Фильтр склада: принимаются предметы разрешённых категорий и перечисленные по `id_name`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StockpileFilter {
+    pub categories: Vec<ItemCategory>,
+    pub items:      Vec<String>,
+}
+
+impl Default for StockpileFilter {
+    fn default() -> Self {
+        Self {
+            categories: ItemCategory::ALL.to_vec(),
+            items:      Vec::new(),
+        }
+    }
+}
+
+impl StockpileFilter {
+    pub fn accepts(&self, id_name: &str, item_type: &ItemType) -> bool {
+        self.categories.contains(&ItemCategory::of(item_type)) || self.items.iter().any(|item| item == id_name)
+    }
+
+    pub fn toggle(&mut self, category: ItemCategory) {
+        match self.categories.iter().position(|allowed| *allowed == category) {
+            Some(index) => { self.categories.remove(index); },
+            None => self.categories.push(category),
+        }
+    }
+}
+
+/// Зона склада (прямоугольник клеток), предметы лежат в её ячейках - под-клетках 8x8
+#[derive(Component, Clone, Debug)]
+pub struct Stockpile {
+    pub area:       Footprint,
+    pub filter:     StockpileFilter,
+    pub priority:   JobPriority,
+}
+
+impl Stockpile {
+    /// Все ячейки склада
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> {
+        let Footprint(anchor, size) = self.area;
+        (0..size.x * 2).flat_map(move |x| (0..size.y * 2).map(move |y| anchor * 2 + IVec2::new(x, y)))
+    }
+
+    pub fn contains_cell(&self, cell: IVec2) -> bool {
+        self.area.contains(IVec2::new(cell.x.div_euclid(2), cell.y.div_euclid(2)))
+    }
+
+    pub fn accepts(&self, item: &ItemRegistry) -> bool {
+        self.filter.accepts(&item.id_name, &item.item_type)
+    }
+}
+
+/// Положение центра ячейки склада в глобальных координатах
+pub fn cell_position(cell: IVec2) -> Vec2 {
+    (cell * 8 + 4).as_vec2()
+}
+
+/// Сколько единиц предмета ещё помещается в ячейку (с учётом предела стака из реестра)
+pub fn cell_space<'a>(
+    cell:       IVec2,
+    info:       &ItemRegistry,
+    instance:   &ItemInstance,
+    grid:       &Grid,
+    stack_at:   &impl Fn(IVec2) -> Option<&'a ItemEntity>,
+) -> usize {
+    if grid.check_exist_object(&(cell * 8)) {
+        return 0;
+    }
+
+    let stackable = info.stackable.map_or(false, |stackable| stackable.is_stackable());
+    let limit = if stackable { info.stack_size.unwrap_or(usize::MAX) } else { 1 };
+
+    match stack_at(cell) {
+        None => limit,
+        Some(stack) if stackable && stack.id_name == info.id_name && stack.instance == *instance => limit.saturating_sub(stack.count),
+        Some(_) => 0,
+    }
+}
+
+/// Приоритет склада, в котором уже лежит предмет (если склад его принимает)
+pub fn stored_priority<'a>(stockpiles: impl Iterator<Item = &'a Stockpile>, cell: IVec2, info: &ItemRegistry) -> Option<JobPriority> {
+    stockpiles
+        .filter(|stockpile| stockpile.contains_cell(cell) && stockpile.accepts(info))
+        .map(|stockpile| stockpile.priority)
+        .max()
+}
+
+/// Лучшая ячейка для предмета: склад с наибольшим приоритетом (выше `above`), затем ближайшая ячейка
+///
+/// Ячейки из `reserved` уже заняты другими работами переноса и не выбираются
+pub fn find_stockpile_cell<'a, 'b>(
+    stockpiles: impl Iterator<Item = (Entity, &'b Stockpile)>,
+    info:       &ItemRegistry,
+    instance:   &ItemInstance,
+    from:       Vec2,
+    above:      Option<JobPriority>,
+    grid:       &Grid,
+    reserved:   &HashSet<IVec2>,
+    stack_at:   &impl Fn(IVec2) -> Option<&'a ItemEntity>,
+) -> Option<(Entity, IVec2)> {
+    stockpiles
+        .filter(|(_, stockpile)| stockpile.accepts(info) && above.map_or(true, |above| stockpile.priority > above))
+        .flat_map(|(entity, stockpile)| stockpile.cells().map(move |cell| (entity, stockpile.priority, cell)))
+        .filter(|(_, _, cell)| !reserved.contains(cell) && cell_space(*cell, info, instance, grid, stack_at) > 0)
+        .min_by(|a, b| {
+            b.1.cmp(&a.1).then(cell_position(a.2).distance(from).total_cmp(&cell_position(b.2).distance(from)))
+        })
+        .map(|(entity, _, cell)| (entity, cell))
+}
+
+/// Создание зоны склада с подсветкой её клеток
+pub fn spawn_stockpile(commands: &mut Commands, area: Footprint, filter: StockpileFilter, priority: JobPriority) -> Entity {
+    commands.spawn((
+        Stockpile {
+            area,
+            filter,
+            priority,
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                color:          STOCKPILE_COLOR,
+                custom_size:    Some((area.1 * 16).as_vec2()),
+                ..default()
+            },
+            transform: Transform::from_translation(area.center().extend(0.05)),
+            ..default()
+        },
+        Name::new("stockpile")
+    )).id()
+}
+
+/// Выделение зоны в `CursorMode::Zone`
+#[derive(Resource, Default)]
+pub struct ZonePlacer {
+    pub drag_start: Option<IVec2>,  // Клетка, с которой начато выделение
+}
+
+/// Подсветка выделяемой зоны
+#[derive(Component)]
+pub struct ZoneGhost;
+
+/// Прямоугольник клеток между двумя углами
+fn zone_area(start: IVec2, end: IVec2) -> Footprint {
+    let anchor = start.min(end);
+    Footprint(anchor, (start.max(end) - anchor) + IVec2::ONE)
+}
+
+/// Управление режимом зон
+///
+/// ЛКМ с протягиванием - создать склад (на чужие склады не накладывается), ПКМ - удалить склад под курсором,
+/// Esc - выйти из режима
+fn zone_input(
+    mut commands:       Commands,
+    mut cursor_mode:    ResMut<CursorMode>,
+    mut placer:         ResMut<ZonePlacer>,
+    mut contexts:       EguiContexts,
+        stockpiles:     Query<(Entity, &Stockpile)>,
+        cursor:         Res<CursorPosition>,
+        mouse_input:    Res<ButtonInput<MouseButton>>,
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if *cursor_mode != CursorMode::Zone {
+        placer.drag_start = None;
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        placer.drag_start = None;
+        *cursor_mode = CursorMode::None;
+        return;
+    }
+
+    // Нажатия по окнам интерфейса не выделяют зону
+    if contexts.ctx_mut().is_pointer_over_area() && placer.drag_start.is_none() {
+        return;
+    }
+
+    let tile = WorldSystem::get_currect_chunk_tile(cursor.0.as_ivec2());
+
+    if mouse_input.just_pressed(MouseButton::Right) {
+        if placer.drag_start.take().is_none() {
+            for (entity, stockpile) in &stockpiles {
+                if stockpile.area.contains(tile) {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+        }
+        return;
+    }
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        placer.drag_start = Some(tile);
+    }
+
+    if !mouse_input.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(start) = placer.drag_start.take() else {
+        return;
+    };
+
+    let area = zone_area(start, tile);
+    if stockpiles.iter().any(|(_, stockpile)| area.tiles().any(|tile| stockpile.area.contains(tile))) {
+        info!("Stockpile - зона пересекается с другим складом");
+        return;
+    }
+    spawn_stockpile(&mut commands, area, StockpileFilter::default(), JobPriority::Normal);
+}
+
+/// Подсветка выделяемой зоны от начальной клетки до курсора
+fn update_zone_ghost(
+    mut commands:   Commands,
+    mut ghosts:     Query<(&mut Sprite, &mut Transform), With<ZoneGhost>>,
+        entities:   Query<Entity, With<ZoneGhost>>,
+        placer:     Res<ZonePlacer>,
+        cursor:     Res<CursorPosition>,
+) {
+    let Some(start) = placer.drag_start else {
+        for ghost in &entities {
+            commands.entity(ghost).despawn();
+        }
+        return;
+    };
+
+    let area = zone_area(start, WorldSystem::get_currect_chunk_tile(cursor.0.as_ivec2()));
+    let size = Some((area.1 * 16).as_vec2());
+    let translation = area.center().extend(0.9);
+
+    if let Ok((mut sprite, mut transform)) = ghosts.get_single_mut() {
+        sprite.custom_size = size;
+        transform.translation = translation;
+        return;
+    }
+
+    commands.spawn((
+        ZoneGhost,
+        SpriteBundle {
+            sprite: Sprite {
+                color:          ZONE_GHOST_COLOR,
+                custom_size:    size,
+                ..default()
+            },
+            transform: Transform::from_translation(translation),
+            ..default()
+        },
+        Name::new("zone_ghost")
+    ));
+}
+
+/// Постановка работ переноса для предметов на земле
+///
+/// Предмет переносится, если он не лежит на принимающем его складе и для него есть место на складе
+/// (или в хранилище), либо если есть склад с более высоким приоритетом
+fn post_haul_jobs(
+    mut board:      ResMut<JobBoard>,
+    mut timer:      Local<f32>,
+        items:      Query<(Entity, &ItemEntity, &Transform)>,
+        stockpiles: Query<(Entity, &Stockpile)>,
+        storages:   Query<(), (With<EntityObject>, With<Inventory>)>,
+        chunk:      Res<Chunk>,
+        grid:       Res<Grid>,
+        registry:   Res<Registry>,
+        clock:      Res<WorldInfo>,
+) {
+    *timer += clock.delta();
+    if *timer < HAUL_SCAN_TIME {
+        return;
+    }
+    *timer = 0.0;
+
+    let stack_at = |cell: IVec2| chunk.objects_ex.get(&cell).and_then(|entity| items.get(*entity).ok()).map(|(_, item, _)| item);
+    let reserved = board.reserved_cells(None);
+
+    for (entity, item, transform) in &items {
+        if board.find(JobKind::Haul, entity).is_some() {
+            continue;
+        }
+        let Some(info) = registry.get_item_info(&item.id_name) else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+        let stored = stored_priority(stockpiles.iter().map(|(_, stockpile)| stockpile), WorldSystem::get_currect_chunk_subtile(position.as_ivec2()), info);
+        let destination = find_stockpile_cell(stockpiles.iter(), info, &item.instance, position, stored, &grid, &reserved, &stack_at);
+
+        if destination.is_some() || (stored.is_none() && !storages.is_empty()) {
+            board.post(JobKind::Haul, entity, position, JobPriority::Normal, None);
+        }
+    }
+}
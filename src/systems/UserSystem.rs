@@ -21,7 +21,7 @@ use crate::core::{
     AppState,
     world::{
         World::WorldSystem,
-        chunk::Chunk::Chunk
+        Grid::Grid
     },
     Camera::UserCamera,
     Entity::{
@@ -30,8 +30,19 @@ use crate::core::{
         EntitySpawn,
     },
     Item::ItemSpawn,
-    Object::ObjectSpawn,
-    ContainerSystem::Inventory,
+    Object::{
+        ObjectSpawn,
+        PersistentObjectSpawn,
+        Footprint
+    },
+    ObjectAnimation::ObjectDirectionState,
+    ContainerSystem::{
+        Inventory,
+        Equipment,
+        CarryCapacity
+    },
+    CraftSystem::CraftQueue,
+    PlantSystem::PlantSpawn,
 };
 
 #[derive(Component, InspectorOptions, Reflect, Resource)]
@@ -99,12 +110,17 @@ impl User {
             if user.control_entity.is_none() {
                 for (entity_b, transform) in &entity_b {
                     if 8.0 > Vec3::distance(transform.translation, cursor.0.extend(0.5)) {
+                        let capacity = CarryCapacity::default();
+
                         commands.entity(entity_b)
                             .insert(UserControl {
                                 uid: user.uid,
                                 user_name: user.user_name.clone()
                             })
-                            .insert(Inventory::with_capacity(12));
+                            .insert(Inventory::with_capacity(capacity.slots).with_limits(capacity.max_mass, capacity.max_volume))
+                            .insert(capacity)
+                            .insert(Equipment::default())
+                            .insert(CraftQueue::default());
 
                         for (entity_h, head, transform) in &entity_h {
                             if head.parent == entity_b {
@@ -234,6 +250,7 @@ pub enum CursorMode {
     None,
     Placer,
     Build,
+    Zone,
     Atack,
 }
 
@@ -252,8 +269,10 @@ fn placer(
     mut cursor_mode:    ResMut<CursorMode>,
     mut placer:         ResMut<CursorPlacer>,
     mut obj_event:      EventWriter<ObjectSpawn>,
+    mut obj_ct_event:   EventWriter<PersistentObjectSpawn>,
     mut item_event:     EventWriter<ItemSpawn>,
     mut entity_event:   EventWriter<EntitySpawn>,
+    mut plant_event:    EventWriter<PlantSpawn>,
         cursor:         Res<CursorPosition>,
         mouse_input:    Res<ButtonInput<MouseButton>>,
         keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -264,14 +283,20 @@ fn placer(
             if let Some(match_type) = placer.placer.clone() {
                 match match_type.0.as_str() {
                     "item" => {
-                        item_event.send(ItemSpawn(match_type.1, WorldSystem::get_currect_chunk_subtile(cursor.0.as_ivec2()), 1));
+                        item_event.send(ItemSpawn(match_type.1, WorldSystem::get_currect_chunk_subtile(cursor.0.as_ivec2()), 1, None));
                     },
                     "object" => {
-                        obj_event.send(ObjectSpawn(match_type.1, cursor.0.as_ivec2()));
+                        obj_event.send(ObjectSpawn(match_type.1, cursor.0.as_ivec2(), ObjectDirectionState::South));
+                    },
+                    "object_ct" => {
+                        obj_ct_event.send(PersistentObjectSpawn(match_type.1, cursor.0.as_ivec2()));
                     },
                     "entity" => {
                         entity_event.send(EntitySpawn(match_type.1, cursor.0));
                     },
+                    "plant" => {
+                        plant_event.send(PlantSpawn(match_type.1, cursor.0.as_ivec2()));
+                    },
                     _ => warn!("Неверный указанный тип!")
                 }
             }
@@ -446,15 +471,20 @@ pub fn update_select_box(
 // Test
 // ==============================
 
+/// Удаление объекта под курсором со всех его клеток
 fn delete_object(
     mut commands:       Commands,
         cursor:         Res<CursorPosition>,
         keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut chunk_res:      ResMut<Chunk>
+    mut grid:           ResMut<Grid>,
+        footprints:     Query<&Footprint>,
 ) {
     if keyboard_input.just_pressed(KeyCode::KeyT) {
-        let tiled_pos = WorldSystem::get_currect_chunk_tile(cursor.0.as_ivec2());
-        if let Some(entity) = chunk_res.remove_object(&tiled_pos) {
+        let coord = cursor.0.as_ivec2();
+        if let Some(entity) = grid.object_at(&coord) {
+            let footprint = footprints.get(entity).copied().unwrap_or(Footprint::single(Grid::tile_of(coord)));
+
+            grid.remove_object(entity, &footprint);
             commands.entity(entity).despawn_recursive();
         }
     }
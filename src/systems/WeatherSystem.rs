@@ -0,0 +1,524 @@
+use std::f32::consts::{
+    PI,
+    TAU
+};
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*
+};
+
+use rand::Rng;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    resource::{
+        graphic::Atlas::{
+            AtlasRes,
+            AtlasType
+        },
+        Registry::{
+            Registry,
+            WeatherRegistry
+        }
+    },
+    world::{
+        World::WorldInfo,
+        Grid::Grid
+    },
+    Camera::UserCamera,
+    Entity::EntityBase,
+    LightSystem::LIGHT_LAYER_Z,
+    TimeCycle::Season,
+    AppState
+};
+
+//
+//
+//
+
+pub fn weather_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<Weather>()
+        // Init Events
+        .add_event::<SetWeather>()
+        .add_event::<WeatherChanged>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                set_weather,
+                update_weather,
+                update_exposure,
+                spawn_weather_particles,
+                update_weather_particles
+            ).chain().run_if(in_state(AppState::Game))
+        )
+        .add_systems(OnExit(AppState::Game), reset_weather);
+}
+
+//
+//
+//
+
+/// Погода нового мира
+pub const DEFAULT_WEATHER: &str = "clear";
+/// Игровых часов на плавную смену погоды
+pub const WEATHER_BLEND_HOURS: f32 = 1.0;
+/// Температура, к которой стремится помещение под крышей
+pub const INDOOR_TEMPERATURE: f32 = 18.0;
+/// Насколько крыша сглаживает уличную температуру (0 - 1)
+pub const ROOF_INSULATION: f32 = 0.6;
+/// На сколько градусов холоднее ощущается полностью мокрая сущность
+pub const WET_CHILL: f32 = 6.0;
+/// Высыхание за игровой час в укрытии или без осадков
+pub const DRY_RATE: f32 = 0.5;
+/// Замедление полностью мокрой сущности
+pub const WETNESS_SLOWDOWN: f32 = 0.15;
+/// Предел частиц погоды на экране
+pub const MAX_WEATHER_PARTICLES: usize = 600;
+/// Частицы погоды рисуются под слоем освещения
+pub const WEATHER_PARTICLE_Z: f32 = LIGHT_LAYER_Z - 0.5;
+
+/// Биом мира, задаёт базовую температуру и вероятности погоды
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum Biome {
+    #[default]
+    Temperate,
+    Desert,
+    Tundra,
+}
+
+impl Biome {
+    pub const ALL: [Biome; 3] = [
+        Biome::Temperate,
+        Biome::Desert,
+        Biome::Tundra,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Biome::Temperate    => "Temperate",
+            Biome::Desert       => "Desert",
+            Biome::Tundra       => "Tundra",
+        }
+    }
+
+    /// Средняя температура времени года
+    pub fn temperature(&self, season: Season) -> f32 {
+        match (self, season) {
+            (Biome::Temperate, Season::Spring)  => 12.0,
+            (Biome::Temperate, Season::Summer)  => 22.0,
+            (Biome::Temperate, Season::Autumn)  => 10.0,
+            (Biome::Temperate, Season::Winter)  => -5.0,
+            (Biome::Desert, Season::Spring)     => 26.0,
+            (Biome::Desert, Season::Summer)     => 36.0,
+            (Biome::Desert, Season::Autumn)     => 24.0,
+            (Biome::Desert, Season::Winter)     => 12.0,
+            (Biome::Tundra, Season::Spring)     => -6.0,
+            (Biome::Tundra, Season::Summer)     => 8.0,
+            (Biome::Tundra, Season::Autumn)     => -8.0,
+            (Biome::Tundra, Season::Winter)     => -26.0,
+        }
+    }
+
+    /// Разница между температурой дня и ночи от средней
+    pub fn daily_swing(&self) -> f32 {
+        match self {
+            Biome::Temperate    => 6.0,
+            Biome::Desert       => 12.0,
+            Biome::Tundra       => 4.0,
+        }
+    }
+}
+
+/// Вид погоды, определяет поведение частиц
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Storm,
+    Snow,
+    Fog,
+}
+
+/// Текущая погода, смена плавная: `blend` - доля новой погоды (0 - 1)
+#[derive(Resource, Clone, Debug)]
+pub struct Weather {
+    pub current:    String,
+    pub previous:   Option<String>,
+    pub remaining:  f32,            // Игровых часов до смены погоды
+    pub blend:      f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            current:    DEFAULT_WEATHER.to_string(),
+            previous:   None,
+            remaining:  6.0,
+            blend:      1.0,
+        }
+    }
+}
+
+impl Weather {
+    /// Значение, смешанное между прошлой и текущей погодой
+    fn mix(&self, registry: &Registry, value: impl Fn(&WeatherRegistry) -> f32) -> f32 {
+        let current = registry.get_weather_info(&self.current).map_or(0.0, &value);
+        let previous = self.previous.as_ref()
+            .and_then(|previous| registry.get_weather_info(previous))
+            .map_or(0.0, &value);
+
+        previous + (current - previous) * self.blend
+    }
+
+    pub fn temperature(&self, registry: &Registry) -> f32 {
+        self.mix(registry, |info| info.temperature)
+    }
+
+    pub fn darkness(&self, registry: &Registry) -> f32 {
+        self.mix(registry, |info| info.darkness).clamp(0.0, 1.0)
+    }
+
+    pub fn wetness(&self, registry: &Registry) -> f32 {
+        self.mix(registry, |info| info.wetness).max(0.0)
+    }
+
+    pub fn slowdown(&self, registry: &Registry) -> f32 {
+        self.mix(registry, |info| info.slowdown).clamp(0.0, 1.0)
+    }
+
+    /// Случайная следующая погода по весам переходов, биому и времени года
+    pub fn pick_next(&self, registry: &Registry, biome: Biome, season: Season) -> String {
+        let weight = |id_name: &String| registry.get_weather_info(id_name).map_or(0.0, |info| info.weight(biome, season));
+
+        let mut options: Vec<(&String, f32)> = registry.get_weather_info(&self.current)
+            .map(|info| info.transitions.iter().map(|(id_name, chance)| (id_name, chance.max(0.0) * weight(id_name))).collect())
+            .unwrap_or_default();
+
+        // Без подходящих переходов выбирается любая погода, возможная в биоме
+        if options.iter().all(|(_, chance)| *chance <= 0.0) {
+            options = registry.weather_registry.keys().map(|id_name| (id_name, weight(id_name))).collect();
+        }
+
+        let total: f32 = options.iter().map(|(_, chance)| chance).sum();
+        if total <= 0.0 {
+            return DEFAULT_WEATHER.to_string();
+        }
+
+        let mut roll = rand::thread_rng().gen_range(0.0..total);
+        for (id_name, chance) in &options {
+            if roll < *chance {
+                return (*id_name).clone();
+            }
+            roll -= chance;
+        }
+        options.last().map_or(DEFAULT_WEATHER.to_string(), |(id_name, _)| (*id_name).clone())
+    }
+
+    /// Начало новой погоды со случайной длительностью
+    pub fn begin(&mut self, id_name: String, registry: &Registry) -> bool {
+        let Some(info) = registry.get_weather_info(&id_name) else {
+            warn!("В регистре не найдена погода - {}", id_name);
+            return false;
+        };
+
+        self.remaining = rand::thread_rng().gen_range(info.duration.0..=info.duration.1);
+        self.previous = Some(std::mem::replace(&mut self.current, id_name));
+        self.blend = 0.0;
+        true
+    }
+}
+
+/// Температура без учёта погоды: биом, время года и суток
+pub fn base_temperature(clock: &WorldInfo) -> f32 {
+    let biome = clock.biome;
+    // Холоднее всего в 03:00, теплее всего в 15:00
+    let daily = -(TAU * (clock.daytime - 0.125)).cos();
+
+    biome.temperature(clock.season()) + biome.daily_swing() * daily
+}
+
+/// Погодные условия на клетках: температура, укрытие, намокание
+#[derive(SystemParam)]
+pub struct Climate<'w> {
+    weather:    Res<'w, Weather>,
+    registry:   Res<'w, Registry>,
+    clock:      Res<'w, WorldInfo>,
+    grid:       Res<'w, Grid>,
+}
+
+impl<'w> Climate<'w> {
+    pub fn weather(&self) -> &Weather {
+        &self.weather
+    }
+
+    pub fn base_temperature(&self) -> f32 {
+        base_temperature(&self.clock)
+    }
+
+    pub fn outdoor_temperature(&self) -> f32 {
+        self.base_temperature() + self.weather.temperature(&self.registry)
+    }
+
+    pub fn is_sheltered(&self, tile: IVec2) -> bool {
+        self.grid.is_roofed(tile)
+    }
+
+    /// Температура клетки, под крышей погода не действует и температура ближе к комнатной
+    pub fn temperature_at(&self, tile: IVec2) -> f32 {
+        if !self.is_sheltered(tile) {
+            return self.outdoor_temperature();
+        }
+        let base = self.base_temperature();
+        base + (INDOOR_TEMPERATURE - base) * ROOF_INSULATION
+    }
+
+    /// Намокание на клетке за игровой час
+    pub fn wetness_at(&self, tile: IVec2) -> f32 {
+        match self.is_sheltered(tile) {
+            true  => 0.0,
+            false => self.weather.wetness(&self.registry),
+        }
+    }
+
+    /// Замедление от погоды на клетке (0 - 1)
+    pub fn slowdown_at(&self, tile: IVec2) -> f32 {
+        match self.is_sheltered(tile) {
+            true  => 0.0,
+            false => self.weather.slowdown(&self.registry),
+        }
+    }
+}
+
+/// Воздействие погоды на сущность, по нему потребности считают тепло и комфорт
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct WeatherExposure {
+    pub temperature:    f32,
+    pub wetness:        f32,    // 0 - 1
+    pub slowdown:       f32,    // Замедление погодой (0 - 1)
+    pub sheltered:      bool,
+}
+
+impl WeatherExposure {
+    /// Ощущаемая температура, мокрой сущности холоднее
+    pub fn feels_like(&self) -> f32 {
+        self.temperature - self.wetness * WET_CHILL
+    }
+
+    /// Множитель скорости передвижения
+    pub fn speed_factor(&self) -> f32 {
+        (1.0 - self.slowdown) * (1.0 - WETNESS_SLOWDOWN * self.wetness)
+    }
+}
+
+/// Частица погоды
+#[derive(Component, Clone, Copy, Debug)]
+pub struct WeatherParticle {
+    pub kind:       WeatherKind,
+    pub velocity:   Vec2,
+    pub lifetime:   f32,
+    pub total:      f32,
+    pub alpha:      f32,
+}
+
+/// Ивент принудительной смены погоды (0 - Погода)
+#[derive(Event)]
+pub struct SetWeather(pub String);
+
+/// Ивент смены погоды (0 - Новая погода)
+#[derive(Event)]
+pub struct WeatherChanged(pub String);
+
+fn reset_weather(
+    mut commands:   Commands,
+    mut weather:    ResMut<Weather>,
+        particles:  Query<Entity, With<WeatherParticle>>,
+) {
+    for entity in &particles {
+        commands.entity(entity).despawn_recursive();
+    }
+    *weather = Weather::default();
+}
+
+fn set_weather(
+    mut weather:        ResMut<Weather>,
+    mut event:          EventReader<SetWeather>,
+    mut changed_event:  EventWriter<WeatherChanged>,
+        registry:       Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        if weather.begin(event.0.clone(), &registry) {
+            changed_event.send(WeatherChanged(event.0.clone()));
+        }
+    }
+}
+
+/// Плавная смена погоды и выбор следующей по истечении длительности
+fn update_weather(
+    mut weather:        ResMut<Weather>,
+    mut changed_event:  EventWriter<WeatherChanged>,
+        registry:       Res<Registry>,
+        clock:          Res<WorldInfo>,
+) {
+    let hours = clock.delta_hours();
+    if hours <= 0.0 {
+        return;
+    }
+
+    weather.blend = (weather.blend + hours / WEATHER_BLEND_HOURS).min(1.0);
+    weather.remaining -= hours;
+    if weather.remaining > 0.0 {
+        return;
+    }
+
+    let next = weather.pick_next(&registry, clock.biome, clock.season());
+    if weather.begin(next.clone(), &registry) {
+        info!("Weather - погода сменилась на {}", next);
+        changed_event.send(WeatherChanged(next));
+    } else {
+        weather.remaining = WEATHER_BLEND_HOURS;
+    }
+}
+
+/// Температура, намокание и замедление сущностей по их клеткам
+fn update_exposure(
+    mut commands:   Commands,
+    mut entities:   Query<(Entity, &Transform, Option<&mut WeatherExposure>), With<EntityBase>>,
+        climate:    Climate,
+) {
+    let hours = climate.clock.delta_hours();
+
+    for (entity, transform, exposure) in &mut entities {
+        let tile = Grid::tile_of(transform.translation.truncate().as_ivec2());
+        let temperature = climate.temperature_at(tile);
+        let wetting = climate.wetness_at(tile);
+
+        let Some(mut exposure) = exposure else {
+            commands.entity(entity).insert(WeatherExposure {
+                temperature,
+                sheltered: climate.is_sheltered(tile),
+                slowdown: climate.slowdown_at(tile),
+                ..default()
+            });
+            continue;
+        };
+
+        // Под дождём сущность мокнет, иначе сохнет, в тепле быстрее
+        exposure.wetness = match wetting > 0.0 {
+            true  => exposure.wetness + wetting * hours,
+            false => exposure.wetness - DRY_RATE * (1.0 + temperature.max(0.0) / 20.0) * hours,
+        }.clamp(0.0, 1.0);
+        exposure.temperature = temperature;
+        exposure.sheltered = climate.is_sheltered(tile);
+        exposure.slowdown = climate.slowdown_at(tile);
+    }
+}
+
+/// Частицы текущей и уходящей погоды появляются в пределах камеры
+fn spawn_weather_particles(
+    mut commands:   Commands,
+    mut pending:    Local<f32>,
+        particles:  Query<(), With<WeatherParticle>>,
+        camera:     Query<(&GlobalTransform, &OrthographicProjection), With<UserCamera>>,
+        weather:    Res<Weather>,
+        registry:   Res<Registry>,
+        atlas:      Res<AtlasRes>,
+        clock:      Res<WorldInfo>,
+) {
+    let Ok((camera_transform, projection)) = camera.get_single() else {
+        return;
+    };
+    if clock.delta() <= 0.0 {
+        return;
+    }
+
+    let area = Rect::from_center_size(
+        camera_transform.translation().truncate() + projection.area.center(),
+        projection.area.size()
+    );
+    let mut count = particles.iter().count();
+    let mut rng = rand::thread_rng();
+
+    let sources = [(Some(&weather.current), weather.blend), (weather.previous.as_ref(), 1.0 - weather.blend)];
+    for (id_name, share) in sources {
+        let Some(info) = id_name.and_then(|id_name| registry.get_weather_info(id_name)) else {
+            continue;
+        };
+        let Some(particle) = &info.particle else {
+            continue;
+        };
+
+        *pending += particle.rate * share * clock.delta();
+        while *pending >= 1.0 && count < MAX_WEATHER_PARTICLES {
+            *pending -= 1.0;
+            count += 1;
+
+            let Some(mut sprite) = atlas.get_spritesheet(AtlasType::Particle, &particle.id_texture) else {
+                break;
+            };
+
+            // Падающие частицы появляются над экраном, остальные - по всей площади
+            let velocity = Vec2::new(particle.velocity.0, particle.velocity.1)
+                * (1.0 + rng.gen_range(-1.0..=1.0) * particle.spread.clamp(0.0, 1.0));
+            let x = rng.gen_range(area.min.x..=area.max.x) - velocity.x * particle.lifetime * 0.5;
+            let y = match velocity.y < 0.0 {
+                true  => area.max.y + rng.gen_range(0.0..=16.0),
+                false => rng.gen_range(area.min.y..=area.max.y),
+            };
+            let alpha = 1.0 - particle.transparency.clamp(0.0, 1.0);
+
+            sprite.transform.translation = Vec3::new(x, y, WEATHER_PARTICLE_Z);
+            sprite.sprite.color.set_a(alpha);
+            commands.spawn((
+                sprite,
+                WeatherParticle {
+                    kind:       info.kind,
+                    velocity,
+                    lifetime:   particle.lifetime,
+                    total:      particle.lifetime,
+                    alpha,
+                },
+            ));
+        }
+    }
+    *pending = pending.min(1.0);
+}
+
+/// Движение частиц: снег раскачивается, туман плавно появляется и исчезает
+fn update_weather_particles(
+    mut commands:   Commands,
+    mut particles:  Query<(Entity, &mut WeatherParticle, &mut Transform, &mut Sprite)>,
+        clock:      Res<WorldInfo>,
+) {
+    let delta = clock.delta();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (entity, mut particle, mut transform, mut sprite) in &mut particles {
+        particle.lifetime -= delta;
+        if particle.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let age = 1.0 - particle.lifetime / particle.total.max(0.01);
+        let mut velocity = particle.velocity;
+        match particle.kind {
+            WeatherKind::Snow   => velocity.x += (age * TAU * 2.0 + entity.index() as f32).sin() * 12.0,
+            WeatherKind::Fog    => { sprite.color.set_a(particle.alpha * (age * PI).sin()); },
+            _ => {}
+        }
+        transform.translation += (velocity * delta).extend(0.0);
+    }
+}
@@ -29,8 +29,18 @@ use crate::
             EntityNeutrality
         },
         EntityAnimation::EntityDirectionState,
-        stats::Stats,
+        stats::{
+            Stats,
+            stats_plugin
+        },
+        skills::{
+            skills_plugin,
+            Dashing
+        },
         Missile::{update_bullet_hits, update_bullets},
+        world::World::WorldInfo,
+        WeatherSystem::WeatherExposure,
+        Attributes::Speed as SpeedBonus,
         AppState
     };
 
@@ -61,13 +71,13 @@ pub struct Enemy;
 
 pub fn update_spawning(
     mut spawner_query: Query<(&mut EnemySpawner, &Transform)>,
-    time: Res<Time>,
+    clock: Res<WorldInfo>,
     handle: Res<TestTextureAtlas>,
     mut commands: Commands,
 ) {
     for (mut spawner, trans) in spawner_query.iter_mut() {
         if spawner.is_active {
-            spawner.timer -= time.delta_seconds();
+            spawner.timer -= clock.delta();
             if spawner.timer <= 0. {
 
                 spawner.timer = spawner.cooldown;
@@ -157,7 +167,7 @@ impl Plugin for EntitySystem {
             .add_event::<DirectionChangeEvent>()
             .add_event::<MovementEntity>()
             // Init Plugins
-            
+            .add_plugins((stats_plugin, skills_plugin))
             // Init Systems
             .add_systems(
                 Update,
@@ -219,8 +229,10 @@ fn handle_move(
     mut query: Query<(
         &mut EntityBase, 
         &mut Transform,
-        &mut Velocity
-    )>,
+        &mut Velocity,
+        Option<&WeatherExposure>,
+        Option<&SpeedBonus>
+    ), Without<Dashing>>,
     mut event:      EventReader<MovementEntity>,
 ) {
     if event.is_empty() {
@@ -228,10 +240,13 @@ fn handle_move(
     }
 
     for event in event.read() {
-        if let Ok((mut entity_base, mut transform, mut velocity)) = query.get_mut(event.0) {
+        if let Ok((mut entity_base, mut transform, mut velocity, exposure, bonus)) = query.get_mut(event.0) {
             if event.1 != Vec3::ZERO {
                 let move_var = event.1 / event.1.length();
-                velocity.linvel = move_var.truncate() * event.2;
+                // Погода и намокание замедляют передвижение, атрибут `Speed` ускоряет (в процентах)
+                let speed_factor = exposure.map_or(1.0, |exposure| exposure.speed_factor())
+                    * bonus.map_or(1.0, |bonus| (1.0 + bonus.0 as f32 / 100.0).max(0.0));
+                velocity.linvel = move_var.truncate() * event.2 * speed_factor;
 
                 entity_base.position = Position(transform.translation.truncate());
             }
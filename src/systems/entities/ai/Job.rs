@@ -0,0 +1,1207 @@
+use std::{
+    collections::{
+        HashMap,
+        HashSet
+    },
+    sync::Arc
+};
+
+use bevy::prelude::*;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    entities::ai::{
+        Brain,
+        Pawn,
+        Path::{
+            AiPath,
+            PathFailed,
+            PathfindingTask,
+            spawn_optimized_pathfinding_task
+        }
+    },
+    resource::Registry::Registry,
+    world::{
+        World::{
+            WorldSystem,
+            WorldInfo
+        },
+        Grid::{
+            Grid,
+            GridLocation
+        },
+        chunk::Chunk::Chunk
+    },
+    BuildSystem::{
+        Blueprint,
+        BuildWork
+    },
+    StockpileSystem::{
+        Stockpile,
+        cell_position,
+        cell_space,
+        find_stockpile_cell,
+        stored_priority
+    },
+    ContainerSystem::{
+        Inventory,
+        Equipment,
+        Slot,
+        drop_slots
+    },
+    CraftSystem::{
+        give_items,
+        take_ingredients
+    },
+    Entity::EntityBase,
+    EntityType::EntityNeutrality,
+    Item::{
+        ItemEntity,
+        ItemSpawn
+    },
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    ObjType::{
+        Harvestable,
+        PlantObject
+    },
+    PlantSystem::{
+        HarvestPlant,
+        has_tool
+    },
+    interact::Interact::{
+        Interactable,
+        InteractionKind,
+        ReleaseObject,
+        Reserved,
+        UsingObject
+    },
+    stats::{
+        ExperienceGain,
+        XpSource
+    },
+    UserSystem::UserControl,
+    AppState
+};
+
+//
+//
+//
+
+pub fn job_plugin(app: &mut App) {
+    app
+        // Init Resources
+        .init_resource::<JobBoard>()
+        // Init Events
+        .add_event::<PostJob>()
+        .add_event::<CancelJob>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                post_jobs,
+                cleanup_jobs,
+                drop_stale_cargo,
+                claim_jobs,
+                move_to_job,
+                work_construct,
+                work_haul,
+                work_deconstruct,
+                work_harvest,
+                work_craft
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Максимальная дистанция, на которой рабочий берёт работу
+pub const JOB_MAX_DISTANCE: f32 = 512.0;
+/// Радиус взаимодействия рабочих (должен покрывать соседние с объектом клетки)
+pub const PAWN_INTERACTION_RADIUS: f32 = 24.0;
+/// Через сколько секунд рабочие снова пробуют недоступные им работы
+pub const JOB_RETRY_TIME: f32 = 10.0;
+/// Время разбора объекта без рецепта постройки
+pub const DECONSTRUCT_TIME: f32 = 3.0;
+/// Приоритет работы у рабочего по умолчанию (1 - высший, 4 - низший, 0 - не выполняет)
+pub const WORK_PRIORITY_DEFAULT: u8 = 3;
+pub const WORK_PRIORITY_MAX: u8 = 4;
+
+/// Тип работы
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// Доставка материалов и постройка чертежа
+    Construct,
+    /// Перенос предмета с земли в хранилище
+    Haul,
+    /// Разбор объекта с возвратом материалов
+    Deconstruct,
+    /// Крафт на рабочем месте из его хранилища
+    Craft,
+    /// Сбор урожая
+    Harvest,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 5] = [
+        JobKind::Construct,
+        JobKind::Haul,
+        JobKind::Deconstruct,
+        JobKind::Craft,
+        JobKind::Harvest,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobKind::Construct      => "Construct",
+            JobKind::Haul           => "Haul",
+            JobKind::Deconstruct    => "Deconstruct",
+            JobKind::Craft          => "Craft",
+            JobKind::Harvest        => "Harvest",
+        }
+    }
+}
+
+/// Приоритет работы на доске
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl JobPriority {
+    pub fn raise(self) -> Self {
+        match self {
+            JobPriority::Low    => JobPriority::Normal,
+            JobPriority::Normal => JobPriority::High,
+            _                   => JobPriority::Urgent,
+        }
+    }
+
+    pub fn lower(self) -> Self {
+        match self {
+            JobPriority::Urgent => JobPriority::High,
+            JobPriority::High   => JobPriority::Normal,
+            _                   => JobPriority::Low,
+        }
+    }
+}
+
+/// Работа на доске
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub id:         u64,
+    pub kind:       JobKind,
+    pub target:     Entity,
+    pub position:   Vec2,
+    pub priority:   JobPriority,
+    pub recipe:     Option<String>,     // Рецепт для `JobKind::Craft`
+    pub destination: Option<IVec2>,     // Зарезервированная ячейка склада, куда несут предмет (`JobKind::Haul`)
+    pub haul_to:    Option<(Entity, Vec2)>, // Склад или хранилище, выбранные для переноса, и их положение
+    pub claimed:    Option<Entity>,     // Рабочий, взявший работу
+    pub blocked:    Vec<Entity>,        // Рабочие, которые не смогли выполнить работу
+    pub progress:   f32,                // Выполненная работа в секундах
+    pub started:    bool,               // Ресурсы для работы уже изъяты
+}
+
+impl Job {
+    /// Может ли рабочий взять работу
+    pub fn available_for(&self, pawn: Entity) -> bool {
+        self.claimed.is_none() && !self.blocked.contains(&pawn)
+    }
+
+    /// Снятие рабочего, место переноса, выбранное до подъёма предмета, освобождается для следующего рабочего
+    pub fn unclaim(&mut self) {
+        self.claimed = None;
+        if !self.started {
+            self.destination = None;
+            self.haul_to = None;
+        }
+    }
+}
+
+/// Общая доска работ колонии
+#[derive(Resource, Default)]
+pub struct JobBoard {
+    pub jobs:   Vec<Job>,
+    next_id:    u64,
+}
+
+impl JobBoard {
+    /// Добавление работы, одинаковая работа над той же целью не дублируется
+    pub fn post(&mut self, kind: JobKind, target: Entity, position: Vec2, priority: JobPriority, recipe: Option<String>) -> Option<u64> {
+        if self.jobs.iter().any(|job| job.kind == kind && job.target == target && job.recipe == recipe) {
+            return None;
+        }
+
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id:         self.next_id,
+            kind,
+            target,
+            position,
+            priority,
+            recipe,
+            destination: None,
+            haul_to:    None,
+            claimed:    None,
+            blocked:    Vec::new(),
+            progress:   0.0,
+            started:    false,
+        });
+        Some(self.next_id)
+    }
+
+    pub fn find(&self, kind: JobKind, target: Entity) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.kind == kind && job.target == target)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    /// Резервация работы рабочим, занятую работу взять нельзя
+    pub fn claim(&mut self, id: u64, pawn: Entity) -> bool {
+        match self.get_mut(id) {
+            Some(job) if job.available_for(pawn) => {
+                job.claimed = Some(pawn);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Отказ рабочего от работы, `blocked` - работа недоступна ему до повторной попытки
+    pub fn release(&mut self, id: u64, pawn: Entity, blocked: bool) {
+        if let Some(job) = self.get_mut(id) {
+            if job.claimed == Some(pawn) {
+                job.unclaim();
+            }
+            if blocked && !job.blocked.contains(&pawn) {
+                job.blocked.push(pawn);
+            }
+        }
+    }
+
+    pub fn finish(&mut self, id: u64) {
+        self.jobs.retain(|job| job.id != id);
+    }
+
+    /// Ячейки складов, зарезервированные работами переноса (кроме работы `except`)
+    pub fn reserved_cells(&self, except: Option<u64>) -> HashSet<IVec2> {
+        self.jobs.iter()
+            .filter(|job| job.kind == JobKind::Haul && Some(job.id) != except)
+            .filter_map(|job| job.destination)
+            .collect()
+    }
+
+    pub fn cancel(&mut self, kind: JobKind, target: Entity) {
+        self.jobs.retain(|job| job.kind != kind || job.target != target);
+    }
+}
+
+/// Приоритеты работ рабочего (1 - высший, 4 - низший, 0 - не выполняет)
+#[derive(Component, Clone, Debug)]
+pub struct WorkPriorities(pub HashMap<JobKind, u8>);
+
+impl Default for WorkPriorities {
+    fn default() -> Self {
+        Self(JobKind::ALL.iter().map(|kind| (*kind, WORK_PRIORITY_DEFAULT)).collect())
+    }
+}
+
+impl WorkPriorities {
+    pub fn get(&self, kind: JobKind) -> u8 {
+        self.0.get(&kind).copied().unwrap_or(WORK_PRIORITY_DEFAULT)
+    }
+
+    /// Переключение приоритета по кругу 1 -> 2 -> 3 -> 4 -> 0 -> 1
+    pub fn cycle(&mut self, kind: JobKind) {
+        let next = (self.get(kind) + 1) % (WORK_PRIORITY_MAX + 1);
+        self.0.insert(kind, next);
+    }
+}
+
+/// Работа, которую выполняет рабочий
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CurrentJob {
+    pub id:     u64,
+    pub fetch:  Option<Entity>,     // Хранилище, из которого рабочий берёт материалы
+}
+
+/// Предметы, которые рабочий несёт по работе переноса (лежат в его инвентаре)
+#[derive(Component, Clone, Debug)]
+pub struct HaulCargo(pub Slot);
+
+/// Ивент добавления работы на доску (0 - Тип | 1 - Цель | 2 - Рецепт)
+#[derive(Event)]
+pub struct PostJob(pub JobKind, pub Entity, pub Option<String>);
+
+/// Ивент снятия работы с доски (0 - Тип | 1 - Цель)
+#[derive(Event)]
+pub struct CancelJob(pub JobKind, pub Entity);
+
+/// Положение цели работы, у объекта - центр его клеток
+fn target_position(targets: &Query<(&Transform, Option<&Footprint>)>, target: Entity) -> Option<Vec2> {
+    targets.get(target).ok().map(|(transform, footprint)| {
+        footprint.map_or(transform.translation.truncate(), |footprint| footprint.center())
+    })
+}
+
+/// Отказ от работы со сбросом состояния рабочего
+fn abandon_job(commands: &mut Commands, board: &mut JobBoard, pawn: Entity, brain: &mut Brain, id: u64, blocked: bool) {
+    board.release(id, pawn, blocked);
+    commands.entity(pawn).remove::<CurrentJob>();
+    brain.reset();
+}
+
+/// Ближайшая к `from` проходимая клетка вокруг объекта
+pub fn approach_tile(grid: &Grid, footprint: &Footprint, from: Vec2, faction: Option<EntityNeutrality>) -> Option<IVec2> {
+    let Footprint(anchor, size) = *footprint;
+
+    (anchor.x - 1..=anchor.x + size.x)
+        .flat_map(|x| (anchor.y - 1..=anchor.y + size.y).map(move |y| IVec2::new(x, y)))
+        .filter(|tile| !footprint.contains(*tile))
+        .filter(|tile| grid.step_cost(&(*tile * 16 + 8), faction).is_some())
+        .min_by(|a, b| {
+            let a = (*a * 16 + 8).as_vec2().distance(from);
+            let b = (*b * 16 + 8).as_vec2().distance(from);
+            a.total_cmp(&b)
+        })
+}
+
+/// Добавление и снятие работ, чертежи сразу получают работу постройки
+///
+/// Работы переноса ставит `StockpileSystem`
+fn post_jobs(
+    mut board:          ResMut<JobBoard>,
+    mut post_event:     EventReader<PostJob>,
+    mut cancel_event:   EventReader<CancelJob>,
+        blueprints:     Query<Entity, Added<Blueprint>>,
+        targets:        Query<(&Transform, Option<&Footprint>)>,
+) {
+    for blueprint in &blueprints {
+        if let Some(position) = target_position(&targets, blueprint) {
+            board.post(JobKind::Construct, blueprint, position, JobPriority::Normal, None);
+        }
+    }
+
+    for event in post_event.read() {
+        let Some(position) = target_position(&targets, event.1) else {
+            continue;
+        };
+        board.post(event.0, event.1, position, JobPriority::Normal, event.2.clone());
+    }
+
+    for event in cancel_event.read() {
+        board.cancel(event.0, event.1);
+    }
+}
+
+/// Удаление работ с уничтоженной целью и освобождение работ, брошенных рабочими
+///
+/// Рабочий, которым стал управлять игрок, бросает свою работу
+fn cleanup_jobs(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut pawns:      Query<(Entity, &CurrentJob, &mut Brain, Has<UserControl>)>,
+    mut retry:      Local<f32>,
+        existing:   Query<()>,
+        clock:      Res<WorldInfo>,
+) {
+    board.jobs.retain(|job| existing.contains(job.target));
+
+    for job in board.jobs.iter_mut() {
+        let Some(pawn) = job.claimed else {
+            continue;
+        };
+        if !pawns.get(pawn).map_or(false, |(_, current, _, user)| current.id == job.id && !user) {
+            job.unclaim();
+        }
+    }
+
+    // Начатый перенос без рабочего снимается вместе с резервацией ячейки, груз рабочий выкладывает рядом
+    board.jobs.retain(|job| job.kind != JobKind::Haul || !job.started || job.claimed.is_some());
+
+    for (pawn, current, mut brain, user) in &mut pawns {
+        if user || board.get(current.id).is_none() {
+            commands.entity(pawn).remove::<CurrentJob>();
+            brain.reset();
+        }
+    }
+
+    *retry += clock.delta();
+    if *retry >= JOB_RETRY_TIME {
+        *retry = 0.0;
+        for job in board.jobs.iter_mut() {
+            job.blocked.clear();
+        }
+    }
+}
+
+/// Рабочий, потерявший работу переноса (цель уничтожена или работа снята), выкладывает груз рядом,
+/// а лежащий на земле предмет снова получает работу переноса
+fn drop_stale_cargo(
+    mut commands:   Commands,
+    mut pawns:      Query<(Entity, &HaulCargo, &mut Inventory, &Transform, Option<&CurrentJob>)>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+        board:      Res<JobBoard>,
+) {
+    for (pawn, cargo, mut inventory, transform, current) in &mut pawns {
+        let hauling = current.and_then(|current| board.get(current.id)).map_or(false, |job| job.kind == JobKind::Haul);
+        if hauling {
+            continue;
+        }
+
+        drop_slots(inventory.take_matching(&cargo.0), transform.translation.truncate().as_ivec2(), &mut spawn_i);
+        commands.entity(pawn).remove::<HaulCargo>();
+    }
+}
+
+/// Свободные рабочие берут работу по своим приоритетам, затем по приоритету работы и расстоянию
+fn claim_jobs(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut pawns:      Query<(Entity, &Transform, &WorkPriorities, &mut Brain), (With<Pawn>, Without<CurrentJob>, Without<UserControl>)>,
+) {
+    if board.jobs.is_empty() {
+        return;
+    }
+
+    for (pawn, transform, priorities, mut brain) in &mut pawns {
+        if brain.machine().is_some() {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let best = board.jobs.iter()
+            .filter(|job| job.available_for(pawn) && priorities.get(job.kind) > 0)
+            .map(|job| (job, job.position.distance(position)))
+            .filter(|(_, distance)| *distance <= JOB_MAX_DISTANCE)
+            .min_by(|(a, a_distance), (b, b_distance)| {
+                priorities.get(a.kind).cmp(&priorities.get(b.kind))
+                    .then(b.priority.cmp(&a.priority))
+                    .then(a_distance.total_cmp(b_distance))
+            })
+            .map(|(job, _)| job.id);
+
+        if let Some(id) = best {
+            if board.claim(id, pawn) {
+                commands.entity(pawn).insert(CurrentJob { id, fetch: None });
+                brain.work(id);
+            }
+        }
+    }
+}
+
+/// Движение рабочего к цели работы (к хранилищу с материалами или к ячейке склада)
+///
+/// Рабочий, который не может дойти, отказывается от работы до повторной попытки
+fn move_to_job(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut pawns:      Query<(Entity, &CurrentJob, &mut Brain, &mut AiPath, &EntityBase, &Transform, Option<&EntityNeutrality>, Has<PathFailed>), Without<PathfindingTask>>,
+        targets:    Query<(&Transform, Option<&Footprint>)>,
+        grid:       Res<Grid>,
+) {
+    for (pawn, current, mut brain, mut path, base, transform, faction, failed) in &mut pawns {
+        let Some(job) = board.get(current.id) else {
+            continue;
+        };
+        // К рабочему месту рабочего ведёт `operate_machine`, здесь остаётся только отказ от недостижимой работы
+        if brain.machine().is_some() && !failed {
+            continue;
+        }
+
+        // Ячейка склада свободна от объектов, поэтому рабочий с грузом идёт прямо на неё
+        let (center, footprint) = match job.destination.filter(|_| job.started && current.fetch.is_none()) {
+            Some(cell) => (cell_position(cell), None),
+            None => {
+                let Ok((target_transform, footprint)) = targets.get(current.fetch.unwrap_or(job.target)) else {
+                    continue;
+                };
+                let footprint = footprint.copied().unwrap_or(Footprint::single(Grid::tile_of(target_transform.translation.truncate().as_ivec2())));
+                (footprint.center(), Some(footprint))
+            },
+        };
+
+        let position = transform.translation.truncate();
+        if center.distance(position) <= base.interaction_radius {
+            path.locations.clear();
+            continue;
+        }
+
+        if failed {
+            commands.entity(pawn).remove::<PathFailed>();
+            info!("Job - {:?} не может дойти до работы {}", pawn, current.id);
+            abandon_job(&mut commands, &mut board, pawn, &mut brain, current.id, true);
+            continue;
+        }
+
+        if !path.locations.is_empty() {
+            continue;
+        }
+
+        let goal = match footprint {
+            Some(footprint) => approach_tile(&grid, &footprint, position, faction.copied()).map(|tile| tile * 16 + 8),
+            None => Some(center.as_ivec2()).filter(|goal| grid.step_cost(goal, faction.copied()).is_some()),
+        };
+
+        match goal {
+            Some(goal) => spawn_optimized_pathfinding_task(
+                &mut commands,
+                pawn,
+                Arc::new(grid.clone()),
+                GridLocation::new(position.x as i32, position.y as i32),
+                GridLocation(goal),
+                faction.copied(),
+            ),
+            None => abandon_job(&mut commands, &mut board, pawn, &mut brain, current.id, true),
+        }
+    }
+}
+
+/// Постройка чертежа: материалы берутся из инвентаря рабочего, недостающие - из ближайшего хранилища
+fn work_construct(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut pawns:      Query<(Entity, &mut CurrentJob, &mut Brain, &mut Inventory, &EntityBase, &Transform), With<Pawn>>,
+    mut storages:   Query<(Entity, &mut Inventory, &Transform), (With<EntityObject>, Without<Pawn>)>,
+    mut work_event: EventWriter<BuildWork>,
+        blueprints: Query<(&Blueprint, &Footprint)>,
+        registry:   Res<Registry>,
+) {
+    for (pawn, mut current, mut brain, mut inventory, base, transform) in &mut pawns {
+        let Some(job) = board.get(current.id).filter(|job| job.kind == JobKind::Construct) else {
+            continue;
+        };
+        let Ok((blueprint, footprint)) = blueprints.get(job.target) else {
+            continue;
+        };
+        let (id, target) = (job.id, job.target);
+        let position = transform.translation.truncate();
+
+        let carrying = blueprint.materials.iter().any(|material| inventory.count_of(&material.item) > 0);
+        if blueprint.supplied() || carrying {
+            current.fetch = None;
+            if footprint.center().distance(position) <= base.interaction_radius {
+                work_event.send(BuildWork(pawn, target));
+            }
+            continue;
+        }
+
+        if current.fetch.is_none() {
+            current.fetch = storages.iter()
+                .filter(|(_, storage, _)| blueprint.materials.iter().any(|material| storage.count_of(&material.item) > 0))
+                .map(|(storage, _, storage_transform)| (storage, storage_transform.translation.truncate().distance(position)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(storage, _)| storage);
+
+            if current.fetch.is_none() {
+                info!("Job - нет материалов для {}", blueprint.object);
+                abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+                continue;
+            }
+        }
+
+        let Some(Ok((_, mut storage, storage_transform))) = current.fetch.map(|fetch| storages.get_mut(fetch)) else {
+            current.fetch = None;
+            continue;
+        };
+        if storage_transform.translation.truncate().distance(position) > base.interaction_radius {
+            continue;
+        }
+
+        // Рабочий берёт сколько унесёт, не поместившееся остаётся в хранилище
+        for material in &blueprint.materials {
+            let count = storage.count_of(&material.item).min(material.count);
+            let Some(info) = registry.get_item_info(&material.item) else {
+                continue;
+            };
+            if count == 0 || storage.remove_count(&material.item, count).is_err() {
+                continue;
+            }
+
+            let mut item = ItemEntity::from_info(info, count);
+            let _ = inventory.add(&mut item);
+            if item.count > 0 {
+                let _ = storage.add(&mut item);
+            }
+        }
+        current.fetch = None;
+    }
+}
+
+/// Перенос предмета с земли на склад (в ячейку с таким же стаком или пустую), если склада нет - в хранилище
+///
+/// Предмет, уже лежащий на складе, переносится только на склад с более высоким приоритетом
+fn work_haul(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut chunk:      ResMut<Chunk>,
+    mut pawns:      Query<(Entity, &CurrentJob, &mut Brain, &mut Inventory, &EntityBase, &Transform, Option<&HaulCargo>), With<Pawn>>,
+    mut storages:   Query<(Entity, &mut Inventory, &Transform), (With<EntityObject>, Without<Pawn>)>,
+    mut items:      Query<(&mut ItemEntity, &Transform), Without<Pawn>>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+        stockpiles: Query<(Entity, &Stockpile)>,
+        grid:       Res<Grid>,
+        registry:   Res<Registry>,
+) {
+    for (pawn, current, mut brain, mut inventory, base, transform, cargo) in &mut pawns {
+        let Some(job) = board.get(current.id).filter(|job| job.kind == JobKind::Haul) else {
+            continue;
+        };
+        let (id, target, started, destination, haul_to) = (job.id, job.target, job.started, job.destination, job.haul_to);
+        let position = transform.translation.truncate();
+
+        if !started {
+            let Ok((item, item_transform)) = items.get(target) else {
+                continue;
+            };
+            let Some(info) = registry.get_item_info(&item.id_name) else {
+                board.finish(id);
+                continue;
+            };
+            let item_position = item_transform.translation.truncate();
+
+            // Место переноса выбирается один раз и резервируется на работе, заново - только если склад или хранилище пропали
+            let haul_to = haul_to.filter(|(entity, _)| stockpiles.contains(*entity) || storages.contains(*entity));
+            let haul_to = match haul_to {
+                Some(haul_to) => haul_to,
+                None => {
+                    let stack_at = |cell: IVec2| chunk.objects_ex.get(&cell).and_then(|entity| items.get(*entity).ok()).map(|(item, _)| item);
+                    let subtile = WorldSystem::get_currect_chunk_subtile(item_position.as_ivec2());
+                    let stored = stored_priority(stockpiles.iter().map(|(_, stockpile)| stockpile), subtile, info);
+
+                    let reserved = board.reserved_cells(Some(id));
+
+                    let resolved = match find_stockpile_cell(stockpiles.iter(), info, &item.instance, item_position, stored, &grid, &reserved, &stack_at) {
+                        Some((stockpile, cell)) => Some((stockpile, Some(cell), cell_position(cell))),
+                        // Хранилище, в которое помещается хотя бы часть предмета
+                        None if stored.is_none() => storages.iter()
+                            .filter(|(_, storage, _)| {
+                                let mut preview = item.clone();
+                                let _ = Inventory::clone(storage).add(&mut preview);
+                                preview.count < item.count
+                            })
+                            .map(|(storage, _, storage_transform)| (storage, None, storage_transform.translation.truncate()))
+                            .min_by(|a, b| a.2.distance(position).total_cmp(&b.2.distance(position))),
+                        None => None,
+                    };
+
+                    let Some((destination, cell, destination_position)) = resolved else {
+                        abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+                        continue;
+                    };
+                    if let Some(job) = board.get_mut(id) {
+                        job.destination = cell;
+                        job.haul_to = Some((destination, destination_position));
+                    }
+                    (destination, destination_position)
+                },
+            };
+            if item_position.distance(position) > base.interaction_radius {
+                continue;
+            }
+
+            let Ok((mut item, _)) = items.get_mut(target) else {
+                continue;
+            };
+            let count = item.count;
+            let _ = inventory.add(&mut item);
+
+            // Рабочий несёт только поднятую часть стака, остальной инвентарь в работе не участвует
+            let picked = count - item.count;
+            let carried = inventory.iter_some()
+                .find(|slot| slot.id_name == item.id_name && slot.instance == item.instance)
+                .cloned()
+                .filter(|_| picked > 0);
+            let Some(mut carried) = carried else {
+                abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+                continue;
+            };
+            carried.count = picked;
+            commands.entity(pawn).insert(HaulCargo(carried));
+
+            if item.count == 0 {
+                chunk.remove_sub_object_ex(target);
+                commands.entity(target).despawn_recursive();
+            }
+
+            // Дальше целью работы становится склад или хранилище, остаток на земле получает новую работу
+            let remainder = item.count > 0;
+            if let Some(job) = board.get_mut(id) {
+                job.target = haul_to.0;
+                job.position = haul_to.1;
+                job.started = true;
+            }
+            if remainder {
+                board.post(JobKind::Haul, target, item_position, JobPriority::Normal, None);
+            }
+            continue;
+        }
+
+        if let Some(cell) = destination {
+            if cell_position(cell).distance(position) > base.interaction_radius {
+                continue;
+            }
+
+            let Some(cargo) = cargo else {
+                board.finish(id);
+                continue;
+            };
+
+            let mut leftover = Vec::new();
+            if let Ok((_, stockpile)) = stockpiles.get(target) {
+                let stack_at = |cell: IVec2| chunk.objects_ex.get(&cell).and_then(|entity| items.get(*entity).ok()).map(|(item, _)| item);
+                // Груз по ячейкам: в ячейку кладётся один стак одним спавном (другой предмет туда не кладётся)
+                let mut planned: Vec<(IVec2, Slot)> = Vec::new();
+                let reserved = board.reserved_cells(Some(id));
+
+                for mut slot in inventory.take_matching(&cargo.0) {
+                    let Some(info) = registry.get_item_info(&slot.id_name).filter(|info| stockpile.accepts(info)) else {
+                        leftover.push(slot);
+                        continue;
+                    };
+
+                    // Сначала выбранная ячейка, затем остальные ячейки склада
+                    for cell in std::iter::once(cell).chain(stockpile.cells().filter(|cell| !reserved.contains(cell))) {
+                        if slot.count == 0 {
+                            break;
+                        }
+
+                        let stack = planned.iter().position(|(planned_cell, _)| *planned_cell == cell);
+                        let used = match stack.map(|index| &planned[index].1) {
+                            Some(stack) if !stack.same_item(&slot) => continue,
+                            Some(stack) => stack.count,
+                            None => 0,
+                        };
+                        let count = cell_space(cell, info, &slot.instance, &grid, &stack_at).saturating_sub(used).min(slot.count);
+                        if count == 0 {
+                            continue;
+                        }
+
+                        match stack {
+                            Some(index) => planned[index].1.count += count,
+                            None => {
+                                let mut part = slot.clone();
+                                part.count = count;
+                                planned.push((cell, part));
+                            },
+                        }
+                        slot.count -= count;
+                    }
+
+                    if slot.count > 0 {
+                        leftover.push(slot);
+                    }
+                }
+
+                for (cell, stack) in planned {
+                    spawn_i.send(ItemSpawn(stack.id_name, cell, stack.count, Some(stack.instance)));
+                }
+            } else {
+                leftover = inventory.take_matching(&cargo.0);
+            }
+
+            // Не поместившееся на склад рабочий выкладывает рядом
+            drop_slots(leftover, position.as_ivec2(), &mut spawn_i);
+            commands.entity(pawn).remove::<HaulCargo>();
+            board.finish(id);
+            continue;
+        }
+
+        let Ok((_, mut storage, storage_transform)) = storages.get_mut(target) else {
+            continue;
+        };
+        if storage_transform.translation.truncate().distance(position) > base.interaction_radius {
+            continue;
+        }
+        let Some(cargo) = cargo else {
+            board.finish(id);
+            continue;
+        };
+
+        // Груз переносится через отдельный инвентарь, всё, что не поместилось в хранилище, рабочий выкладывает рядом
+        let slots = inventory.take_matching(&cargo.0);
+        let mut carried = Inventory::with_capacity(slots.len());
+        for slot in slots {
+            let _ = carried.insert_slot(slot);
+        }
+        for index in 0..carried.len() {
+            if let Err(err) = carried.quick_move(index, &mut storage) {
+                info!("Job - предмет не поместился в хранилище ({:?})", err);
+            }
+        }
+        drop_slots(carried.take_all(), position.as_ivec2(), &mut spawn_i);
+        commands.entity(pawn).remove::<HaulCargo>();
+        board.finish(id);
+    }
+}
+
+/// Разбор объекта: материалы рецепта постройки и содержимое хранилища выпадают на землю
+fn work_deconstruct(
+    mut commands:   Commands,
+    mut board:      ResMut<JobBoard>,
+    mut grid:       ResMut<Grid>,
+    mut objects:    Query<(&EntityObject, &Footprint, Option<&mut Inventory>), Without<Pawn>>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+        pawns:      Query<(&CurrentJob, &EntityBase, &Transform), With<Pawn>>,
+        registry:   Res<Registry>,
+        clock:      Res<WorldInfo>,
+) {
+    for (current, base, transform) in &pawns {
+        let Some(job) = board.get_mut(current.id).filter(|job| job.kind == JobKind::Deconstruct) else {
+            continue;
+        };
+        let Ok((object, footprint, inventory)) = objects.get_mut(job.target) else {
+            continue;
+        };
+        if footprint.center().distance(transform.translation.truncate()) > base.interaction_radius {
+            continue;
+        }
+
+        let recipe = registry.get_construction_recipe(&object.id_name);
+        job.progress += clock.delta();
+        if job.progress < recipe.map_or(DECONSTRUCT_TIME, |recipe| recipe.craft_time.max(DECONSTRUCT_TIME)) {
+            continue;
+        }
+
+        let origin = footprint.center().as_ivec2();
+        if let Some(recipe) = recipe {
+            let subtile = WorldSystem::get_currect_chunk_subtile(origin);
+            for (index, material) in recipe.request.iter().enumerate() {
+                spawn_i.send(ItemSpawn(material.item.clone(), subtile + IVec2::new(index as i32 % 3 - 1, 1), material.count, None));
+            }
+        }
+        if let Some(mut inventory) = inventory {
+            drop_slots(inventory.take_all(), origin, &mut spawn_i);
+        }
+
+        let (id, target) = (job.id, job.target);
+        grid.remove_object(target, footprint);
+        commands.entity(target).despawn_recursive();
+        board.finish(id);
+    }
+}
+
+/// Сбор урожая, рабочий без нужного инструмента отказывается от работы
+///
+/// Работа над несозревшим растением снимается, `PlantSystem` поставит её снова, когда растение созреет
+fn work_harvest(
+    mut commands:       Commands,
+    mut board:          ResMut<JobBoard>,
+    mut pawns:          Query<(Entity, &CurrentJob, &mut Brain, &Inventory, Option<&Equipment>, &EntityBase, &Transform), With<Pawn>>,
+    mut harvest_event:  EventWriter<HarvestPlant>,
+        plants:         Query<(&Footprint, Option<&Harvestable>), With<PlantObject>>,
+        clock:          Res<WorldInfo>,
+) {
+    for (pawn, current, mut brain, inventory, equipment, base, transform) in &mut pawns {
+        let Some(job) = board.get(current.id).filter(|job| job.kind == JobKind::Harvest) else {
+            continue;
+        };
+        let (id, target) = (job.id, job.target);
+        let Ok((footprint, harvestable)) = plants.get(target) else {
+            continue;
+        };
+
+        let Some(harvestable) = harvestable else {
+            board.finish(id);
+            continue;
+        };
+        if harvestable.tool.map_or(false, |tool| !has_tool(Some(inventory), equipment, tool)) {
+            info!("Job - у {:?} нет инструмента {:?}", pawn, harvestable.tool);
+            abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+            continue;
+        }
+        if footprint.center().distance(transform.translation.truncate()) > base.interaction_radius {
+            continue;
+        }
+
+        let Some(job) = board.get_mut(id) else {
+            continue;
+        };
+        job.progress += clock.delta();
+        if job.progress < harvestable.work {
+            continue;
+        }
+
+        harvest_event.send(HarvestPlant(pawn, target));
+        board.finish(id);
+    }
+}
+
+/// Крафт на рабочем месте, ингредиенты берутся из хранилища рабочего места, результат кладётся туда же
+///
+/// Рабочее место с `UseWorkstation` занимается через `Brain::operate`: подход, проверку дистанции и резерв выполняет `operate_machine`
+fn work_craft(
+    mut commands:       Commands,
+    mut board:          ResMut<JobBoard>,
+    mut pawns:          Query<(Entity, &CurrentJob, &mut Brain, &EntityBase, &Transform, Option<&UsingObject>), With<Pawn>>,
+    mut stations:       Query<(&mut Inventory, &Transform, Option<&Interactable>, Option<&Reserved>), With<EntityObject>>,
+    mut release_event:  EventWriter<ReleaseObject>,
+    mut xp_event:       EventWriter<ExperienceGain>,
+    mut spawn_i:        EventWriter<ItemSpawn>,
+        registry:       Res<Registry>,
+        clock:          Res<WorldInfo>,
+) {
+    for (pawn, current, mut brain, base, transform, using) in &mut pawns {
+        let Some(job) = board.get(current.id).filter(|job| job.kind == JobKind::Craft) else {
+            continue;
+        };
+        let (id, target, started) = (job.id, job.target, job.started);
+
+        let Some(recipe) = job.recipe.as_ref().and_then(|recipe| registry.get_recipe_info(recipe)) else {
+            warn!("Job - у работы {} нет рецепта", id);
+            board.finish(id);
+            continue;
+        };
+        let Ok((mut inventory, station_transform, interactable, reserved)) = stations.get_mut(target) else {
+            continue;
+        };
+
+        if !Reserved::allows(reserved, pawn) {
+            abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+            continue;
+        }
+
+        let station_position = station_transform.translation.truncate();
+        if interactable.map_or(false, |interactable| interactable.has(InteractionKind::UseWorkstation)) {
+            if !using.map_or(false, |using| using.0 == target) {
+                if brain.machine() != Some(target) {
+                    brain.operate(target);
+                }
+                continue;
+            }
+        } else if station_position.distance(transform.translation.truncate()) > base.interaction_radius {
+            continue;
+        }
+
+        if !started {
+            if let Err(err) = take_ingredients(&mut inventory, recipe) {
+                info!("Job - {}", err);
+                release_event.send(ReleaseObject(pawn));
+                abandon_job(&mut commands, &mut board, pawn, &mut brain, id, true);
+                continue;
+            }
+        }
+
+        let Some(job) = board.get_mut(id) else {
+            continue;
+        };
+        job.started = true;
+        job.progress += clock.delta();
+        if job.progress < recipe.craft_time {
+            continue;
+        }
+
+        if let Some(info) = registry.get_item_info(&recipe.result.output) {
+            give_items(&mut inventory, ItemEntity::from_info(info, recipe.result.count), station_position, &mut spawn_i);
+        }
+        if recipe.xp > 0 {
+            xp_event.send(ExperienceGain(pawn, recipe.xp, XpSource::Craft));
+        }
+        release_event.send(ReleaseObject(pawn));
+        board.finish(id);
+        brain.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{
+        resource::Registry::ItemRegistry,
+        Item::ItemInstance,
+        ItemType::{
+            ItemSizeType,
+            ItemStackType,
+            ItemType
+        },
+        StockpileSystem::StockpileFilter
+    };
+
+    use super::*;
+
+    /// Мир с доской работ, системы запускаются отдельным расписанием
+    fn world_with(board: JobBoard) -> World {
+        let mut world = World::new();
+        world.insert_resource(board);
+        world
+    }
+
+    fn spawn_pawn(world: &mut World, position: Vec2, priorities: &[(JobKind, u8)]) -> Entity {
+        let mut work = WorkPriorities::default();
+        work.0.extend(priorities.iter().copied());
+        world.spawn((Pawn, Brain::default(), Transform::from_translation(position.extend(0.0)), work)).id()
+    }
+
+    fn claimed(world: &mut World, pawn: Entity) -> Option<u64> {
+        world.get::<CurrentJob>(pawn).map(|current| current.id)
+    }
+
+    fn run_claim(world: &mut World) {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(claim_jobs);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn claim_prefers_pawn_priority_over_distance() {
+        let mut world = World::new();
+        let (near, far) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let mut board = JobBoard::default();
+        let haul = board.post(JobKind::Haul, near, Vec2::new(16.0, 0.0), JobPriority::Normal, None).unwrap();
+        let construct = board.post(JobKind::Construct, far, Vec2::new(300.0, 0.0), JobPriority::Normal, None).unwrap();
+        world.insert_resource(board);
+
+        let builder = spawn_pawn(&mut world, Vec2::ZERO, &[(JobKind::Construct, 1), (JobKind::Haul, 3)]);
+        run_claim(&mut world);
+        assert_eq!(claimed(&mut world, builder), Some(construct));
+
+        // Работа с приоритетом 0 рабочим не берётся
+        let hauler = spawn_pawn(&mut world, Vec2::ZERO, &[(JobKind::Construct, 0), (JobKind::Haul, 4)]);
+        run_claim(&mut world);
+        assert_eq!(claimed(&mut world, hauler), Some(haul));
+    }
+
+    #[test]
+    fn claim_orders_by_job_priority_then_distance() {
+        let mut world = World::new();
+        let targets: Vec<Entity> = (0..3).map(|_| world.spawn_empty().id()).collect();
+        let mut board = JobBoard::default();
+        let near = board.post(JobKind::Haul, targets[0], Vec2::new(16.0, 0.0), JobPriority::Normal, None).unwrap();
+        let far = board.post(JobKind::Haul, targets[1], Vec2::new(64.0, 0.0), JobPriority::Normal, None).unwrap();
+        let urgent = board.post(JobKind::Haul, targets[2], Vec2::new(128.0, 0.0), JobPriority::Urgent, None).unwrap();
+        world.insert_resource(board);
+
+        let pawns: Vec<Entity> = (0..3).map(|_| spawn_pawn(&mut world, Vec2::ZERO, &[])).collect();
+        run_claim(&mut world);
+
+        let claimed: Vec<Option<u64>> = pawns.iter().map(|pawn| claimed(&mut world, *pawn)).collect();
+        assert_eq!(claimed, vec![Some(urgent), Some(near), Some(far)]);
+    }
+
+    #[test]
+    fn claim_skips_distant_and_blocked_jobs() {
+        let mut world = World::new();
+        let (distant, blocked) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let pawn = spawn_pawn(&mut world, Vec2::ZERO, &[]);
+
+        let mut board = JobBoard::default();
+        board.post(JobKind::Haul, distant, Vec2::new(JOB_MAX_DISTANCE + 1.0, 0.0), JobPriority::Urgent, None);
+        let id = board.post(JobKind::Haul, blocked, Vec2::new(16.0, 0.0), JobPriority::Normal, None).unwrap();
+        board.release(id, pawn, true);
+        world.insert_resource(board);
+
+        run_claim(&mut world);
+        assert_eq!(claimed(&mut world, pawn), None);
+    }
+
+    #[test]
+    fn cleanup_clears_blocked_after_retry_time() {
+        let mut world = world_with(JobBoard::default());
+        let (target, pawn) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let id = {
+            let mut board = world.resource_mut::<JobBoard>();
+            let id = board.post(JobKind::Haul, target, Vec2::ZERO, JobPriority::Normal, None).unwrap();
+            board.release(id, pawn, true);
+            id
+        };
+        world.insert_resource(WorldInfo::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(cleanup_jobs);
+        let mut step = |world: &mut World, seconds: f32| {
+            world.resource_mut::<WorldInfo>().advance(seconds);
+            schedule.run(world);
+            world.resource::<JobBoard>().get(id).unwrap().blocked.clone()
+        };
+
+        assert_eq!(step(&mut world, JOB_RETRY_TIME * 0.6), vec![pawn]);
+
+        // Пауза не продвигает таймер повтора
+        world.resource_mut::<WorldInfo>().is_paused = true;
+        assert_eq!(step(&mut world, JOB_RETRY_TIME), vec![pawn]);
+        world.resource_mut::<WorldInfo>().is_paused = false;
+
+        assert!(step(&mut world, JOB_RETRY_TIME * 0.6).is_empty());
+    }
+
+    fn item_info() -> ItemRegistry {
+        ItemRegistry {
+            id_name:    "stone".to_string(),
+            id_source:  None,
+            id_texture: "stone".to_string(),
+            item_type:  ItemType::None,
+            item_size:  ItemSizeType::Small,
+            range_info: None,
+            stackable:  Some(ItemStackType::Scalable),
+            stack_size: Some(10),
+            durability: None,
+            mass:       None,
+            volume:     None,
+            equip:      None,
+            container:  None,
+            light:      None
+        }
+    }
+
+    #[test]
+    fn stockpile_cell_skips_reserved() {
+        let entity = Entity::from_raw(1);
+        let stockpile = Stockpile {
+            area:       Footprint::single(IVec2::ZERO),
+            filter:     StockpileFilter::default(),
+            priority:   JobPriority::Normal,
+        };
+        let info = item_info();
+        let grid = Grid::new(1);
+        let stack_at = |_: IVec2| None::<&ItemEntity>;
+        let find = |reserved: &HashSet<IVec2>| {
+            find_stockpile_cell(std::iter::once((entity, &stockpile)), &info, &ItemInstance::default(), cell_position(IVec2::ZERO), None, &grid, reserved, &stack_at)
+        };
+
+        assert_eq!(find(&HashSet::new()), Some((entity, IVec2::ZERO)));
+
+        // Ближайшая ячейка занята другой работой, выбирается следующая
+        let reserved: HashSet<IVec2> = [IVec2::ZERO].into();
+        let found = find(&reserved).map(|(_, cell)| cell);
+        assert!(found.map_or(false, |cell| cell != IVec2::ZERO && stockpile.contains_cell(cell)));
+
+        let reserved: HashSet<IVec2> = stockpile.cells().collect();
+        assert_eq!(find(&reserved), None);
+    }
+
+    #[test]
+    fn board_reserves_haul_destinations() {
+        let mut board = JobBoard::default();
+        let (item, pawn) = (Entity::from_raw(1), Entity::from_raw(2));
+        let id = board.post(JobKind::Haul, item, Vec2::ZERO, JobPriority::Normal, None).unwrap();
+        assert!(board.claim(id, pawn));
+
+        let job = board.get_mut(id).unwrap();
+        job.destination = Some(IVec2::new(3, 4));
+        job.haul_to = Some((Entity::from_raw(3), Vec2::ZERO));
+        assert!(board.reserved_cells(None).contains(&IVec2::new(3, 4)));
+        assert!(board.reserved_cells(Some(id)).is_empty());
+
+        // До подъёма предмета отказ снимает резервацию
+        board.release(id, pawn, false);
+        assert!(board.reserved_cells(None).is_empty());
+        assert_eq!(board.get(id).unwrap().haul_to, None);
+    }
+}
@@ -9,6 +9,7 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 
 use crate::core::{
+    EntityType::EntityNeutrality,
     world::Grid::{
         Grid, 
         GridLocation
@@ -53,38 +54,38 @@ impl Path {
     }
 }
 
-pub fn neumann_neighbors(grid: &Grid, location: &GridLocation) -> Vec<GridLocation> {
+pub fn neumann_neighbors(grid: &Grid, location: &GridLocation, faction: Option<EntityNeutrality>) -> Vec<(GridLocation, usize)> {
     let (x, y) = (location.0.x as i32, location.0.y as i32);
 
-    // Вектор с подтверждённым путём без препятствий
+    // Вектор с подтверждённым путём без препятствий (двери проходимы с повышенной стоимостью)
     let mut sucessors = Vec::new();
 
     // Проверка пути слева от точки вычисления
     if let Some(left) = x.checked_sub(1) {
         let location = GridLocation::new(left, y);
-        if !grid.check_exist_object(&location.0) {
-            sucessors.push(location);
+        if let Some(cost) = grid.step_cost(&location.0, faction) {
+            sucessors.push((location, cost));
         }
     }
     // Проверка пути снизу от точки вычисления
     if let Some(down) = y.checked_sub(1) {
         let location = GridLocation::new(x, down);
-        if !grid.check_exist_object(&location.0) {
-            sucessors.push(location);
+        if let Some(cost) = grid.step_cost(&location.0, faction) {
+            sucessors.push((location, cost));
         }
     }
     // Проверка пути справа от точки вычисления
     if let Some(right) = x.checked_add(1) {
         let location = GridLocation::new(right, y);
-        if !grid.check_exist_object(&location.0) {
-            sucessors.push(location);
+        if let Some(cost) = grid.step_cost(&location.0, faction) {
+            sucessors.push((location, cost));
         }
     }
     // Проверка пути сверху от точки вычисления
     if let Some(up) = y.checked_add(1) {
         let location = GridLocation::new(x, up);
-        if !grid.check_exist_object(&location.0) {
-            sucessors.push(location);
+        if let Some(cost) = grid.step_cost(&location.0, faction) {
+            sucessors.push((location, cost));
         }
     }
     sucessors
@@ -93,17 +94,13 @@ pub fn neumann_neighbors(grid: &Grid, location: &GridLocation) -> Vec<GridLocati
 impl Grid {
     pub fn path_to(
         &self,
-        start:  &GridLocation,
-        goal:   &GridLocation,
+        start:      &GridLocation,
+        goal:       &GridLocation,
+        faction:    Option<EntityNeutrality>,
     ) -> Result<Path, PathfindingError> {
         let result = astar(
             start,
-            |p| {
-                neumann_neighbors(self, p)
-                    .iter()
-                    .map(|neighbor| (neighbor.clone(), 1))
-                    .collect::<Vec<_>>()
-            },
+            |p| neumann_neighbors(self, p, faction),
             |p| p.distance(goal) / 3,
             |p| p == goal,
         );
@@ -130,6 +127,10 @@ pub fn calculate_pos_IV2(target: (IVec2, IVec2)) -> IVec2 {
     )
 }
 
+/// Путь до цели не найден, ставится вместо пути и снимается тем, кто его обработал
+#[derive(Component)]
+pub struct PathFailed;
+
 #[derive(Component)]
 pub struct PathfindingTask(Task<Result<Path, PathfindingError>>);
 
@@ -139,9 +140,10 @@ pub fn spawn_optimized_pathfinding_task(
     grid:       Arc<Grid>,
     start:      GridLocation,
     end:        GridLocation,
+    faction:    Option<EntityNeutrality>,
 ) {
     // Выход, если в конец нельзя прийти
-    if grid.check_exist_object(&end.0) {
+    if grid.step_cost(&end.0, faction).is_none() {
         return;
     }
 
@@ -152,7 +154,7 @@ pub fn spawn_optimized_pathfinding_task(
     // let grid_arc = Arc::new(grid.clone());
 
     let task = thread_pool.spawn(async move {
-        let mut path = grid.path_to(&start, &end);
+        let mut path = grid.path_to(&start, &end, faction);
         let _ = path.as_mut().map(|p| p.optimize_corners());
         path
     });
@@ -169,13 +171,18 @@ pub fn apply_pathfinding_to_ai(
         if let Some(result) = future::block_on(future::poll_once(&mut task.0)) {
             commands.entity(task_entity).remove::<PathfindingTask>();
             if let Ok(mut ai_path) = paths.get_mut(task_entity) {
-                if let Ok(path) = result {
-                    ai_path.locations.clear();
-                    for location in path.steps.iter() {
-                        ai_path
-                            .locations
-                            .push_back(location.0.as_vec2());
-                    }
+                match result {
+                    Ok(path) => {
+                        ai_path.locations.clear();
+                        for location in path.steps.iter() {
+                            ai_path
+                                .locations
+                                .push_back(location.0.as_vec2());
+                        }
+                    },
+                    Err(_) => {
+                        commands.entity(task_entity).insert(PathFailed);
+                    },
                 }
             }
         }
@@ -1,5 +1,6 @@
 #![allow(unused)]
 #![allow(non_snake_case)]
+pub mod Job;
 pub mod Path;
 
 use std::sync::Arc;
@@ -10,6 +11,10 @@ use crate::core::{
     Entity::EntityBase,
     entities::{
         EntitySystem::MovementEntity,
+        ai::Job::{
+            job_plugin,
+            approach_tile
+        },
         ai::Path::{
             path_finding_plugin,
             AiPath,
@@ -22,10 +27,33 @@ use crate::core::{
         Hunger,
         Recreation
     },
-    world::Grid::{
-        Grid,
-        GridLocation
+    EntityType::EntityNeutrality,
+    skills::{
+        skill_ready,
+        CastingSkill,
+        Energy,
+        SkillCooldowns,
+        SkillEffect,
+        SkillSlots,
+        UseSkill,
+        SKILL_SLOTS
+    },
+    resource::Registry::Registry,
+    world::{
+        World::WorldInfo,
+        Grid::{
+            Grid,
+            GridLocation
+        }
+    },
+    interact::Interact::{
+        Interactable,
+        InteractionKind,
+        InteractObject,
+        Reserved,
+        UsingObject
     },
+    Object::Footprint,
     UserSystem::UserControl,
     AppState
 };
@@ -35,10 +63,13 @@ pub struct AiPlugin;
 impl Plugin for AiPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(path_finding_plugin);
+        app.add_plugins(job_plugin);
         app.add_systems(Update, 
             (
                 to_player,
-                follow_path.after(to_player)
+                operate_machine,
+                follow_path.after(to_player),
+                use_skills
             ).run_if(in_state(AppState::Game))
         );
     }
@@ -56,9 +87,43 @@ pub enum BrainState {
     Wander(f32),
     GetFood,
     OperateMachine(Entity),
+    Work(u64),
     Relax,
 }
 
+impl Brain {
+    /// Отправить сущность использовать объект
+    pub fn operate(&mut self, machine: Entity) {
+        self.state = BrainState::OperateMachine(machine);
+    }
+
+    /// Объект, который сущность собирается использовать
+    pub fn machine(&self) -> Option<Entity> {
+        match self.state {
+            BrainState::OperateMachine(machine) => Some(machine),
+            _ => None,
+        }
+    }
+
+    /// Отправить сущность выполнять работу с доски работ
+    pub fn work(&mut self, job: u64) {
+        self.state = BrainState::Work(job);
+    }
+
+    /// Работа, которую выполняет сущность
+    pub fn job(&self) -> Option<u64> {
+        match self.state {
+            BrainState::Work(job) => Some(job),
+            _ => None,
+        }
+    }
+
+    /// Вернуться к обычному поведению
+    pub fn reset(&mut self) {
+        self.state = BrainState::default();
+    }
+}
+
 impl Default for BrainState {
     fn default() -> Self {
         BrainState::Wander(0.0)
@@ -71,7 +136,7 @@ pub struct LastDirection(pub Vec2);
 fn update_brains(mut brains: Query<(&mut Brain, &Hunger, &Recreation)>) {
     for (mut brain, hunger, _recreation) in &mut brains {
 
-        if matches!(brain.state, BrainState::OperateMachine(_)) {
+        if matches!(brain.state, BrainState::OperateMachine(_) | BrainState::Work(_)) {
             continue;
         }
 
@@ -122,7 +187,7 @@ fn update_brains(mut brains: Query<(&mut Brain, &Hunger, &Recreation)>) {
 
 fn to_player(
     mut commands:   Commands,
-    mut brains:     Query<(Entity, &AiPath, &Transform), (Without<PathfindingTask>, Without<UserControl>)>,
+    mut brains:     Query<(Entity, &AiPath, &Transform, Option<&Brain>, Option<&EntityNeutrality>), (Without<PathfindingTask>, Without<UserControl>)>,
         player:     Query<(Entity, &Transform), With<UserControl>>,
         grid:       Res<Grid>,
 ) {
@@ -130,7 +195,10 @@ fn to_player(
         return;
     }
 
-    for (target, path, transform) in &mut brains {
+    for (target, path, transform, brain, faction) in &mut brains {
+        if brain.map_or(false, |brain| brain.machine().is_some() || brain.job().is_some()) {
+            continue;
+        }
 
         // let brain_location = match GridLocation::from_world(transform.translation.truncate()) {
         //     Some(val) => val,
@@ -183,6 +251,7 @@ fn to_player(
                     Arc::new(grid.clone()),
                     GridLocation::new(transform.translation.x as i32, transform.translation.y as i32),
                     GridLocation::new(player.1.translation.x as i32, player.1.translation.y as i32),
+                    faction.copied(),
                 );
             }
         }
@@ -248,16 +317,75 @@ fn to_player(
 //     }
 // }
 
+/// Использование объекта сущностью в состоянии `BrainState::OperateMachine`
+///
+/// Сущность идёт к ближайшей проходимой клетке у объекта и занимает его, занятый другой сущностью объект сбрасывает состояние
+fn operate_machine(
+    mut commands:       Commands,
+    mut brains:         Query<(Entity, &mut Brain, &AiPath, &EntityBase, &Transform, Option<&UsingObject>, Option<&EntityNeutrality>), (Without<PathfindingTask>, Without<UserControl>)>,
+    mut interact_event: EventWriter<InteractObject>,
+        machines:       Query<(&Interactable, &Transform, Option<&Footprint>, Option<&Reserved>)>,
+        grid:           Res<Grid>,
+) {
+    for (entity, mut brain, path, entity_base, transform, using, faction) in &mut brains {
+        let Some(machine) = brain.machine() else {
+            continue;
+        };
+
+        let Ok((interactable, machine_transform, footprint, reserved)) = machines.get(machine) else {
+            brain.reset();
+            continue;
+        };
+
+        if !Reserved::allows(reserved, entity) {
+            info!("AI - {:?} занят, {:?} ищет другое занятие", machine, entity);
+            brain.reset();
+            continue;
+        }
+
+        if using.map_or(false, |using| using.0 == machine) {
+            continue;
+        }
+
+        let Some(kind) = [InteractionKind::UseWorkstation, InteractionKind::Sit].into_iter().find(|kind| interactable.has(*kind)) else {
+            brain.reset();
+            continue;
+        };
+
+        // Дистанция считается до края объекта, большие объекты доступны с любой стороны
+        let footprint = footprint.copied().unwrap_or(Footprint::single(Grid::tile_of(machine_transform.translation.truncate().as_ivec2())));
+        let position = transform.translation.truncate();
+        if footprint.distance(position) <= entity_base.interaction_radius {
+            interact_event.send(InteractObject(entity, machine, kind));
+        } else if path.locations.is_empty() {
+            // Клетки объекта заняты, поэтому цель - ближайшая проходимая клетка вокруг него
+            let Some(goal) = approach_tile(&grid, &footprint, position, faction.copied()) else {
+                info!("AI - к {:?} не подойти, {:?} ищет другое занятие", machine, entity);
+                brain.reset();
+                continue;
+            };
+            spawn_optimized_pathfinding_task(
+                &mut commands,
+                entity,
+                Arc::new(grid.clone()),
+                GridLocation::new(position.x as i32, position.y as i32),
+                GridLocation(goal * 16 + 8),
+                faction.copied(),
+            );
+        }
+    }
+}
+
 fn follow_path(
     mut paths:  Query<(Entity, &EntityBase, &mut Transform, &mut AiPath, &mut LastDirection)>,
     mut event:  EventWriter<MovementEntity>,
-        time:   Res<Time>,
+        clock:  Res<WorldInfo>,
 ) {
     for (entity, entity_base, mut transform, mut path, mut last_direction) in &mut paths {
         if let Some(next_target) = path.locations.front() {
 
             let delta = *next_target - transform.translation.truncate();
-            let travel_amount = time.delta_seconds();
+            let travel_amount = clock.delta();
 
             if delta.length() > travel_amount * 1.1 {
                 let direction = delta.normalize().extend(0.0) * travel_amount;
@@ -271,4 +399,52 @@ fn follow_path(
             last_direction.0 = Vec2::ZERO;
         }
     }
-}
\ No newline at end of file
+}
+
+/// Дистанция, на которой враждебные сущности начинают применять навыки против игрока
+const AI_SKILL_RANGE: f32 = 160.0;
+
+/// Применение активных навыков враждебными сущностями
+///
+/// Лечение - при здоровье ниже половины, область - если игрок в радиусе,
+/// рывок и снаряд - если игрок в пределах `AI_SKILL_RANGE`
+fn use_skills(
+        brains:     Query<(Entity, &Transform, &EntityBase, &EntityNeutrality, &SkillSlots, &SkillCooldowns, &Energy), (Without<CastingSkill>, Without<UserControl>)>,
+        player:     Query<&Transform, With<UserControl>>,
+    mut event:      EventWriter<UseSkill>,
+        registry:   Res<Registry>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    for (entity, transform, entity_base, neutrality, slots, cooldowns, energy) in &brains {
+        if !matches!(neutrality, EntityNeutrality::Hostile) {
+            continue;
+        }
+
+        let distance = transform.translation.truncate().distance(player.translation.truncate());
+        let max_health = registry.get_entity_info(&entity_base.id_name).map_or(entity_base.health.0, |info| info.health);
+
+        for slot in 0..SKILL_SLOTS {
+            if !skill_ready(&registry, slots, cooldowns, energy, slot) {
+                continue;
+            }
+
+            let Some(info) = slots.slots[slot].as_ref().and_then(|skill| registry.get_skill_info(skill)) else {
+                continue;
+            };
+
+            let usable = match info.effect {
+                SkillEffect::Heal { .. } => entity_base.health.0 < max_health * 0.5,
+                SkillEffect::AreaDamage { radius, .. } => distance <= radius,
+                SkillEffect::Dash { .. } | SkillEffect::Projectile { .. } => distance <= AI_SKILL_RANGE,
+            };
+
+            if usable {
+                event.send(UseSkill(entity, slot, player.translation.truncate()));
+                break;
+            }
+        }
+    }
+}
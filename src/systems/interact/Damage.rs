@@ -4,13 +4,29 @@ use bevy::prelude::*;
 use crate::core::{
     AppState,
     UserSystem::UserControl,
-    Object::EntityObject,
+    Object::{
+        EntityObject,
+        PersistentObject,
+        Footprint
+    },
     Entity::EntityBase,
-    // world::chunk::Chunk::Chunk,
-    world::Grid::{
-        Grid,
-        get_format_current_chunk
+    Attributes::{
+        InvincibilityCooldown,
+        Attack,
+        Defence
+    },
+    resource::Registry::Registry,
+    ContainerSystem::{
+        Inventory,
+        drop_slots
+    },
+    Item::ItemSpawn,
+    stats::{
+        ExperienceGain,
+        XpSource
     },
+    // world::chunk::Chunk::Chunk,
+    world::Grid::Grid,
 };
 
 pub struct DamageSystem;
@@ -26,45 +42,113 @@ impl Plugin for DamageSystem {
     }
 }
 
-/// Ивент для нанесения урона объекту (1 - Местоположение | 2 - Урон)
+/// Опыт за убийство сущности, если в реестре не указан свой
+pub const DEFAULT_KILL_XP: u32 = 10;
+
+/// Ивент для нанесения урона объекту (1 - Местоположение | 2 - Урон | 3 - Атакующий | 4 - Цель)
+///
+/// Если цель указана, урон получает только она, без объектов и соседних сущностей
 #[derive(Event)]
-pub struct DamageObject(pub IVec2, pub f32);
+pub struct DamageObject(pub IVec2, pub f32, pub Option<Entity>, pub Option<Entity>);
 
 impl DamageSystem {
     fn damage_recorder(
         mut commands:   Commands,
-        mut objects:    Query<(Entity,&mut EntityObject)>,
-        mut entities:   Query<(Entity, &Transform, &mut EntityBase), Without<UserControl>>,
+        mut objects:    Query<(Entity,&mut EntityObject, Option<&mut Inventory>, Option<&Footprint>)>,
+        mut persistent: Query<&mut PersistentObject>,
+        mut entities:   Query<(Entity, &Transform, &mut EntityBase, Option<&Defence>, Has<UserControl>), Without<InvincibilityCooldown>>,
+            attackers:  Query<&Attack>,
         mut grid:       ResMut<Grid>,
-        mut event:      EventReader<DamageObject>
+        mut event:      EventReader<DamageObject>,
+        mut xp_event:   EventWriter<ExperienceGain>,
+        mut spawn_i:    EventWriter<ItemSpawn>,
+            registry:   Res<Registry>,
     ) {
         if event.is_empty() {
             return;
         }
 
         for damage_event in event.read() {
-            if let Some(chunk) = grid.chunks.get_mut(&get_format_current_chunk(damage_event.0)) {
-                if let Some(object) = chunk.get_object(damage_event.0) {
-                    if let Ok(mut entity) = objects.get_mut(object) {
-                        if entity.1.health.0 > damage_event.1 {
-                            entity.1.health.0 -= damage_event.1;
-                        } else {
-                            chunk.remove_object(entity.0);
-                            commands.entity(entity.0).despawn_recursive();
-                        }
-                    }
+            // Атрибут `Attack` атакующего добавляется к урону удара
+            let damage = damage_event.1 + damage_event.2
+                .and_then(|attacker| attackers.get(attacker).ok())
+                .map_or(0.0, |attack| attack.0 as f32);
+
+            if let Some(target) = damage_event.3 {
+                if let Ok(entity) = entities.get_mut(target) {
+                    hit_entity(&mut commands, entity, damage, damage_event.2, &registry, &mut xp_event);
                 }
+                continue;
             }
 
-            for mut entity in &mut entities {
-                if 8.0 > Vec3::distance(damage_event.0.as_vec2().extend(0.5), entity.1.translation) {
-                    if entity.2.health.0 > damage_event.1 {
-                        entity.2.health.0 -= damage_event.1;
+            // Любая клетка многоклеточного объекта указывает на один и тот же объект
+            if let Some(object) = grid.object_at(&damage_event.0) {
+                let single = Footprint::single(Grid::tile_of(damage_event.0));
+
+                if let Ok(mut entity) = objects.get_mut(object) {
+                    if entity.1.health.0 > damage {
+                        entity.1.health.0 -= damage;
                     } else {
+                        // Содержимое разрушенного хранилища выпадает на землю
+                        if let Some(inventory) = entity.2.as_mut() {
+                            drop_slots(inventory.take_all(), damage_event.0, &mut spawn_i);
+                        }
+
+                        grid.remove_object(entity.0, entity.3.unwrap_or(&single));
                         commands.entity(entity.0).despawn_recursive();
                     }
+                } else if let Ok(mut persistent) = persistent.get_mut(object) {
+                    // Соседи разрушенного объекта пересчитывают соединение в `Connect`
+                    if persistent.health.0 > damage {
+                        persistent.health.0 -= damage;
+                    } else {
+                        grid.remove_object(object, &single);
+                        commands.entity(object).despawn_recursive();
+                    }
+                }
+            }
+
+            for entity in &mut entities {
+                if !entity.4 && 8.0 > Vec3::distance(damage_event.0.as_vec2().extend(0.5), entity.1.translation) {
+                    hit_entity(&mut commands, entity, damage, damage_event.2, &registry, &mut xp_event);
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Урон по сущности, убийство приносит опыт атакующему. Игрок от урона не исчезает
+fn hit_entity(
+    commands:   &mut Commands,
+    entity:     (Entity, &Transform, Mut<EntityBase>, Option<&Defence>, bool),
+    damage:     f32,
+    attacker:   Option<Entity>,
+    registry:   &Registry,
+    xp_event:   &mut EventWriter<ExperienceGain>,
+) {
+    let (entity, _, mut entity_base, defence, is_user) = entity;
+
+    let damage = mitigate(damage, defence);
+    if entity_base.health.0 > damage {
+        entity_base.health.0 -= damage;
+        return;
+    }
+
+    if is_user {
+        entity_base.health.0 = 0.0;
+        return;
+    }
+
+    if let Some(attacker) = attacker {
+        let xp = registry.get_entity_info(&entity_base.id_name)
+            .and_then(|info| info.xp_reward)
+            .unwrap_or(DEFAULT_KILL_XP);
+        xp_event.send(ExperienceGain(attacker, xp, XpSource::Kill));
+    }
+    commands.entity(entity).despawn_recursive();
+}
+
+/// Урон после вычета атрибута `Defence` цели
+pub fn mitigate(damage: f32, defence: Option<&Defence>) -> f32 {
+    (damage - defence.map_or(0.0, |defence| defence.0 as f32)).max(0.0)
+}
@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    AppState,
+    UserSystem::UserControl,
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    Entity::EntityBase,
+    interface::game_ui::{
+        GameUI,
+        Inventory::InventoryDisplayToggleEvent
+    },
+};
+
+pub struct InteractSystem;
+
+impl Plugin for InteractSystem {
+    fn build(&self, app: &mut App) {
+        app
+            // Init Events
+            .add_event::<InteractObject>()
+            .add_event::<ReleaseObject>()
+            .add_event::<DoorToggle>()
+            // Init Systems
+            .add_systems(Update,
+                (
+                    Self::interact_object,
+                    Self::release_object,
+                    Self::release_distant
+                ).chain().run_if(in_state(AppState::Game))
+            )
+        ;
+    }
+}
+
+/// Тип взаимодействия с объектом, задаётся в `Defs/objects` полем `interactions`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InteractionKind {
+    /// Открыть инвентарь объекта-хранилища
+    OpenContainer,
+    /// Занять рабочее место (крафт с требованием `workstation`)
+    UseWorkstation,
+    /// Сесть на объект
+    Sit,
+    /// Открыть или закрыть дверь
+    ToggleDoor,
+}
+
+impl InteractionKind {
+    /// Взаимодействие занимает объект, пока сущность его использует
+    pub fn reserves(&self) -> bool {
+        matches!(self, InteractionKind::UseWorkstation | InteractionKind::Sit)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InteractionKind::OpenContainer  => "Open",
+            InteractionKind::UseWorkstation => "Use",
+            InteractionKind::Sit            => "Sit",
+            InteractionKind::ToggleDoor     => "Door",
+        }
+    }
+}
+
+/// Доступные взаимодействия объекта
+#[derive(Component, Clone, Debug, Default)]
+pub struct Interactable(pub Vec<InteractionKind>);
+
+impl Interactable {
+    pub fn has(&self, kind: InteractionKind) -> bool {
+        self.0.contains(&kind)
+    }
+}
+
+/// Объект занят сущностью, другие сущности не могут его использовать
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Reserved(pub Entity);
+
+impl Reserved {
+    /// Может ли сущность использовать объект с (возможной) резервацией
+    pub fn allows(reserved: Option<&Reserved>, actor: Entity) -> bool {
+        reserved.map_or(true, |reserved| reserved.0 == actor)
+    }
+}
+
+/// Объект, который сейчас использует сущность (0 - Объект | 1 - Тип взаимодействия)
+#[derive(Component, Clone, Copy, Debug)]
+pub struct UsingObject(pub Entity, pub InteractionKind);
+
+/// Ивент взаимодействия с объектом (0 - Сущность | 1 - Объект | 2 - Тип взаимодействия)
+#[derive(Event)]
+pub struct InteractObject(pub Entity, pub Entity, pub InteractionKind);
+
+/// Ивент освобождения объекта (0 - Сущность)
+#[derive(Event)]
+pub struct ReleaseObject(pub Entity);
+
+/// Ивент переключения двери (0 - Дверь | 1 - Сущность)
+#[derive(Event)]
+pub struct DoorToggle(pub Entity, pub Entity);
+
+impl InteractSystem {
+    fn interact_object(
+        mut commands:       Commands,
+        mut event:          EventReader<InteractObject>,
+        mut toggle_event:   EventWriter<InventoryDisplayToggleEvent>,
+        mut door_event:     EventWriter<DoorToggle>,
+        mut actors:         Query<(&EntityBase, &mut Transform, Option<&UsingObject>, Has<UserControl>), Without<EntityObject>>,
+        mut game_ui:        Query<&mut GameUI>,
+            objects:        Query<(&Interactable, &Transform, &Footprint, Option<&Reserved>), With<EntityObject>>,
+    ) {
+        if event.is_empty() {
+            return;
+        }
+
+        for event in event.read() {
+            let Ok((interactable, object_transform, footprint, reserved)) = objects.get(event.1) else {
+                continue;
+            };
+            let Ok((base, mut transform, using, is_user)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            if !interactable.has(event.2) {
+                continue;
+            }
+            if footprint.distance(transform.translation.truncate()) > base.interaction_radius {
+                info!("Interact - {:?} слишком далеко от {:?}", event.0, event.1);
+                continue;
+            }
+
+            if event.2.reserves() {
+                if !Reserved::allows(reserved, event.0) {
+                    info!("Interact - {:?} уже занят", event.1);
+                    continue;
+                }
+                // Прошлый объект освобождается, повторное взаимодействие с тем же объектом - только освобождает
+                if let Some(using) = using {
+                    if objects.contains(using.0) {
+                        commands.entity(using.0).remove::<Reserved>();
+                    }
+                    commands.entity(event.0).remove::<UsingObject>();
+                    if using.0 == event.1 {
+                        continue;
+                    }
+                }
+            }
+
+            match event.2 {
+                InteractionKind::OpenContainer => {
+                    if is_user {
+                        toggle_event.send(InventoryDisplayToggleEvent { actor: event.1 });
+                    }
+                },
+                InteractionKind::UseWorkstation => {
+                    if is_user {
+                        if let Ok(mut game_ui) = game_ui.get_single_mut() {
+                            game_ui.crafting_toggle = true;
+                        }
+                    }
+                },
+                InteractionKind::Sit => {
+                    transform.translation.x = object_transform.translation.x;
+                    transform.translation.y = object_transform.translation.y;
+                },
+                InteractionKind::ToggleDoor => {
+                    door_event.send(DoorToggle(event.1, event.0));
+                },
+            }
+
+            if event.2.reserves() {
+                commands.entity(event.1).insert(Reserved(event.0));
+                commands.entity(event.0).insert(UsingObject(event.1, event.2));
+            }
+        }
+    }
+
+    fn release_object(
+        mut commands:   Commands,
+        mut event:      EventReader<ReleaseObject>,
+            actors:     Query<&UsingObject>,
+            objects:    Query<&Reserved>,
+    ) {
+        if event.is_empty() {
+            return;
+        }
+
+        for event in event.read() {
+            let Ok(using) = actors.get(event.0) else {
+                continue;
+            };
+
+            if objects.get(using.0).map_or(false, |reserved| reserved.0 == event.0) {
+                commands.entity(using.0).remove::<Reserved>();
+            }
+            commands.entity(event.0).remove::<UsingObject>();
+        }
+    }
+
+    /// Объект освобождается, если сущность отошла от него или объект разрушен
+    fn release_distant(
+            actors:         Query<(Entity, &EntityBase, &Transform, &UsingObject)>,
+            objects:        Query<&Footprint, With<Reserved>>,
+        mut release_event:  EventWriter<ReleaseObject>,
+    ) {
+        for (actor, base, transform, using) in &actors {
+            let near = objects.get(using.0).map_or(false, |footprint| {
+                footprint.distance(transform.translation.truncate()) <= base.interaction_radius
+            });
+
+            if !near {
+                release_event.send(ReleaseObject(actor));
+            }
+        }
+    }
+}
@@ -1,2 +1,3 @@
 #![allow(non_snake_case)]
-pub mod Damage;
\ No newline at end of file
+pub mod Damage;
+pub mod Interact;
@@ -0,0 +1,65 @@
+use bevy::{
+    app::AppExit,
+    prelude::*
+};
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    resource::Diagnostic::{
+        LoadDiagnostics,
+        Severity
+    },
+    AppState
+};
+
+// ==============================
+// LoadingUI
+// ==============================
+
+/// Окно ошибок загрузки модулей, без строгого режима загрузку можно продолжить
+pub fn show_load_diagnostics(
+    mut contexts:       EguiContexts,
+    mut next_state:     ResMut<NextState<AppState>>,
+    mut app_exit:       EventWriter<AppExit>,
+        diagnostics:    Res<LoadDiagnostics>,
+) {
+    egui::Window::new("Loading errors")
+        .default_size((640.0, 400.0))
+        .anchor(egui::Align2::CENTER_CENTER, (0.0, 0.0))
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!(
+                "Errors: {}  Warnings: {}",
+                diagnostics.count(Severity::Error),
+                diagnostics.count(Severity::Warning)
+            ));
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for diagnostic in &diagnostics.entries {
+                    let color = match diagnostic.severity {
+                        Severity::Error     => egui::Color32::LIGHT_RED,
+                        Severity::Warning   => egui::Color32::YELLOW,
+                    };
+                    ui.colored_label(color, format!("{:?} {}", diagnostic.severity, diagnostic.location()));
+                    if let Some(field) = &diagnostic.field {
+                        ui.label(format!("field: {}", field));
+                    }
+                    ui.label(&diagnostic.message);
+                    ui.separator();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if !diagnostics.strict && ui.button("Continue").clicked() {
+                    next_state.set(AppState::MainMenu);
+                }
+                if ui.button("Quit").clicked() {
+                    app_exit.send(AppExit);
+                }
+            });
+        });
+}
@@ -326,6 +326,8 @@ impl MainMenu {
                             }
                         }
 
+                        ui.checkbox(&mut settings_res.strict_loading, "Strict loading");
+
                         ui.horizontal(|ui| {
                             if ui.button("Apply").clicked() {
                                 settings_res.save();
@@ -8,6 +8,11 @@ use crate::core::{
         AtlasType
     }, 
     ContainerSystem::Inventory as Container,
+    stats::{
+        Level,
+        LevelUpEvent,
+        SkillPoints
+    },
     UserSystem::{
         User, 
         UserControl
@@ -23,6 +28,7 @@ pub fn bargui_ui_plugin(app: &mut App) {
         (
             BarGui::build_gui,
             BarGui::update_player_info,
+            BarGui::update_level_info,
             BarGui::interact_with_to_about_avatar_button,
             BarGui::interact_with_to_inv_visible_button,
             BarGui::interact_with_to_handle_crafting_button
@@ -50,6 +56,9 @@ pub struct HealthBarLine;
 #[derive(Component)]
 pub struct HealthBarNum;
 
+#[derive(Component)]
+pub struct LevelBarNum;
+
 #[allow(unused)]
 #[derive(Component)]
 pub struct AmmoBarGui;
@@ -169,6 +178,30 @@ impl BarGui {
                             ));
                         });
 
+                        // === Level Bar
+
+                        parent.spawn((
+                            TextBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    bottom: Val::Px(125.0),
+                                    ..default()
+                                },
+                                text: Text {
+                                    sections: vec![TextSection::new(
+                                        "Lv",
+                                        TextStyle {
+                                            font_size: 11.0,
+                                            ..default()
+                                        },
+                                    )],
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            LevelBarNum
+                        ));
+
                         // === Ammo Bar
 
                         // parent.spawn((
@@ -355,7 +388,8 @@ impl BarGui {
         mut button_query: Query<
             (&Interaction, &mut BackgroundColor),
             (Changed<Interaction>, With<AboutAvatarButton>),
-        >
+        >,
+        mut game_ui: Query<&mut GameUI>,
     ) {
         if button_query.is_empty() {
             return;
@@ -365,6 +399,9 @@ impl BarGui {
             match *interaction {
                 Interaction::Pressed => {
                     *background_color = BTN_PRESS_COLOR.into();
+                    if let Ok(mut game_ui) = game_ui.get_single_mut() {
+                        game_ui.equipment_toggle = !game_ui.equipment_toggle;
+                    }
                 }
                 Interaction::Hovered => {
                     *background_color = BTN_HOVER_COLOR.into();
@@ -417,7 +454,8 @@ impl BarGui {
         mut button_query: Query<
             (&Interaction, &mut BackgroundColor),
             (Changed<Interaction>, With<HandleCraftingButton>),
-        >
+        >,
+        mut game_ui: Query<&mut GameUI>,
     ) {
         if button_query.is_empty() {
             return;
@@ -427,6 +465,9 @@ impl BarGui {
             match *interaction {
                 Interaction::Pressed => {
                     *background_color = BTN_PRESS_COLOR.into();
+                    if let Ok(mut game_ui) = game_ui.get_single_mut() {
+                        game_ui.crafting_toggle = !game_ui.crafting_toggle;
+                    }
                 }
                 Interaction::Hovered => {
                     *background_color = BTN_HOVER_COLOR.into();
@@ -474,4 +515,35 @@ impl BarGui {
             }
         }
     }
-}
\ No newline at end of file
+
+    // ========== BARGUI
+    // Обновление информации об уровне, опыте и очках навыков
+    // Обновляется при повышении уровня, изменении опыта или создании панели
+    // ==========
+    pub fn update_level_info(
+        mut text_l:     Query<&mut Text, With<LevelBarNum>>,
+        mut level_up:   EventReader<LevelUpEvent>,
+            player:     Query<(Entity, Ref<Level>, Ref<SkillPoints>), With<UserControl>>
+    ) {
+        if text_l.is_empty() {
+            level_up.clear();
+            return;
+        }
+
+        if let Ok((entity, level, sp)) = player.get_single() {
+            let level_up = level_up.read().filter(|event| event.0 == entity).count() > 0;
+
+            if let Ok(mut text) = text_l.get_single_mut() {
+                if level_up || level.is_changed() || sp.is_changed() || text.is_added() {
+                    text.sections = vec![TextSection::new(
+                        format!("Lv {} {}/{} SP {}", level.level, level.xp, level.next_level_xp, sp.count),
+                        TextStyle {
+                            font_size: 11.0,
+                            ..default()
+                        },
+                    )];
+                }
+            }
+        }
+    }
+}
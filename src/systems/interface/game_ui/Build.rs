@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    BuildSystem::{
+        Blueprint,
+        BuildPlacer
+    },
+    resource::Registry::Registry,
+    UserSystem::CursorMode,
+    AppState
+};
+
+use super::GameUI;
+
+//
+//
+//
+
+pub fn build_ui_plugin(app: &mut App) {
+    app.add_systems(Update,
+        (
+            BuildPanel::toggle_build_key,
+            BuildPanel::toggle_build_window
+        ).chain().run_if(in_state(AppState::Game))
+    );
+}
+
+// ========== Build ==========
+pub struct BuildPanel;
+
+impl BuildPanel {
+    // ==========
+    // Открытие окна строительства по клавише B
+    // ==========
+    fn toggle_build_key(
+        mut game_ui:        Query<&mut GameUI>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+    ) {
+        if !keyboard_input.just_pressed(KeyCode::KeyB) {
+            return;
+        }
+
+        if let Ok(mut game_ui) = game_ui.get_single_mut() {
+            game_ui.build_toggle = !game_ui.build_toggle;
+        }
+    }
+
+    // ==========
+    // Окно строительства со списком построек и чертежей
+    // ==========
+    fn toggle_build_window(
+        mut contexts:       EguiContexts,
+        mut placer:         ResMut<BuildPlacer>,
+        mut cursor_mode:    ResMut<CursorMode>,
+            game_ui:        Query<&GameUI>,
+            blueprints:     Query<&Blueprint>,
+            registry:       Res<Registry>,
+    ) {
+        let Ok(game_ui) = game_ui.get_single() else {
+            return;
+        };
+
+        if !game_ui.build_toggle {
+            return;
+        }
+
+        let mut recipes: Vec<_> = registry.recipe_registry.values().filter(|recipe| recipe.construction).collect();
+        recipes.sort_by(|a, b| a.result.output.cmp(&b.result.output));
+
+        egui::Window::new("Build")
+            .show(contexts.ctx_mut(), |ui| {
+                for recipe in recipes {
+                    ui.horizontal(|ui| {
+                        let selected = placer.object.as_ref() == Some(&recipe.result.output) && *cursor_mode == CursorMode::Build;
+                        if ui.selectable_label(selected, &recipe.result.output).clicked() {
+                            placer.select(&recipe.result.output, &mut cursor_mode);
+                        }
+
+                        for ingredient in &recipe.request {
+                            ui.label(format!("{} x{}", ingredient.item, ingredient.count));
+                        }
+                        ui.label(format!("{:.1}s", recipe.craft_time));
+                    });
+                }
+
+                if *cursor_mode == CursorMode::Build {
+                    ui.label(format!("{:?} | R - rotate, Shift - rectangle, RMB - cancel, Esc - exit", placer.direction));
+                }
+
+                if blueprints.is_empty() {
+                    return;
+                }
+
+                ui.separator();
+                for blueprint in &blueprints {
+                    ui.horizontal(|ui| {
+                        ui.label(&blueprint.object);
+                        if blueprint.supplied() {
+                            ui.add(egui::ProgressBar::new(blueprint.progress()).desired_width(80.0));
+                        } else {
+                            for material in &blueprint.materials {
+                                ui.label(format!("{} x{}", material.item, material.count));
+                            }
+                        }
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    world::World::WorldInfo,
+    TimeCycle::GAME_SPEEDS,
+    resource::Registry::Registry,
+    WeatherSystem::{
+        Weather,
+        base_temperature
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn clock_ui_plugin(app: &mut App) {
+    app.add_systems(Update, ClockPanel::clock_window.run_if(in_state(AppState::Game)));
+}
+
+// ========== Clock ==========
+pub struct ClockPanel;
+
+impl ClockPanel {
+    // ==========
+    // Окно часов: день, время, время года, погода, пауза и скорость
+    // ==========
+    fn clock_window(
+        mut contexts:   EguiContexts,
+        mut clock:      ResMut<WorldInfo>,
+            weather:    Res<Weather>,
+            registry:   Res<Registry>,
+    ) {
+        egui::Window::new("Clock")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8., 8.))
+            .resizable(false)
+            .collapsible(false)
+            .title_bar(false)
+            .show(contexts.ctx_mut(), |ui| {
+                ui.label(format!("Day {} - {} {}", clock.day + 1, clock.season().label(), clock.day_of_season() + 1));
+                ui.label(format!("{:02}:{:02}", clock.hour(), clock.minute()));
+                ui.label(format!("{} {:.0}°C", weather.current, base_temperature(&clock) + weather.temperature(&registry)));
+
+                ui.horizontal(|ui| {
+                    let paused = clock.is_paused;
+                    if ui.selectable_label(paused, "||").clicked() {
+                        clock.is_paused = !paused;
+                    }
+                    for speed in GAME_SPEEDS {
+                        if ui.selectable_label(!paused && clock.speed == speed, format!("x{}", speed)).clicked() {
+                            clock.is_paused = false;
+                            clock.speed = speed;
+                        }
+                    }
+                });
+            });
+    }
+}
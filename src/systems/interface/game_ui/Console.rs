@@ -10,6 +10,17 @@ use crate::core::{
     ContainerSystem::Inventory,
     UserSystem::UserControl,
     resource::Registry::Registry,
+    world::WorldSVD::{
+        SaveWorld,
+        LoadWorld
+    },
+    stats::{
+        ExperienceGain,
+        XpSource,
+        SpendSkillPoint,
+        SkillPointTarget,
+        StatType
+    },
     AppState
 };
 
@@ -92,8 +103,12 @@ pub struct ConsoleInput(pub String);
 
 pub fn cmd_execute(
     mut _commands:  Commands,
-    mut player:     Query<(&mut Transform, &mut Inventory), With<UserControl>>,
+    mut player:     Query<(&mut Transform, &mut Inventory, Entity), With<UserControl>>,
     mut event:      EventReader<ConsoleInput>,
+    mut xp_event:   EventWriter<ExperienceGain>,
+    mut sp_event:   EventWriter<SpendSkillPoint>,
+    mut save_event: EventWriter<SaveWorld>,
+    mut load_event: EventWriter<LoadWorld>,
         registry:   Res<Registry>
 ) {
     if event.is_empty() {
@@ -145,19 +160,9 @@ pub fn cmd_execute(
                                         if let Ok(mut player) = player.get_single_mut() {
                                             if let Ok(y) = y_str.parse::<usize>() {
                                                 if let Some(info) = registry.get_item_info("bullet") {
-                                                    if player.1.add_ex(ItemEntity { 
-                                                        name:       info.id_name.clone(), 
-                                                        id_name:    info.id_name.clone(),
-                                                        id_source:  info.id_source.clone(),
-                                                        item_type:  info.item_type.clone(),
-                                                        durability: info.durability.clone(), 
-                                                        stack_size: info.stack_size.clone(), 
-                                                        stackable:  info.stackable.clone(), 
-                                                        count: y
-                                                    }) {
-                                                        println!("Пользователю - [] выдано [] в размере {}", y)
-                                                    } else {
-                                                        println!("Не удалось добавить предмет в инвентарь")
+                                                    match player.1.add_ex(ItemEntity::from_info(info, y)) {
+                                                        Ok(()) => println!("Пользователю - [] выдано [] в размере {}", y),
+                                                        Err(reason) => println!("Не удалось добавить предмет в инвентарь: {}", reason)
                                                     }
                                                 }
                                             }
@@ -171,6 +176,51 @@ pub fn cmd_execute(
                         }
                     }
                     
+                    "/xp" => {
+                        println!("/xp");
+
+                        if let Some(x_str) = parts.get(1) {
+                            if let Ok(x) = x_str.parse::<u32>() {
+                                if let Ok(player) = player.get_single() {
+                                    xp_event.send(ExperienceGain(player.2, x, XpSource::Other));
+                                }
+                            }
+                        }
+                    }
+
+                    "/spend" => {
+                        println!("/spend");
+
+                        if let Some(x_str) = parts.get(1).map(|s| *s) {
+                            if let Ok(player) = player.get_single() {
+                                let target = match x_str {
+                                    "str" => SkillPointTarget::Stat(StatType::Str),
+                                    "dex" => SkillPointTarget::Stat(StatType::Dex),
+                                    "end" => SkillPointTarget::Stat(StatType::End),
+                                    "int" => SkillPointTarget::Stat(StatType::Int),
+                                    skill => SkillPointTarget::Skill(skill.to_string()),
+                                };
+                                sp_event.send(SpendSkillPoint(player.2, target));
+                            }
+                        }
+                    }
+
+                    "/save" => {
+                        println!("/save");
+
+                        if let Some(name) = parts.get(1) {
+                            save_event.send(SaveWorld(name.to_string()));
+                        }
+                    }
+
+                    "/load" => {
+                        println!("/load");
+
+                        if let Some(name) = parts.get(1) {
+                            load_event.send(LoadWorld(name.to_string()));
+                        }
+                    }
+
                     _ => {
                         println!("Неизвестная команда")
                     }
@@ -9,6 +9,11 @@ use crate::core::{
     UserSystem::UserControl,
     Camera::UserCamera,
     ContainerSystem::Slot,
+    interact::Interact::{
+        Interactable,
+        InteractionKind,
+        InteractObject
+    },
     AppState,
 };
 
@@ -22,6 +27,7 @@ pub fn context_menu_plugin(app: &mut App) {
         (
                 interact_with_about_context_button,
                 interact_with_take_context_button,
+                interact_with_object_context_button,
                 track_context.after(track_pos_context)
             ).run_if(in_state(AppState::Game))
         );
@@ -39,7 +45,8 @@ struct ContextUIPos {
 // ==============================
 
 fn build_context_menu(
-    commands:   &mut Commands,
+    commands:       &mut Commands,
+    interactions:   &[InteractionKind],
 ) -> Entity {
     commands.spawn((
         NodeBundle {
@@ -127,6 +134,38 @@ fn build_context_menu(
             });
         });
 
+        // === Interactions
+
+        for kind in interactions {
+            parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        display:    Display::Grid,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color:   Color::rgb(0.21, 0.21, 0.21).into(),
+                    ..default()
+                },
+                InteractButton(*kind),
+                Name::new(format!("{:?}Btn", kind))
+            )).with_children(|parent| {
+                parent.spawn(TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            kind.label(),
+                            TextStyle {
+                                font_size: 11.0,
+                                ..default()
+                            },
+                        )],
+                        ..default()
+                    },
+                    ..default()
+                });
+            });
+        }
+
         // === Atack
 
 
@@ -176,7 +215,7 @@ fn track_pos_context(
 
 fn def_context_menu(
     mut commands:   Commands,
-        query:      Query<Entity, With<Selected>>,
+        query:      Query<Option<&Interactable>, With<Selected>>,
         context:    Query<Entity, With<ContextMenu>>,
         key_input:  Res<ButtonInput<KeyCode>>
 ) {
@@ -192,7 +231,8 @@ fn def_context_menu(
     
     if !query.is_empty() && context.is_empty() {
         if key_input.just_pressed(KeyCode::AltLeft) {
-            build_context_menu(&mut commands);
+            let interactions = query.get_single().ok().flatten().map_or(Vec::new(), |interactable| interactable.0.clone());
+            build_context_menu(&mut commands, &interactions);
         }
     }
 }
@@ -290,4 +330,43 @@ fn interact_with_take_context_button(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Кнопка взаимодействия с выделенным объектом
+#[derive(Component)]
+struct InteractButton(InteractionKind);
+
+fn interact_with_object_context_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor, &InteractButton),
+        Changed<Interaction>,
+    >,
+    mut event: EventWriter<InteractObject>,
+        select:Query<Entity, With<Selected>>,
+        user:  Query<Entity, With<UserControl>>,
+) {
+    if button_query.is_empty() {
+        return;
+    }
+
+    for (interaction, mut background_color, button) in &mut button_query {
+        match *interaction {
+
+            Interaction::Pressed => {
+                *background_color = Color::rgb(0.19, 0.19, 0.19).into();
+
+                if let (Ok(entity), Ok(selected)) = (user.get_single(), select.get_single()) {
+                    event.send(InteractObject(entity, selected, button.0));
+                }
+            }
+
+            Interaction::Hovered => {
+                *background_color = Color::rgb(0.24, 0.24, 0.24).into();
+            }
+
+            Interaction::None => {
+                *background_color = Color::rgb(0.21, 0.21, 0.21).into();
+            }
+        }
+    }
+}
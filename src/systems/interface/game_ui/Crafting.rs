@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    ContainerSystem::Inventory,
+    CraftSystem::{
+        CraftQueue,
+        CraftRequest,
+        CraftCancel,
+        can_craft,
+        workstation_near
+    },
+    entities::ai::Job::{
+        JobKind,
+        PostJob
+    },
+    resource::Registry::Registry,
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    Entity::EntityBase,
+    interact::Interact::Reserved,
+    UserSystem::UserControl,
+    AppState
+};
+
+use super::GameUI;
+
+//
+//
+//
+
+pub fn crafting_ui_plugin(app: &mut App) {
+    app.add_systems(Update,
+        CraftingPanel::toggle_crafting_window.run_if(in_state(AppState::Game))
+    );
+}
+
+// ========== Crafting ==========
+pub struct CraftingPanel;
+
+impl CraftingPanel {
+    // ==========
+    // Окно крафта игрока (открывается кнопкой крафта)
+    // ==========
+    fn toggle_crafting_window(
+        mut contexts:       EguiContexts,
+        mut craft_event:    EventWriter<CraftRequest>,
+        mut cancel_event:   EventWriter<CraftCancel>,
+        mut job_event:      EventWriter<PostJob>,
+            game_ui:        Query<&GameUI>,
+            player:         Query<(Entity, &Inventory, &CraftQueue, &Transform, &EntityBase), With<UserControl>>,
+            stations:       Query<(Entity, &EntityObject, &Footprint, Option<&Reserved>)>,
+            registry:       Res<Registry>,
+    ) {
+        let Ok(game_ui) = game_ui.get_single() else {
+            return;
+        };
+
+        if !game_ui.crafting_toggle {
+            return;
+        }
+
+        let Ok((player, inventory, queue, transform, base)) = player.get_single() else {
+            return;
+        };
+
+        // Заказ крафта уходит рабочим на ближайшее к игроку рабочее место
+        let nearest_station = |workstation: &str| {
+            stations.iter()
+                .filter(|(_, object, _, _)| object.id_name == workstation)
+                .map(|(entity, _, station, _)| (entity, station.distance(transform.translation.truncate())))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(entity, _)| entity)
+        };
+
+        // Рецепты построек выполняются через режим строительства
+        let mut recipes: Vec<_> = registry.recipe_registry.values().filter(|recipe| !recipe.construction).collect();
+        recipes.sort_by(|a, b| a.id_name.cmp(&b.id_name));
+
+        egui::Window::new("Crafting")
+            .show(contexts.ctx_mut(), |ui| {
+                for recipe in recipes {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} x{}", recipe.result.output, recipe.result.count));
+
+                        for ingredient in &recipe.request {
+                            ui.label(format!("{} {}/{}", ingredient.item, inventory.count_of(&ingredient.item), ingredient.count));
+                        }
+                        for tool in &recipe.tools {
+                            ui.label(format!("[{}]", tool.item));
+                        }
+                        if let Some(workstation) = &recipe.workstation {
+                            ui.label(format!("@{}", workstation));
+                        }
+                        ui.label(format!("{:.1}s", recipe.craft_time));
+
+                        let near = workstation_near(
+                            recipe,
+                            player,
+                            transform.translation.truncate(),
+                            base.interaction_radius,
+                            stations.iter().map(|(_, object, station, reserved)| (object, station, reserved))
+                        );
+                        if ui.add_enabled(can_craft(inventory, recipe, near), egui::Button::new("Craft")).clicked() {
+                            craft_event.send(CraftRequest(player, recipe.id_name.clone()));
+                        }
+                        if let Some(station) = recipe.workstation.as_deref().and_then(|workstation| nearest_station(workstation)) {
+                            if ui.button("Order").clicked() {
+                                job_event.send(PostJob(JobKind::Craft, station, Some(recipe.id_name.clone())));
+                            }
+                        }
+                    });
+                }
+
+                if queue.tasks.is_empty() {
+                    return;
+                }
+
+                ui.separator();
+                for (index, task) in queue.tasks.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(&task.recipe);
+                        ui.add(egui::ProgressBar::new(task.progress()).desired_width(80.0));
+                        if ui.button("Cancel").clicked() {
+                            cancel_event.send(CraftCancel(player, index));
+                        }
+                    });
+                }
+            });
+    }
+}
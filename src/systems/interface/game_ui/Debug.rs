@@ -8,8 +8,20 @@ use bevy_egui::{
 use crate::core::{
     Entity::EntityBase,
     entities::EntitySystem::EnemySpawner, 
-    resource::Registry::Registry, 
-    world::World::WorldSystem, 
+    resource::Registry::{
+        Registry,
+        DefKind
+    },
+    world::World::{
+        WorldSystem,
+        WorldInfo
+    },
+    LightSystem::LightMap,
+    RoomSystem::Rooms,
+    WeatherSystem::{
+        Biome,
+        SetWeather
+    },
     UserSystem::{
         CursorMode, 
         CursorPlacer, 
@@ -31,6 +43,13 @@ pub fn debug_ui_plugin(app: &mut App) {
 // ========== DEBUG ==========
 pub struct DebugInfoPanel;
 
+/// Определение, выбранное для просмотра в меню отладки
+#[derive(Default)]
+struct DefInspector {
+    kind:       Option<DefKind>,
+    id_name:    String,
+}
+
 impl DebugInfoPanel {
     // ==========
     // Функционал для открытия дебага
@@ -41,9 +60,14 @@ impl DebugInfoPanel {
         mut spawners:       Query<&mut EnemySpawner, With<EnemySpawner>>,
         mut placer:         ResMut<CursorPlacer>,
         mut cursor_mode:    ResMut<CursorMode>,
+        mut clock:          ResMut<WorldInfo>,
+        mut weather_event:  EventWriter<SetWeather>,
+        mut rooms:          ResMut<Rooms>,
             registry:       Res<Registry>,
+            light_map:      Res<LightMap>,
             player:         Query<&EntityBase, With<UserControl>>,
             keyboard_input: Res<ButtonInput<KeyCode>>,
+        mut inspector:      Local<DefInspector>,
     ) {
         if parent_query.is_empty() {
             return;
@@ -64,6 +88,16 @@ impl DebugInfoPanel {
                                 ui.label(format!("Pos: {}", player_pos.position.0));
                                 ui.label(format!("Pos_T: {}", WorldSystem::get_currect_chunk_tile(player_pos.position.0.as_ivec2())));
                                 ui.label(format!("Pos_C: {}", WorldSystem::get_current_chunk(player_pos.position.0.as_ivec2())));
+                                ui.label(format!("Light: {:.2}", light_map.level_at_position(player_pos.position.0)));
+
+                                let tile = WorldSystem::get_currect_chunk_tile(player_pos.position.0.as_ivec2());
+                                match rooms.room_at(tile) {
+                                    Some(room) => ui.label(format!(
+                                        "Room: {} tiles, roof {:.0}%, beauty {:.2}, clean {:.2}",
+                                        room.size(), room.roof_coverage() * 100.0, room.stats.beauty, room.stats.cleanliness
+                                    )),
+                                    None => ui.label("Room: outdoors"),
+                                };
                             });
                         }
 
@@ -75,6 +109,8 @@ impl DebugInfoPanel {
                             }
                         });
 
+                        ui.checkbox(&mut rooms.overlay, "Rooms");
+
                         if ui.button("menu").clicked() {
                             game_ui.debug_menu = !game_ui.debug_menu;
                         }
@@ -107,7 +143,33 @@ impl DebugInfoPanel {
                             for key in registry.object_ct_registry.keys() {
                                 if ui.button(key).clicked() {
                                     *cursor_mode = CursorMode::Placer;
-                                    placer.placer = Some(("object".to_string(), key.clone()));
+                                    placer.placer = Some(("object_ct".to_string(), key.clone()));
+                                }
+                            }
+                        });
+
+                        ui.label("Plants");
+                        ui.horizontal(|ui| {
+                            for key in registry.plant_registry.keys() {
+                                if ui.button(key).clicked() {
+                                    *cursor_mode = CursorMode::Placer;
+                                    placer.placer = Some(("plant".to_string(), key.clone()));
+                                }
+                            }
+                        });
+
+                        ui.label("Weather");
+                        ui.horizontal(|ui| {
+                            for key in registry.weather_registry.keys() {
+                                if ui.button(key).clicked() {
+                                    weather_event.send(SetWeather(key.clone()));
+                                }
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            for biome in Biome::ALL {
+                                if ui.selectable_label(clock.biome == biome, biome.label()).clicked() {
+                                    clock.biome = biome;
                                 }
                             }
                         });
@@ -121,6 +183,36 @@ impl DebugInfoPanel {
                                 }
                             }
                         });
+
+                        // Поля определения и модуль, последним их изменивший
+                        ui.collapsing("Defs", |ui| {
+                            let kind = inspector.kind.unwrap_or(DefKind::Item);
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_source("def_kind")
+                                    .selected_text(kind.label())
+                                    .show_ui(ui, |ui| {
+                                        for option in DefKind::ALL {
+                                            ui.selectable_value(&mut inspector.kind, Some(option), option.label());
+                                        }
+                                    });
+                                egui::ComboBox::from_id_source("def_id")
+                                    .selected_text(inspector.id_name.clone())
+                                    .show_ui(ui, |ui| {
+                                        for id_name in registry.def_ids(kind) {
+                                            ui.selectable_value(&mut inspector.id_name, id_name.clone(), id_name);
+                                        }
+                                    });
+                            });
+
+                            egui::Grid::new("def_fields").striped(true).show(ui, |ui| {
+                                for (field, value, module) in registry.def_fields(kind, &inspector.id_name) {
+                                    ui.label(field);
+                                    ui.label(value.to_string().chars().take(48).collect::<String>());
+                                    ui.label(module);
+                                    ui.end_row();
+                                }
+                            });
+                        });
                     });
                 
             }
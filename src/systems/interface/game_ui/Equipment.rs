@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    ContainerSystem::{
+        Equipment,
+        EquipSlot,
+        EquipItem,
+        UnequipItem
+    },
+    UserSystem::UserControl,
+    AppState
+};
+
+use super::{
+    Inventory::InventoryDisplaySlot,
+    GameUI
+};
+
+//
+//
+//
+
+pub fn equipment_ui_plugin(app: &mut App) {
+    app.add_systems(Update,
+        (
+            EquipmentPanel::equip_hovered_slot,
+            EquipmentPanel::toggle_equipment_window
+        ).run_if(in_state(AppState::Game))
+    );
+}
+
+// ========== Equipment ==========
+pub struct EquipmentPanel;
+
+impl EquipmentPanel {
+    // ==========
+    // Экипировка предмета под курсором в инвентаре по клавише F
+    // ==========
+    fn equip_hovered_slot(
+            player:         Query<Entity, (With<UserControl>, With<Equipment>)>,
+            slots:          Query<(&Interaction, &InventoryDisplaySlot)>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+        mut equip_event:    EventWriter<EquipItem>,
+    ) {
+        if !keyboard_input.just_pressed(KeyCode::KeyF) {
+            return;
+        }
+
+        let Ok(player) = player.get_single() else {
+            return;
+        };
+
+        for (interaction, slot) in &slots {
+            if *interaction == Interaction::Hovered && slot.owner == player && slot.slot.is_some() {
+                equip_event.send(EquipItem(player, slot.index, None));
+            }
+        }
+    }
+
+    // ==========
+    // Окно экипировки игрока (открывается кнопкой аватара)
+    // ==========
+    fn toggle_equipment_window(
+        mut contexts:       EguiContexts,
+        mut unequip_event:  EventWriter<UnequipItem>,
+            game_ui:        Query<&GameUI>,
+            player:         Query<(Entity, &Equipment), With<UserControl>>,
+    ) {
+        let Ok(game_ui) = game_ui.get_single() else {
+            return;
+        };
+
+        if !game_ui.equipment_toggle {
+            return;
+        }
+
+        let Ok((player, equipment)) = player.get_single() else {
+            return;
+        };
+
+        egui::Window::new("Equipment")
+            .show(contexts.ctx_mut(), |ui| {
+                for slot in EquipSlot::ALL {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{:?}:", slot));
+
+                        if slot == EquipSlot::LeftHand && equipment.two_handed {
+                            ui.label("(two-handed)");
+                            return;
+                        }
+
+                        match equipment.get(slot) {
+                            Some(item) => {
+                                ui.label(item.display_name());
+                                if let Some(durability) = item.instance.durability {
+                                    ui.label(format!("[{}]", durability));
+                                }
+                                if ui.button("Unequip").clicked() {
+                                    unequip_event.send(UnequipItem(player, slot));
+                                }
+                            },
+                            None => {
+                                ui.label("-");
+                            }
+                        }
+                    });
+                }
+            });
+    }
+}
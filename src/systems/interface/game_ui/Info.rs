@@ -35,8 +35,8 @@ pub fn info_item_panel(
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(format!("ID: {}", info.id_name));
-                        ui.label(format!("Name: {}", info.name));
-                        ui.label(format!("Health: {:?}", info.durability));
+                        ui.label(format!("Name: {}", info.instance.display_name(&info.name)));
+                        ui.label(format!("Health: {:?}", info.instance.durability));
                     });
 
                     ui.vertical(|ui| {
@@ -81,8 +81,9 @@ pub fn cursor_grab(
 
                     match info.item_type {
 
-                        ItemType::Item(_) | ItemType::None => {
-                            if let Some(img) = atlas.get_texture(AtlasType::Items, &info.id_texture) {
+                        ItemType::Item(_) | ItemType::None | ItemType::Tool(_) => {
+                            let atlas_type = if let ItemType::Tool(_) = info.item_type { AtlasType::Tools } else { AtlasType::Items };
+                            if let Some(img) = atlas.get_texture(atlas_type, &info.id_texture) {
                                 commands.spawn((
                                     ImageBundle {
                                         style: Style {
@@ -166,10 +167,6 @@ pub fn cursor_grab(
                                 });
                             }
                         },
-    
-                        ItemType::Tool(_) => {
-                            
-                        }
                     }
                 }
             }
@@ -11,7 +11,12 @@ use crate::core::{
         ItemTypeEx,
         CursorContainer,
         InventoryItemSlot,
+        SortMode,
+        InventoryError,
+        drop_slots,
     },
+    Item::ItemSpawn,
+    Entity::EntityBase,
     UserSystem::UserControl,
     resource::{
         graphic::Atlas::{
@@ -102,113 +107,188 @@ pub(crate) fn toggle_inventory_open<I: ItemTypeEx>(
     mut inv_toggle: EventReader<InventoryDisplayToggleEvent>,
     mut bar_gui:    Query<(Entity, &mut BarGui), With<BarGui>>,
         game_ui:    Query<&GameUI, With<GameUI>>,
-        user:       Query<&Inventory>,
+        user:       Query<(&Inventory, Has<UserControl>)>,
+    mut focused:    ResMut<FocusedStorage>,
+        player:     Query<Entity, (With<UserControl>, With<Inventory>)>,
         inv_displ:  Query<(Entity, &InventoryDisplayOwner)>,
 ) {
-    // if bar_gui.is_empty() || game_ui.is_empty() || user.is_empty() {
-    //     return;
-    // }
-
     if inv_toggle.is_empty() {
         return;
     }
     
     for e in inv_toggle.read() {
+        let Ok(game_ui) = game_ui.get_single() else {
+            continue;
+        };
 
-        // Inventory Spawn
-        if let Ok(game_ui) = game_ui.get_single() {
-            if game_ui.bargui_is_open {
-                if let Ok(mut bar_gui) = bar_gui.get_single_mut() {
+        if !game_ui.bargui_is_open {
+            continue;
+        }
 
-                    let inventory = if let Ok(player) = user.get(e.actor) {
-                        player
-                    } else {
-                        bevy::log::error!("InventoryDisplayToggleEvent with invalif actor_id (missing EquipmentDisplay, Inventory)");
-                        return;
-                    };
-            
-                    if let Some((inventory_display_entity, _)) =
-                        inv_displ.iter().find(|(_, o)| o.actor == e.actor)
-                    {
-                        cmd.entity(inventory_display_entity).despawn_recursive();
-                        bar_gui.1.inventory_open = !bar_gui.1.inventory_open;
-                        
-                        return;
+        let Ok(mut bar_gui) = bar_gui.get_single_mut() else {
+            continue;
+        };
+
+        let (inventory, is_user) = if let Ok(actor) = user.get(e.actor) {
+            actor
+        } else {
+            bevy::log::error!("InventoryDisplayToggleEvent with invalif actor_id (missing EquipmentDisplay, Inventory)");
+            continue;
+        };
+
+        // Inventory Despawn
+        if let Some((inventory_display_entity, _)) =
+            inv_displ.iter().find(|(_, o)| o.actor == e.actor)
+        {
+            cmd.entity(inventory_display_entity).despawn_recursive();
+
+            // Вместе с инвентарём игрока закрываются и открытые хранилища
+            if is_user {
+                for (storage_display_entity, owner) in &inv_displ {
+                    if owner.actor != e.actor {
+                        cmd.entity(storage_display_entity).despawn_recursive();
                     }
+                }
+                bar_gui.1.inventory_open = false;
+            }
+            
+            continue;
+        }
 
-                    if !bar_gui.1.inventory_open {
-                        cmd.entity(bar_gui.0).with_children(|parent| {
-                            parent.spawn((
-                                Name::new("Inventory"),
-                                InventoryDisplayOwner   { actor: e.actor },
-                                InventoryDisplayNode    { id: e.actor },
-                                NodeBundle {
-                                    style: Style {
-                                        display:    Display::Grid,
-                                        left:       Val::Px(-179.0),
-                                        bottom:     Val::Px(3.0),
-                                        width:      Val::Px(136.0),
-                                        height:     Val::Px(104.0),
-                                        border:     UiRect::all(Val::Px(4.0)),
-                                        grid_template_columns: vec![GridTrack::px(32.), GridTrack::px(32.), GridTrack::px(32.), GridTrack::px(32.)],
-                                        grid_template_rows: vec![
-                                            GridTrack::px(32.),
-                                            GridTrack::px(32.)
-                                        ],
-                                        ..default()
-                                    },
-                                    background_color:   Color::rgb(0.13, 0.13, 0.13).into(),
-                                    border_color:       Color::rgb(0.19, 0.19, 0.19).into(),
-                                    ..default()
-                                },
-                                Inventory::with_capacity(12)
-                            )).with_children(|slots| {
-                                for index in 0..inventory.len() {
-                                    slots.spawn((
-                                        Name::new(format!("Slot {index}")),
-                                        InventoryDisplaySlot { index, slot: None },
-                                        Interaction::default(),
-                                        NodeBundle {
-                                            style: Style {
-                                                display: Display::Grid,
-                                                border: UiRect { 
-                                                    left:   Val::Px(5.), 
-                                                    right:  Val::Px(5.), 
-                                                    top:    Val::Px(5.), 
-                                                    bottom: Val::Px(5.) 
-                                                },
-                                                aspect_ratio: Some(1.0),
-                                                ..default()
-                                            },
-                                            background_color:   Color::rgb(0.24, 0.24, 0.24).into(),
-                                            border_color:       Color::rgb(0.13, 0.13, 0.13).into(),
-                                            ..default()
-                                        }
-                                    ));
-                                }
-                            }).with_children(|parent| {
-                                parent.spawn(
-                                    NodeBundle {
-                                        style: Style {
-                                            position_type:  PositionType::Absolute,
-                                            left:           Val::Px(-4.0),
-                                            bottom:         Val::Px(100.0),
-                                            width:          Val::Px(136.0),
-                                            height:         Val::Px(20.0),
-                                            ..default()
-                                        },
-                                        background_color:   Color::rgb(0.19, 0.19, 0.19).into(),
-                                        ..default()
-                                    }
-                                );
-                            });
-                        });
-
-                        bar_gui.1.inventory_open = !bar_gui.1.inventory_open;
+        // Inventory Spawn
+        if is_user {
+            spawn_inventory_display(&mut cmd, bar_gui.0, e.actor, inventory.len(), false);
+            bar_gui.1.inventory_open = true;
+        } else {
+            // Хранилище открывается вторым окном над инвентарём игрока
+            if !bar_gui.1.inventory_open {
+                if let Ok(player) = player.get_single() {
+                    if let Ok((player_inventory, _)) = user.get(player) {
+                        spawn_inventory_display(&mut cmd, bar_gui.0, player, player_inventory.len(), false);
+                        bar_gui.1.inventory_open = true;
                     }
                 }
             }
-        }                    
+
+            spawn_inventory_display(&mut cmd, bar_gui.0, e.actor, inventory.len(), true);
+            focused.0 = Some(e.actor);
+        }
+    }
+}
+
+/// Кол-во колонок в окне инвентаря
+const INVENTORY_COLUMNS: usize = 4;
+
+/// Стиль окна инвентаря под кол-во слотов (у хранилища окно располагается над инвентарём игрока)
+fn inventory_display_style(len: usize, storage: bool) -> Style {
+    let rows = len.div_ceil(INVENTORY_COLUMNS).max(1);
+
+    Style {
+        display:    Display::Grid,
+        position_type: if storage { PositionType::Absolute } else { PositionType::Relative },
+        left:       Val::Px(-179.0),
+        bottom:     if storage { Val::Px(140.0) } else { Val::Px(3.0) },
+        width:      Val::Px(136.0),
+        height:     Val::Px(rows as f32 * 32.0 + 8.0),
+        border:     UiRect::all(Val::Px(4.0)),
+        grid_template_columns: vec![GridTrack::px(32.); INVENTORY_COLUMNS],
+        grid_template_rows: vec![GridTrack::px(32.); rows],
+        ..default()
+    }
+}
+
+/// Размещение окна инвентаря для `actor` дочерним элементом `bar_gui`
+fn spawn_inventory_display(
+    cmd:        &mut Commands,
+    bar_gui:    Entity,
+    actor:      Entity,
+    len:        usize,
+    storage:    bool,
+) {
+    cmd.entity(bar_gui).with_children(|parent| {
+        parent.spawn((
+            Name::new(if storage { "Storage" } else { "Inventory" }),
+            InventoryDisplayOwner   { actor },
+            InventoryDisplayNode    { id: actor },
+            NodeBundle {
+                style:              inventory_display_style(len, storage),
+                background_color:   Color::rgb(0.13, 0.13, 0.13).into(),
+                border_color:       Color::rgb(0.19, 0.19, 0.19).into(),
+                ..default()
+            },
+        )).with_children(|slots| {
+            spawn_inventory_slots(slots, actor, len);
+        });
+    });
+}
+
+/// Слоты окна инвентаря и его заголовок
+fn spawn_inventory_slots(slots: &mut ChildBuilder, owner: Entity, len: usize) {
+    for index in 0..len {
+        slots.spawn((
+            Name::new(format!("Slot {index}")),
+            InventoryDisplaySlot { owner, index, slot: None },
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    border: UiRect { 
+                        left:   Val::Px(5.), 
+                        right:  Val::Px(5.), 
+                        top:    Val::Px(5.), 
+                        bottom: Val::Px(5.) 
+                    },
+                    aspect_ratio: Some(1.0),
+                    ..default()
+                },
+                background_color:   Color::rgb(0.24, 0.24, 0.24).into(),
+                border_color:       Color::rgb(0.13, 0.13, 0.13).into(),
+                ..default()
+            }
+        ));
+    }
+
+    let rows = len.div_ceil(INVENTORY_COLUMNS).max(1);
+    slots.spawn(
+        NodeBundle {
+            style: Style {
+                position_type:  PositionType::Absolute,
+                left:           Val::Px(-4.0),
+                bottom:         Val::Px(rows as f32 * 32.0 + 4.0),
+                width:          Val::Px(136.0),
+                height:         Val::Px(20.0),
+                ..default()
+            },
+            background_color:   Color::rgb(0.19, 0.19, 0.19).into(),
+            ..default()
+        }
+    );
+}
+
+/// Закрытие окон хранилищ, которые были уничтожены или от которых игрок отошёл
+pub(crate) fn close_distant_storage(
+    mut cmd:        Commands,
+        inv_displ:  Query<(Entity, &InventoryDisplayOwner)>,
+        storages:   Query<&GlobalTransform, (With<Inventory>, Without<UserControl>)>,
+        player:     Query<(Entity, &GlobalTransform, &EntityBase), With<UserControl>>,
+) {
+    let player = player.get_single().ok();
+
+    for (display_entity, owner) in &inv_displ {
+        if player.is_some_and(|(player, _, _)| player == owner.actor) {
+            continue;
+        }
+
+        let close = match (storages.get(owner.actor), player) {
+            (Ok(storage), Some((_, transform, base))) => {
+                storage.translation().truncate().distance(transform.translation().truncate()) > base.interaction_radius
+            },
+            _ => true
+        };
+
+        if close {
+            cmd.entity(display_entity).despawn_recursive();
+        }
     }
 }
 
@@ -218,22 +298,37 @@ pub(crate) fn inventory_update<I: ItemTypeEx>(
         register:           Res<Registry>,
         atlas:              Res<AtlasRes>,
         game_ui:            Query<&GameUI, With<GameUI>>,
-        inv_displ_nodes:    Query<(&InventoryDisplayNode, &Children)>,
-        player_inv:         Query<&Inventory, With<UserControl>>,
+    mut node_styles:        Query<&mut Style, With<InventoryDisplayNode>>,
+        inv_displ_nodes:    Query<(Entity, &InventoryDisplayNode, &Children)>,
+        inventories:        Query<&Inventory>,
 ) {
     if game_ui.is_empty() {
         return;
     }
     
     // Прогон по узлу со слотами и их дочерними элементами
-    for (display_node, display_node_children) in inv_displ_nodes.iter() {
-        let inventory = if let Ok(inventory) = player_inv.get(display_node.id) {
+    for (node_entity, display_node, display_node_children) in inv_displ_nodes.iter() {
+        let inventory = if let Ok(inventory) = inventories.get(display_node.id) {
             inventory
         } else {
             bevy::log::error!("InventoryDisplayNode without associated Inventory");
             continue;
         };
 
+        // Вместимость изменилась (например, надет рюкзак) - слоты перестраиваются
+        let slots_count = display_node_children.iter().filter(|child| inv_slots.contains(**child)).count();
+        if slots_count != inventory.len() {
+            if let Ok(mut style) = node_styles.get_mut(node_entity) {
+                let storage = style.position_type == PositionType::Absolute;
+                *style = inventory_display_style(inventory.len(), storage);
+            }
+
+            cmd.entity(node_entity)
+                .despawn_descendants()
+                .with_children(|slots| spawn_inventory_slots(slots, display_node.id, inventory.len()));
+            continue;
+        }
+
         for &slot_entity in display_node_children.iter() {
             let mut slot = if let Ok(slot) = inv_slots.get_mut(slot_entity) {
                 slot
@@ -267,8 +362,9 @@ pub(crate) fn inventory_update<I: ItemTypeEx>(
 
                         match info.item_type {
                             
-                            ItemType::Item(_) | ItemType::None => {
-                                if let Some(img) = atlas.get_texture(AtlasType::Items, &info.id_texture) {
+                            ItemType::Item(_) | ItemType::None | ItemType::Tool(_) => {
+                                let atlas_type = if let ItemType::Tool(_) = info.item_type { AtlasType::Tools } else { AtlasType::Items };
+                                if let Some(img) = atlas.get_texture(atlas_type, &info.id_texture) {
                                     slot_cmd.with_children(|cb| {
                                         cb.spawn((
                                             ImageBundle {
@@ -340,10 +436,6 @@ pub(crate) fn inventory_update<I: ItemTypeEx>(
                                     );
                                 }
                             },
-        
-                            ItemType::Tool(_) => {
-                                
-                            }
                         }
                     }
                 }
@@ -355,39 +447,97 @@ pub(crate) fn inventory_update<I: ItemTypeEx>(
     }
 }
 
+/// Клик по слоту инвентаря.
+///
+/// - ЛКМ - взять / положить / объединить / обменять с курсором
+/// - Shift + ЛКМ - быстрый перенос стака: из хранилища - игроку, от игрока - в последнее открытое или выбранное курсором хранилище
+/// - Ctrl + ЛКМ - взять половину стака в курсор
+/// - Alt + ЛКМ - сортировка инвентаря (с Shift - по имени)
+/// - Ctrl + Shift + ЛКМ - выбросить всё содержимое инвентаря на землю
 pub fn inventory_click_item(
     mut cursor_inv:     ResMut<CursorContainer>,
-    mut player_inv:     Query<&mut Inventory, With<UserControl>>,
+    mut inventories:    Query<(&mut Inventory, Option<&GlobalTransform>)>,
     mut interact_slots: Query<(&Interaction, &mut BackgroundColor, &InventoryDisplaySlot), Changed<Interaction>>,
+    mut spawn_i:        EventWriter<ItemSpawn>,
+    mut focused:        ResMut<FocusedStorage>,
+        inv_displ:      Query<&InventoryDisplayOwner>,
+        player:         Query<Entity, With<UserControl>>,
+        keyboard:       Res<ButtonInput<KeyCode>>,
 ) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let alt = keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let player = player.get_single().ok();
+
     for (interaction, mut color, slot) in &mut interact_slots {
+        if *interaction != Interaction::None && Some(slot.owner) != player {
+            focused.0 = Some(slot.owner);
+        }
+
         match *interaction {
             Interaction::Pressed => {
-                if let Ok(mut inv) = player_inv.get_single_mut() {
-                    // Буферная переменная для временного хранения предмета из курсора
-                    let mut buffer_item = None;
-
-                    // Если в курсоре есть предмет
-                    if let Some(cursor_item) = cursor_inv.slot.take() {
-                        // Если в слоте есть предмет, помещаем его в курсор
-                        if let Some(slot_item) = inv.get_slot_mut(slot.index).and_then(|slot_mut| slot_mut.take()) {
-                            cursor_inv.slot = Some(slot_item);
-                            // Помещаем предмет из курсора в буфер
-                            buffer_item = Some(cursor_item);
-                        } else {
-                            // Если в слоте нет предмета, помещаем предмет из курсора в слот
-                            inv.add_to_slot(slot.index, Some(cursor_item));
+                // Быстрый перенос между окнами
+                if shift && !ctrl && !alt {
+                    let storages: Vec<Entity> = inv_displ.iter().map(|owner| owner.actor).filter(|actor| Some(*actor) != player).collect();
+                    let target = match player {
+                        Some(player) if slot.owner != player => Some(player),
+                        _ => focused.0.filter(|storage| storages.contains(storage)).or(storages.last().copied()),
+                    };
+                    let Some(other) = target else {
+                        continue;
+                    };
+
+                    if let Ok([(mut from, _), (mut to, _)]) = inventories.get_many_mut([slot.owner, other]) {
+                        if let Err(err) = from.quick_move(slot.index, &mut to) {
+                            info!("Inventory - quick move: {}", err);
                         }
-                    } 
-                    // Если в курсоре нет предмета, но есть предмет в слоте, забираем его
-                    else if let Some(slot_item) = inv.get_slot_mut(slot.index).and_then(|slot_mut| slot_mut.take()) {
-                        cursor_inv.slot = Some(slot_item);
                     }
+                    continue;
+                }
+
+                // Предмет берётся из инвентаря владельца слота (игрок или хранилище)
+                let Ok((mut inv, transform)) = inventories.get_mut(slot.owner) else {
+                    continue;
+                };
 
-                    // Если буфер не пуст, помещаем его в слот
-                    if let Some(buffer_item) = buffer_item {
-                        inv.add_to_slot(slot.index, Some(buffer_item));
+                if ctrl && shift {
+                    if let Some(transform) = transform {
+                        drop_slots(inv.take_all(), transform.translation().truncate().as_ivec2(), &mut spawn_i);
+                    }
+                } else if alt {
+                    inv.sort(if shift { SortMode::ByName } else { SortMode::ByType });
+                } else if ctrl {
+                    // Половина стака в пустой курсор
+                    if cursor_inv.slot.is_none() {
+                        let half = inv.get_slot(slot.index)
+                            .and_then(|s| s.as_ref())
+                            .map_or(0, |s| s.count.div_ceil(2));
+
+                        match inv.split_off(slot.index, half) {
+                            Ok(part) => cursor_inv.slot = Some(part),
+                            Err(err) => info!("Inventory - split: {}", err),
+                        }
                     }
+                } else if let Some(cursor_item) = cursor_inv.slot.take() {
+                    // Объединение с тем же предметом, иначе обмен с курсором
+                    match inv.put_into(slot.index, cursor_item.clone()) {
+                        Ok(rest) => cursor_inv.slot = rest,
+                        Err(InventoryError::Mismatch) => match inv.replace_slot(slot.index, cursor_item.clone()) {
+                            Ok(previous) => cursor_inv.slot = previous,
+                            Err(err) => {
+                                info!("Inventory - swap: {}", err);
+                                cursor_inv.slot = Some(cursor_item);
+                            }
+                        },
+                        Err(err) => {
+                            info!("Inventory - put: {}", err);
+                            cursor_inv.slot = Some(cursor_item);
+                        }
+                    }
+                } 
+                // Если в курсоре нет предмета, но есть предмет в слоте, забираем его
+                else if let Some(slot_item) = inv.get_slot_mut(slot.index).and_then(|slot_mut| slot_mut.take()) {
+                    cursor_inv.slot = Some(slot_item);
                 }
             }
             Interaction::Hovered => {
@@ -410,6 +560,10 @@ pub struct InventoryDisplayOwner {
     pub actor: Entity,
 }
 
+/// Хранилище, окно которого игрок открыл или над которым держал курсор последним
+#[derive(Resource, Default)]
+pub struct FocusedStorage(pub Option<Entity>);
+
 /// Узел, содержащий дочерние элементы InventoryDisplaySlot
 #[derive(Debug, Clone, Component)]
 pub struct InventoryDisplayNode {
@@ -419,8 +573,10 @@ pub struct InventoryDisplayNode {
 
 // Для инвентаря
 // #[derive(Default, Debug, Clone, PartialEq, Eq, Component)]
-#[derive(Default, Debug, Clone, Component)]
+#[derive(Debug, Clone, Component)]
 pub struct InventoryDisplaySlot {
+    /// Владелец инвентаря, к которому относится слот
+    pub owner:  Entity,
     pub index:  usize,
     pub slot:   Option<Slot>,
 }
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    entities::ai::{
+        Pawn,
+        Job::{
+            JobBoard,
+            JobKind,
+            WorkPriorities
+        }
+    },
+    Entity::EntityBase,
+    AppState
+};
+
+use super::GameUI;
+
+//
+//
+//
+
+pub fn jobs_ui_plugin(app: &mut App) {
+    app.add_systems(Update,
+        (
+            JobsPanel::toggle_jobs_key,
+            JobsPanel::toggle_jobs_window
+        ).chain().run_if(in_state(AppState::Game))
+    );
+}
+
+// ========== Jobs ==========
+pub struct JobsPanel;
+
+impl JobsPanel {
+    // ==========
+    // Открытие окна работ по клавише J
+    // ==========
+    fn toggle_jobs_key(
+        mut game_ui:        Query<&mut GameUI>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+    ) {
+        if !keyboard_input.just_pressed(KeyCode::KeyJ) {
+            return;
+        }
+
+        if let Ok(mut game_ui) = game_ui.get_single_mut() {
+            game_ui.jobs_toggle = !game_ui.jobs_toggle;
+        }
+    }
+
+    // ==========
+    // Окно доски работ и приоритетов рабочих
+    // ==========
+    fn toggle_jobs_window(
+        mut contexts:   EguiContexts,
+        mut board:      ResMut<JobBoard>,
+        mut pawns:      Query<(Entity, &EntityBase, &mut WorkPriorities), With<Pawn>>,
+            game_ui:    Query<&GameUI>,
+    ) {
+        let Ok(game_ui) = game_ui.get_single() else {
+            return;
+        };
+
+        if !game_ui.jobs_toggle {
+            return;
+        }
+
+        egui::Window::new("Jobs")
+            .show(contexts.ctx_mut(), |ui| {
+                // Приоритеты рабочих: клик переключает 1 -> 2 -> 3 -> 4 -> 0 (не выполняет)
+                egui::Grid::new("work_priorities").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    for kind in JobKind::ALL {
+                        ui.label(kind.label());
+                    }
+                    ui.end_row();
+
+                    for (pawn, base, mut priorities) in &mut pawns {
+                        ui.label(format!("{} {:?}", base.id_name, pawn));
+                        for kind in JobKind::ALL {
+                            let priority = priorities.get(kind);
+                            let text = if priority == 0 { "-".to_string() } else { priority.to_string() };
+                            if ui.button(text).clicked() {
+                                priorities.cycle(kind);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                if board.jobs.is_empty() {
+                    return;
+                }
+
+                ui.separator();
+                for job in board.jobs.iter_mut() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} {}", job.kind.label(), job.recipe.as_deref().unwrap_or_default()));
+                        if ui.small_button("-").clicked() {
+                            job.priority = job.priority.lower();
+                        }
+                        ui.label(format!("{:?}", job.priority));
+                        if ui.small_button("+").clicked() {
+                            job.priority = job.priority.raise();
+                        }
+                        match job.claimed {
+                            Some(pawn) => ui.label(format!("{:?}", pawn)),
+                            None => ui.label("free"),
+                        };
+                    });
+                }
+            });
+    }
+}
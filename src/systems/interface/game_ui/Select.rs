@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::core::{
     Entity::EntityBase,
+    Object::EntityObject,
     resource::graphic::Atlas::{
         AtlasRes, 
         AtlasType
@@ -65,7 +66,7 @@ pub struct Selected;
 // Удаление выделения при тыке на место без объектов
 fn select_object(
     mut commands:           Commands,
-        entities:           Query<(Entity ,&Transform), With<EntityBase>>,
+        entities:           Query<(Entity ,&Transform), Or<(With<EntityBase>, With<EntityObject>)>>,
         cursor:             Res<CursorPosition>,
         keyboard_input:     Res<ButtonInput<KeyCode>>,
         mouse_buttons:      Res<ButtonInput<MouseButton>>,
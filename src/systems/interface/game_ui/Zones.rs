@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui,
+    EguiContexts
+};
+
+use crate::core::{
+    StockpileSystem::{
+        ItemCategory,
+        Stockpile
+    },
+    UserSystem::CursorMode,
+    AppState
+};
+
+use super::GameUI;
+
+//
+//
+//
+
+pub fn zones_ui_plugin(app: &mut App) {
+    app.add_systems(Update,
+        (
+            ZonesPanel::toggle_zones_key,
+            ZonesPanel::toggle_zones_window
+        ).chain().run_if(in_state(AppState::Game))
+    );
+}
+
+// ========== Zones ==========
+pub struct ZonesPanel;
+
+impl ZonesPanel {
+    // ==========
+    // Открытие окна складов по клавише Z
+    // ==========
+    fn toggle_zones_key(
+        mut game_ui:        Query<&mut GameUI>,
+            keyboard_input: Res<ButtonInput<KeyCode>>,
+    ) {
+        if !keyboard_input.just_pressed(KeyCode::KeyZ) {
+            return;
+        }
+
+        if let Ok(mut game_ui) = game_ui.get_single_mut() {
+            game_ui.zones_toggle = !game_ui.zones_toggle;
+        }
+    }
+
+    // ==========
+    // Окно складов: фильтры, приоритеты и выделение новых зон
+    // ==========
+    fn toggle_zones_window(
+        mut commands:       Commands,
+        mut contexts:       EguiContexts,
+        mut cursor_mode:    ResMut<CursorMode>,
+        mut stockpiles:     Query<(Entity, &mut Stockpile)>,
+        mut new_item:       Local<String>,
+            game_ui:        Query<&GameUI>,
+    ) {
+        let Ok(game_ui) = game_ui.get_single() else {
+            return;
+        };
+
+        if !game_ui.zones_toggle {
+            return;
+        }
+
+        egui::Window::new("Stockpiles")
+            .show(contexts.ctx_mut(), |ui| {
+                let designating = *cursor_mode == CursorMode::Zone;
+                if ui.selectable_label(designating, "Designate").clicked() {
+                    *cursor_mode = if designating { CursorMode::None } else { CursorMode::Zone };
+                }
+                if designating {
+                    ui.label("LMB drag - create, RMB - remove, Esc - exit");
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Item id");
+                    ui.text_edit_singleline(&mut *new_item);
+                });
+
+                for (entity, mut stockpile) in &mut stockpiles {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{:?} {}x{}", stockpile.area.0, stockpile.area.1.x, stockpile.area.1.y));
+                        if ui.small_button("-").clicked() {
+                            stockpile.priority = stockpile.priority.lower();
+                        }
+                        ui.label(format!("{:?}", stockpile.priority));
+                        if ui.small_button("+").clicked() {
+                            stockpile.priority = stockpile.priority.raise();
+                        }
+                        if ui.button("Remove").clicked() {
+                            commands.entity(entity).despawn_recursive();
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        for category in ItemCategory::ALL {
+                            let allowed = stockpile.filter.categories.contains(&category);
+                            if ui.selectable_label(allowed, category.label()).clicked() {
+                                stockpile.filter.toggle(category);
+                            }
+                        }
+                        if !new_item.is_empty() && ui.button("Allow id").clicked() && !stockpile.filter.items.contains(&*new_item) {
+                            stockpile.filter.items.push(new_item.clone());
+                        }
+                    });
+
+                    // Клик по разрешённому id убирает его из фильтра
+                    let mut removed = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for (index, item) in stockpile.filter.items.iter().enumerate() {
+                            if ui.small_button(item).clicked() {
+                                removed = Some(index);
+                            }
+                        }
+                    });
+                    if let Some(index) = removed {
+                        stockpile.filter.items.remove(index);
+                    }
+                }
+            });
+    }
+}
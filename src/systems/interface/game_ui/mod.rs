@@ -1,10 +1,16 @@
 pub mod BarGui;
+pub mod Build;
+pub mod Clock;
 pub mod Console;
 pub mod Context;
+pub mod Crafting;
 pub mod Debug;
+pub mod Equipment;
 pub mod Info;
 pub mod Inventory;
+pub mod Jobs;
 pub mod Select;
+pub mod Zones;
 
 use bevy::prelude::*;
 
@@ -37,6 +43,18 @@ pub fn game_ui_plugin(app: &mut App) {
     app.add_plugins(Debug::debug_ui_plugin);
     // GameUI === Console
     app.add_plugins(Console::console_plugin);
+    // GameUI === Equipment
+    app.add_plugins(Equipment::equipment_ui_plugin);
+    // GameUI === Crafting
+    app.add_plugins(Crafting::crafting_ui_plugin);
+    // GameUI === Build
+    app.add_plugins(Build::build_ui_plugin);
+    // GameUI === Jobs
+    app.add_plugins(Jobs::jobs_ui_plugin);
+    // GameUI === Zones
+    app.add_plugins(Zones::zones_ui_plugin);
+    // GameUI === Clock
+    app.add_plugins(Clock::clock_ui_plugin);
     // GameUI === ContextMenu
     app.add_plugins(Context::context_menu_plugin);
     app.add_systems(OnExit(AppState::Game), GameUI::despawn_game_ui);
@@ -51,7 +69,12 @@ pub struct GameUI {
     pub bargui_is_open: bool,
     pub console_toggle: bool,
     pub debug_toggle:   bool,
-    pub debug_menu:     bool
+    pub debug_menu:     bool,
+    pub equipment_toggle: bool,
+    pub crafting_toggle:  bool,
+    pub build_toggle:     bool,
+    pub jobs_toggle:      bool,
+    pub zones_toggle:     bool
 }
 
 #[allow(unused)]
@@ -94,7 +117,12 @@ impl GameUI {
                     bargui_is_open: false,
                     console_toggle: false,
                     debug_toggle:   false,
-                    debug_menu:     false
+                    debug_menu:     false,
+                    equipment_toggle: false,
+                    crafting_toggle:  false,
+                    build_toggle:     false,
+                    jobs_toggle:      false,
+                    zones_toggle:     false
                 },
                 Interaction::None,
                 Name::new("Game UI"),
@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 pub mod game_ui;
+pub mod LoadingUI;
 // pub mod LogoUi;
 pub mod MenuUI;
 pub mod Styles;
@@ -23,6 +24,8 @@ impl Plugin for UIPlugin {
             // Init Systems ==========
             .add_systems(Startup, Styles::setup_egui_style)
             // LogoUI
+            // LoadingUI
+            .add_systems(Update, LoadingUI::show_load_diagnostics.run_if(in_state(AppState::LoadingError)))
             // MenuUI
             .add_systems(OnEnter(AppState::MainMenu), MenuUI::MainMenu::spawn_main_menu)
             .add_systems(Update, (
@@ -5,7 +5,14 @@ pub mod interface;
 pub mod resource;
 pub mod world;
 
+pub mod BuildSystem;
 pub mod Camera;
 pub mod ContainerSystem;
+pub mod CraftSystem;
+pub mod LightSystem;
+pub mod PlantSystem;
 pub mod PlayerSystem;
+pub mod RoomSystem;
+pub mod StockpileSystem;
 pub mod UserSystem;
+pub mod WeatherSystem;
@@ -0,0 +1,205 @@
+use std::{
+    fmt,
+    path::{
+        Path,
+        PathBuf
+    }
+};
+
+use bevy::prelude::*;
+
+use serde::{
+    de::DeserializeOwned,
+    Serialize
+};
+
+use serde_json::Value;
+
+use crate::core::Debug::GameError;
+
+//
+//
+//
+
+/// Важность сообщения загрузки
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,    // Определение загружено, но с ошибкой в данных
+    Error,      // Определение или модуль не загружены
+}
+
+/// Сообщение о проблеме в определениях, текстурах или модулях
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity:   Severity,
+    pub file:       Option<PathBuf>,
+    pub line:       Option<usize>,
+    pub column:     Option<usize>,
+    pub field:      Option<String>,
+    pub message:    String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            file:       None,
+            line:       None,
+            column:     None,
+            field:      None,
+            message:    message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn in_file(mut self, file: Option<&Path>) -> Self {
+        self.file = file.map(Path::to_path_buf);
+        self
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_field(mut self, field: Option<impl Into<String>>) -> Self {
+        self.field = field.map(Into::into);
+        self
+    }
+
+    /// Файл со строкой и столбцом (`Data/Core/Defs/items/gun.json:3:5`)
+    pub fn location(&self) -> String {
+        let mut location = self.file.as_ref().map_or(String::new(), |file| file.display().to_string());
+        if let Some(line) = self.line {
+            location += &format!(":{}", line);
+            if let Some(column) = self.column {
+                location += &format!(":{}", column);
+            }
+        }
+        location
+    }
+}
+
+impl From<GameError> for Diagnostic {
+    fn from(error: GameError) -> Self {
+        Diagnostic::error(error.to_string())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.severity)?;
+        let location = self.location();
+        if !location.is_empty() {
+            write!(f, " {}", location)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " [{}]", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Сообщения последней загрузки ресурсов
+#[derive(Resource, Default, Clone)]
+pub struct LoadDiagnostics {
+    pub entries:    Vec<Diagnostic>,
+    pub strict:     bool,               // Ошибки останавливают загрузку
+}
+
+impl LoadDiagnostics {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.entries.iter().filter(|diagnostic| diagnostic.severity == severity).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    pub fn log(&self) {
+        for diagnostic in &self.entries {
+            match diagnostic.severity {
+                Severity::Error     => error!("{}", diagnostic),
+                Severity::Warning   => warn!("{}", diagnostic),
+            }
+        }
+    }
+}
+
+/// Строка и столбец первого вхождения ключа в json (с единицы)
+pub fn key_position(contents: &str, key: &str) -> Option<(usize, usize)> {
+    let offset = contents.find(&format!("\"{}\"", key))?;
+    let before = &contents[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |line| line.chars().count()) + 1;
+    Some((line, column))
+}
+
+/// Поле из текста ошибки serde (``missing field `health` ``)
+fn error_field(message: &str) -> Option<String> {
+    let start = message.find('`')? + 1;
+    let end = start + message[start..].find('`')?;
+    Some(message[start..end].to_string())
+}
+
+/// Поля входного json, которых нет в разобранном определении
+fn unknown_fields(input: &Value, known: &Value, prefix: &str, found: &mut Vec<String>) {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => {
+            for (key, value) in input {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match known.get(key) {
+                    Some(known) => unknown_fields(value, known, &path, found),
+                    None        => found.push(path),
+                }
+            }
+        },
+        (Value::Array(input), Value::Array(known)) => {
+            for (index, (value, known)) in input.iter().zip(known).enumerate() {
+                unknown_fields(value, known, &format!("{}[{}]", prefix, index), found);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Разбор json определения, ошибки разбора и неизвестные поля попадают в сообщения
+pub fn parse_json<T: Serialize + DeserializeOwned>(file: &Path, contents: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<T> {
+    let def: T = match serde_json::from_str(contents) {
+        Ok(def) => def,
+        Err(err) => {
+            let message = err.to_string();
+            diagnostics.push(
+                Diagnostic::error(message.clone())
+                    .in_file(Some(file))
+                    .at(err.line(), err.column())
+                    .with_field(error_field(&message))
+            );
+            return None;
+        }
+    };
+
+    if let (Ok(input), Ok(known)) = (serde_json::from_str::<Value>(contents), serde_json::to_value(&def)) {
+        let mut found = Vec::new();
+        unknown_fields(&input, &known, "", &mut found);
+
+        for field in found {
+            let key = field.rsplit('.').next().unwrap_or(&field).split('[').next().unwrap_or_default();
+            let mut diagnostic = Diagnostic::warning("unknown field is ignored").in_file(Some(file));
+            if let Some((line, column)) = key_position(contents, key) {
+                diagnostic = diagnostic.at(line, column);
+            }
+            diagnostics.push(diagnostic.with_field(Some(field)));
+        }
+    }
+
+    Some(def)
+}
@@ -0,0 +1,433 @@
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf
+    }
+};
+
+use bevy::{
+    asset::{
+        io::Reader,
+        AssetLoader,
+        LoadContext,
+        LoadedFolder
+    },
+    prelude::*,
+    utils::BoxedFuture
+};
+use bevy_rapier2d::prelude::*;
+
+use futures_lite::AsyncReadExt;
+
+use crate::core::{
+    resource::{
+        graphic::{
+            self,
+            Atlas::{
+                AtlasRes,
+                AtlasType
+            },
+            Connect::connected_index
+        },
+        Diagnostic::{
+            Diagnostic,
+            LoadDiagnostics
+        },
+        Module,
+        Registry::{
+            DefKind,
+            Registry
+        },
+        LoadingBuffer,
+        ResourceModule,
+        ResourcePlugin
+    },
+    Entity::{
+        EntityBase,
+        EntityHead
+    },
+    EntityAnimation::EntityDirectionState,
+    Attributes::MaxHealth,
+    stats::max_health,
+    Object::{
+        EntityObject,
+        PersistentObject
+    },
+    Door::Door,
+    AppState
+};
+
+//
+//
+//
+
+pub fn hot_reload_plugin(app: &mut App) {
+    app
+        // Init Assets
+        .init_asset::<DefFile>()
+        .register_asset_loader(DefFileLoader)
+        // Init Events
+        .add_event::<DefsReloaded>()
+        .add_event::<AtlasTexturesChanged>()
+        .add_event::<AtlasRebuilt>()
+        // Init Systems
+        .add_systems(OnExit(AppState::ResourceLoading), watch_def_folders)
+        .add_systems(Update,
+            (
+                reload_defs,
+                reload_textures,
+                remap_atlas_sprites,
+                refresh_live_defs
+            ).chain().run_if(not(in_state(AppState::ResourceCheck)))
+        );
+}
+
+//
+//
+//
+
+/// Файл определения как ассет, по его изменению определения перечитываются с диска
+#[derive(Asset, TypePath)]
+pub struct DefFile(pub String);
+
+#[derive(Default)]
+pub struct DefFileLoader;
+
+impl AssetLoader for DefFileLoader {
+    type Asset = DefFile;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader:         &'a mut Reader,
+        _settings:      &'a (),
+        _load_context:  &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut contents = String::new();
+            reader.read_to_string(&mut contents).await?;
+            Ok(DefFile(contents))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+/// Папки определений модулей, пока они загружены, их файлы отслеживаются
+#[derive(Resource, Default)]
+pub struct DefFolders(pub Vec<Handle<LoadedFolder>>);
+
+/// Ивент перечитанных определений (0 - Вид и id определений)
+#[derive(Event)]
+pub struct DefsReloaded(pub Vec<(DefKind, String)>);
+
+/// Ивент смены набора текстур атласов после перечитывания определений (0 - Атласы)
+#[derive(Event)]
+pub struct AtlasTexturesChanged(pub Vec<AtlasType>);
+
+/// Ивент пересборки атласа (0 - Атлас | 1 - Индексы текстур до пересборки)
+#[derive(Event)]
+pub struct AtlasRebuilt(pub AtlasType, pub HashMap<String, usize>);
+
+fn watch_def_folders(
+    mut commands:       Commands,
+        load_buff:      Res<LoadingBuffer>,
+        asset_server:   Res<AssetServer>,
+) {
+    let folders = load_buff.modules.iter()
+        .filter(|module| module.path.join("Defs").is_dir())
+        .map(|module| asset_server.load_folder(format!("{}://{}/Defs", Module::DATA_DIR, module.dir)))
+        .collect();
+
+    commands.insert_resource(DefFolders(folders));
+}
+
+/// Изменённые определения и патчи перечитываются полной загрузкой модулей в отдельный реестр,
+/// поэтому порядок модулей и патчи сохраняются. В реестр переносятся только изменившиеся определения
+///
+/// Определение, которое не разобралось или не прошло проверку, остаётся прежним до перезапуска, ошибка сообщается
+fn reload_defs(
+    mut register:       ResMut<Registry>,
+    mut load_buff:      ResMut<LoadingBuffer>,
+    mut event:          EventReader<AssetEvent<DefFile>>,
+    mut reloaded:       EventWriter<DefsReloaded>,
+    mut atlas_stale:    EventWriter<AtlasTexturesChanged>,
+        asset_server:   Res<AssetServer>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    let paths: Vec<PathBuf> = event.read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => asset_server.get_path(*id),
+            _ => None,
+        })
+        .map(|asset_path| Path::new(Module::DATA_DIR).join(asset_path.path()))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+
+    let mut scratch = Registry::new();
+    let mut scratch_buff = LoadingBuffer {
+        modules: load_buff.modules.clone(),
+        ..default()
+    };
+    ResourcePlugin::load_modules(&mut scratch, &mut scratch_buff);
+
+    // Определение, пропавшее из нового реестра, не разобралось, не прошло проверку или удалено из файла
+    let mut changed: Vec<(DefKind, String)> = Vec::new();
+    let mut kept: Vec<(DefKind, String)> = Vec::new();
+    for kind in DefKind::ALL {
+        let mut ids = register.def_ids(kind);
+        ids.extend(scratch.def_ids(kind));
+        ids.sort();
+        ids.dedup();
+
+        for id_name in ids {
+            let fields = scratch.def_fields(kind, &id_name);
+            if fields == register.def_fields(kind, &id_name) {
+                continue;
+            }
+            match register.take_def(&mut scratch, kind, &id_name) {
+                true  => changed.push((kind, id_name)),
+                false => kept.push((kind, id_name)),
+            }
+        }
+    }
+
+    // Прежние определения сохраняют свои файлы
+    let mut def_files = std::mem::take(&mut scratch_buff.def_files);
+    for key in &kept {
+        if let Some(record) = load_buff.def_files.get(key) {
+            def_files.insert(key.clone(), record.clone());
+        }
+    }
+    load_buff.def_files = def_files;
+
+    // Сообщаются только ошибки изменённых файлов и сохранённых определений, остальные уже были при загрузке
+    let kept_files: Vec<&PathBuf> = kept.iter().filter_map(|key| load_buff.def_files.get(key)).map(|(_, file)| file).collect();
+    let mut diagnostics: Vec<Diagnostic> = scratch_buff.diagnostics.into_iter()
+        .filter(|diagnostic| diagnostic.file.as_ref().map_or(false, |file| paths.contains(file) || kept_files.contains(&file)))
+        .collect();
+    for (kind, id_name) in &kept {
+        let file = load_buff.def_files.get(&(*kind, id_name.clone())).map(|(_, file)| file.as_path());
+        diagnostics.push(Diagnostic::warning(format!("{} {} keeps its previous definition until restart", kind.label(), id_name)).in_file(file));
+    }
+
+    if changed.is_empty() && diagnostics.is_empty() {
+        return;
+    }
+
+    // Сменившийся набор текстур определений пересобирает атласы, в которых он изменился
+    if !changed.is_empty() {
+        let before: Vec<Vec<String>> = graphic::MODULE_ATLASES.iter().map(|(atlas_type, _)| load_buff.verified_textures(atlas_type).clone()).collect();
+        ResourcePlugin::collect_def_textures(&register, &mut load_buff);
+        ResourcePlugin::verify_textures(&mut load_buff);
+
+        let stale: Vec<AtlasType> = graphic::MODULE_ATLASES.iter().zip(before)
+            .filter(|((atlas_type, _), before)| {
+                let after = load_buff.verified_textures(atlas_type);
+                before.len() != after.len() || before.iter().any(|texture| !after.contains(texture))
+            })
+            .map(|((atlas_type, _), _)| atlas_type.clone())
+            .collect();
+        if !stale.is_empty() {
+            atlas_stale.send(AtlasTexturesChanged(stale));
+        }
+    }
+
+    for changed in &changed {
+        info!("{} {} reloaded", changed.0.label(), changed.1);
+    }
+    LoadDiagnostics {
+        entries:    diagnostics,
+        strict:     false,
+    }.log();
+
+    reloaded.send(DefsReloaded(changed));
+}
+
+/// Изменённая текстура или набор текстур пересобирает свой атлас на месте старого, handle спрайтов остаются прежними
+fn reload_textures(
+    mut event:              EventReader<AssetEvent<Image>>,
+    mut textures_changed:   EventReader<AtlasTexturesChanged>,
+    mut atlas:              ResMut<AtlasRes>,
+    mut texture_atlases:    ResMut<Assets<TextureAtlasLayout>>,
+    mut textures:           ResMut<Assets<Image>>,
+    mut rebuilt:            EventWriter<AtlasRebuilt>,
+        load_buff:          Res<LoadingBuffer>,
+        resource_module:    Res<ResourceModule>,
+        loaded_folders:     Res<Assets<LoadedFolder>>,
+        asset_server:       Res<AssetServer>,
+) {
+    if event.is_empty() && textures_changed.is_empty() {
+        return;
+    }
+
+    let mut stale: Vec<AtlasType> = textures_changed.read().flat_map(|event| event.0.iter().cloned()).collect();
+    for event in event.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        // Собранные атласы не имеют пути, на них изменения не отслеживаются
+        let Some(stem) = asset_server.get_path(*id).and_then(|path| path.path().file_stem().map(|stem| stem.to_os_string())) else {
+            continue;
+        };
+
+        for (atlas_type, _) in graphic::MODULE_ATLASES {
+            let used = load_buff.verified_textures(&atlas_type).iter()
+                .any(|texture| Path::new(texture).file_stem() == Some(stem.as_os_str()));
+            if used && !stale.contains(&atlas_type) {
+                stale.push(atlas_type);
+            }
+        }
+    }
+
+    if stale.is_empty() {
+        return;
+    }
+
+    let loaded_folder = graphic::module_folder(&resource_module, &loaded_folders);
+    for atlas_type in stale {
+        let Some((layout, image, ids)) = graphic::build_atlas(&atlas_type, &load_buff, &loaded_folder, &mut textures) else {
+            continue;
+        };
+        let atlas_data = atlas.def_atlastype_mut(atlas_type.clone());
+        let (Some(old_layout), Some(old_image)) = (atlas_data.layout.clone(), atlas_data.image.clone()) else {
+            continue;
+        };
+
+        if let Some(image) = textures.remove(&image) {
+            textures.insert(&old_image, image);
+        }
+        texture_atlases.insert(&old_layout, layout);
+
+        let old_ids = atlas_data.ids.replace(ids).unwrap_or_default();
+        info!("Atlas {:?} rebuilt", atlas_type);
+        rebuilt.send(AtlasRebuilt(atlas_type, old_ids));
+    }
+}
+
+/// Индексы спрайтов переносятся в пересобранный атлас по имени текстуры
+///
+/// Сущности и соединяющиеся объекты считают индекс с учётом направления, они обновляются по определениям
+fn remap_atlas_sprites(
+    mut event:      EventReader<AtlasRebuilt>,
+    mut reloaded:   EventWriter<DefsReloaded>,
+    mut sprites:    Query<&mut TextureAtlas, (Without<EntityBase>, Without<EntityHead>, Without<PersistentObject>)>,
+        atlas:      Res<AtlasRes>,
+        register:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for AtlasRebuilt(atlas_type, old_ids) in event.read() {
+        match atlas_type {
+            AtlasType::Entity => {
+                let ids = register.def_ids(DefKind::Entity);
+                reloaded.send(DefsReloaded(ids.into_iter().map(|id_name| (DefKind::Entity, id_name)).collect()));
+            },
+            AtlasType::ConnectObj => {
+                let ids = register.def_ids(DefKind::ObjectCt);
+                reloaded.send(DefsReloaded(ids.into_iter().map(|id_name| (DefKind::ObjectCt, id_name)).collect()));
+            },
+            _ => {
+                let atlas_data = atlas.def_atlastype(atlas_type.clone());
+                let (Some(layout), Some(ids)) = (&atlas_data.layout, &atlas_data.ids) else {
+                    continue;
+                };
+                let names: HashMap<usize, &String> = old_ids.iter().map(|(name, index)| (*index, name)).collect();
+
+                for mut sprite in sprites.iter_mut().filter(|sprite| sprite.layout == *layout) {
+                    if let Some(index) = names.get(&sprite.index).and_then(|name| ids.get(*name)) {
+                        sprite.index = *index;
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Живые сущности перечитанных определений: текстура, коллизия и здоровье
+///
+/// Максимум здоровья берётся из реестра, текущее здоровье только ограничивается новым максимумом
+fn refresh_live_defs(
+    mut commands:   Commands,
+    mut event:      EventReader<DefsReloaded>,
+    mut entities:   Query<(&mut EntityBase, &mut TextureAtlas, Option<&MaxHealth>), Without<EntityHead>>,
+    mut heads:      Query<(&EntityHead, &mut TextureAtlas), Without<EntityBase>>,
+    mut objects:    Query<(Entity, &mut EntityObject, &mut TextureAtlas, Option<&Door>), (Without<EntityBase>, Without<EntityHead>)>,
+    mut walls:      Query<(Entity, &mut PersistentObject, &mut TextureAtlas), (Without<EntityBase>, Without<EntityHead>, Without<EntityObject>)>,
+        atlas:      Res<AtlasRes>,
+        layouts:    Res<Assets<TextureAtlasLayout>>,
+        register:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    let entity_index = |texture: &str, direction: EntityDirectionState| {
+        atlas.entity.ids.as_ref()
+            .and_then(|ids| ids.get(texture))
+            .map(|index| EntityDirectionState::calculate_index(*index, direction.dir_index()))
+    };
+
+    for (kind, id_name) in event.read().flat_map(|event| event.0.iter()) {
+        match kind {
+            DefKind::Entity => {
+                let Some(info) = register.get_entity_info(id_name) else {
+                    continue;
+                };
+                for (mut base, mut sprite, bonus) in entities.iter_mut().filter(|(base, _, _)| base.id_name == *id_name) {
+                    base.health.0 = base.health.0.min(max_health(&register, &base, bonus));
+                    if let Some(index) = entity_index(&info.id_texture_b, base.direction) {
+                        sprite.index = index;
+                    }
+                }
+                for (head, mut sprite) in heads.iter_mut().filter(|(head, _)| head.id_name == *id_name) {
+                    if let Some(index) = info.id_texture_h.as_ref().and_then(|texture| entity_index(texture, head.direction)) {
+                        sprite.index = index;
+                    }
+                }
+            },
+            DefKind::Object => {
+                let Some(info) = register.get_object_info(id_name) else {
+                    continue;
+                };
+                for (entity, mut object, mut sprite, door) in objects.iter_mut().filter(|(_, object, _, _)| object.id_name == *id_name) {
+                    object.health.0 = object.health.0.min(info.health as f32);
+                    commands.entity(entity).insert(Collider::cuboid(info.collision.x, info.collision.y));
+
+                    // Текстура двери зависит от её состояния, дверь её меняет сама
+                    if door.is_some() {
+                        continue;
+                    }
+                    if let Some(index) = atlas.objects.ids.as_ref().and_then(|ids| ids.get(&info.id_texture)) {
+                        sprite.index = *index;
+                    }
+                }
+            },
+            DefKind::ObjectCt => {
+                let Some(info) = register.get_object_ct_info(id_name) else {
+                    continue;
+                };
+                for (entity, mut wall, mut sprite) in walls.iter_mut().filter(|(_, wall, _)| wall.id_name == *id_name) {
+                    wall.health.0 = wall.health.0.min(info.durability.unwrap_or(2) as f32);
+                    commands.entity(entity).insert(Collider::cuboid(info.collision.x, info.collision.y));
+
+                    if let Some(index) = connected_index(&atlas, &layouts, &info.id_texture, wall.direction) {
+                        sprite.index = index;
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
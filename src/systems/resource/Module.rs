@@ -0,0 +1,338 @@
+use std::{
+    collections::{
+        BTreeSet,
+        HashMap,
+        HashSet
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf
+    }
+};
+
+use crate::core::resource::{
+    Diagnostic::{
+        Diagnostic,
+        parse_json
+    },
+    Registry::ModuleRegistry
+};
+
+//
+//
+//
+
+/// Папка с модулями
+pub const DATA_DIR: &str = "Data";
+/// Файл описания модуля
+pub const MODULE_MANIFEST: &str = "mod.json";
+/// Модуль ядра, остальные модули загружаются после него
+pub const CORE_MODULE: &str = "core";
+
+/// Найденный на диске модуль
+#[derive(Clone, Debug)]
+pub struct ModuleEntry {
+    pub info:   ModuleRegistry,
+    pub dir:    String,     // Имя папки модуля внутри `Data`
+    pub path:   PathBuf,
+}
+
+/// Поиск модулей: каждая папка внутри `Data` с файлом `mod.json`
+pub fn discover_modules(data: &Path) -> (Vec<ModuleEntry>, Vec<Diagnostic>) {
+    let mut modules = Vec::new();
+    let mut errors = Vec::new();
+
+    let Ok(entries) = fs::read_dir(data) else {
+        errors.push(Diagnostic::error("data folder is not found").in_file(Some(data)));
+        return (modules, errors);
+    };
+
+    let mut dirs: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    // Порядок папок на диске не определён, сортировка делает загрузку повторяемой
+    dirs.sort();
+
+    for path in dirs {
+        let dir = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let manifest = path.join(MODULE_MANIFEST);
+
+        let Ok(contents) = fs::read_to_string(&manifest) else {
+            errors.push(Diagnostic::error(format!("module folder {} has no {}", dir, MODULE_MANIFEST)).in_file(Some(&path)));
+            continue;
+        };
+        if let Some(info) = parse_json::<ModuleRegistry>(&manifest, &contents, &mut errors) {
+            modules.push(ModuleEntry { info, dir, path });
+        }
+    }
+
+    (modules, errors)
+}
+
+/// Сравнение версий по числовым частям (`1.10` новее `1.9`)
+pub fn version_at_least(found: &str, required: &str) -> bool {
+    let parse = |version: &str| -> Vec<u32> {
+        version.split('.').map(|part| part.trim().parse().unwrap_or(0)).collect()
+    };
+    let (found, required) = (parse(found), parse(required));
+
+    for index in 0..found.len().max(required.len()) {
+        let (a, b) = (found.get(index).copied().unwrap_or(0), required.get(index).copied().unwrap_or(0));
+        if a != b {
+            return a > b;
+        }
+    }
+    true
+}
+
+/// Порядок загрузки модулей
+///
+/// Модули с повторным id, недостающими зависимостями или в цикле не загружаются, вместе с зависящими от них.
+/// Модули, которые загружаются после цикла, сообщаются отдельно от самого цикла
+pub fn resolve_load_order(modules: Vec<ModuleEntry>) -> (Vec<ModuleEntry>, Vec<Diagnostic>) {
+    let mut errors = Vec::new();
+
+    let mut by_id: HashMap<String, ModuleEntry> = HashMap::new();
+    for module in modules {
+        if let Some(existing) = by_id.get(&module.info.id) {
+            errors.push(Diagnostic::error(
+                format!("module id {} is declared by both {} and {}, {} is skipped", module.info.id, existing.dir, module.dir, module.dir)
+            ).in_file(Some(&module.path.join(MODULE_MANIFEST))).with_field(Some("id")));
+            continue;
+        }
+        by_id.insert(module.info.id.clone(), module);
+    }
+
+    // Отсутствие зависимости убирает модуль, что может убрать и зависящие от него
+    loop {
+        let mut missing = Vec::new();
+        for module in by_id.values() {
+            for dependency in &module.info.dependencies {
+                let message = match (by_id.get(&dependency.id), &dependency.version) {
+                    (None, _) => format!("module {} requires missing module {}", module.info.id, dependency.id),
+                    (Some(found), Some(version)) if !version_at_least(&found.info.version, version) => format!(
+                        "module {} requires {} {} or newer, found {}",
+                        module.info.id, dependency.id, version, found.info.version
+                    ),
+                    _ => continue,
+                };
+                errors.push(Diagnostic::error(message).in_file(Some(&module.path.join(MODULE_MANIFEST))).with_field(Some("dependencies")));
+                missing.push(module.info.id.clone());
+                break;
+            }
+        }
+        if missing.is_empty() {
+            break;
+        }
+        for id in missing {
+            by_id.remove(&id);
+        }
+    }
+
+    // Рёбра графа: модуль -> модули, загружаемые после него
+    let mut after: HashMap<&str, BTreeSet<&str>> = by_id.keys().map(|id| (id.as_str(), BTreeSet::new())).collect();
+    for module in by_id.values() {
+        let id = module.info.id.as_str();
+        let earlier = module.info.dependencies.iter().map(|dependency| dependency.id.as_str())
+            .chain(module.info.load_after.iter().map(|id| id.as_str()));
+        for earlier in earlier {
+            if let Some(edges) = after.get_mut(earlier) {
+                edges.insert(id);
+            }
+        }
+        for later in &module.info.load_before {
+            if let (true, Some(edges)) = (by_id.contains_key(later), after.get_mut(id)) {
+                edges.insert(later.as_str());
+            }
+        }
+        // Ядро загружается первым, если модуль явно не просит обратного
+        if id != CORE_MODULE && !module.info.load_before.iter().any(|later| later == CORE_MODULE) {
+            if let Some(edges) = after.get_mut(CORE_MODULE) {
+                edges.insert(id);
+            }
+        }
+    }
+
+    let mut incoming: HashMap<&str, usize> = after.keys().map(|id| (*id, 0)).collect();
+    for edges in after.values() {
+        for id in edges {
+            *incoming.get_mut(id).unwrap() += 1;
+        }
+    }
+
+    // Из готовых к загрузке модулей первым идёт меньший id, чтобы порядок не зависел от хэша
+    let mut ready: BTreeSet<&str> = incoming.iter().filter(|(_, count)| **count == 0).map(|(id, _)| *id).collect();
+    let mut order: Vec<String> = Vec::new();
+    while let Some(id) = ready.pop_first() {
+        order.push(id.to_string());
+        for next in &after[id] {
+            let count = incoming.get_mut(next).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(next);
+            }
+        }
+    }
+
+    // Оставшиеся модули либо сами в цикле, либо загружаются после модуля из цикла
+    let ordered: HashSet<&str> = order.iter().map(|id| id.as_str()).collect();
+    let mut left: Vec<&str> = by_id.keys().map(|id| id.as_str()).filter(|id| !ordered.contains(id)).collect();
+    left.sort();
+
+    let reaches = |from: &str, to: &str| {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = after[from].iter().copied().collect();
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            if visited.insert(id) {
+                stack.extend(after[id].iter().copied());
+            }
+        }
+        false
+    };
+    let (cycle, blocked): (Vec<&str>, Vec<&str>) = left.iter().partition(|id| reaches(id, id));
+
+    for id in &cycle {
+        errors.push(Diagnostic::error(
+            format!("module {} is in a load order cycle among {:?} and is skipped", id, cycle)
+        ).in_file(Some(&by_id[*id].path.join(MODULE_MANIFEST))));
+    }
+    for id in &blocked {
+        let causes: Vec<&str> = cycle.iter().copied().filter(|member| reaches(member, id)).collect();
+        errors.push(Diagnostic::error(
+            format!("module {} loads after the load order cycle among {:?} and is skipped", id, causes)
+        ).in_file(Some(&by_id[*id].path.join(MODULE_MANIFEST))));
+    }
+
+    let order = order.iter().filter_map(|id| by_id.remove(id)).collect();
+    (order, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::resource::Registry::ModuleDependency;
+
+    use super::*;
+
+    /// Модуль для тестов, зависимости - `id` или `id@версия`
+    fn module(id: &str, version: &str, dependencies: &[&str]) -> ModuleEntry {
+        let dependencies = dependencies.iter().map(|dependency| {
+            let mut parts = dependency.split('@');
+            ModuleDependency {
+                id:         parts.next().unwrap_or_default().to_string(),
+                version:    parts.next().map(str::to_string),
+            }
+        }).collect();
+
+        ModuleEntry {
+            info: ModuleRegistry {
+                id:             id.to_string(),
+                name:           id.to_string(),
+                desc:           String::new(),
+                version:        version.to_string(),
+                dependencies,
+                load_after:     Vec::new(),
+                load_before:    Vec::new(),
+            },
+            dir:    id.to_string(),
+            path:   PathBuf::from(DATA_DIR).join(id),
+        }
+    }
+
+    fn ids(modules: &[ModuleEntry]) -> Vec<&str> {
+        modules.iter().map(|module| module.info.id.as_str()).collect()
+    }
+
+    /// Модули из сообщений об ошибках по папке файла
+    fn reported(errors: &[Diagnostic], text: &str) -> Vec<String> {
+        let mut modules: Vec<String> = errors.iter()
+            .filter(|error| error.message.contains(text))
+            .filter_map(|error| error.file.as_ref()?.parent()?.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .collect();
+        modules.sort();
+        modules
+    }
+
+    #[test]
+    fn version_comparison() {
+        assert!(version_at_least("1.10", "1.9"));
+        assert!(version_at_least("1.2", "1.2.0"));
+        assert!(version_at_least("2", "1.99"));
+        assert!(!version_at_least("1.2", "1.2.1"));
+        assert!(!version_at_least("0.9", "1.0"));
+    }
+
+    #[test]
+    fn core_first_then_dependencies() {
+        let (order, errors) = resolve_load_order(vec![
+            module("b", "1.0", &["a"]),
+            module("a", "1.0", &[]),
+            module("c", "1.0", &["b", "a"]),
+            module(CORE_MODULE, "1.0", &[]),
+        ]);
+
+        assert!(errors.is_empty());
+        assert_eq!(ids(&order), vec![CORE_MODULE, "a", "b", "c"]);
+    }
+
+    #[test]
+    fn load_before_and_after() {
+        let mut early = module("z_early", "1.0", &[]);
+        early.info.load_before.push("a_late".to_string());
+        let mut late = module("a_late", "1.0", &[]);
+        late.info.load_after.push("missing".to_string());
+
+        let (order, errors) = resolve_load_order(vec![late, early, module(CORE_MODULE, "1.0", &[])]);
+
+        // Необязательный порядок с отсутствующим модулем не ошибка
+        assert!(errors.is_empty());
+        assert_eq!(ids(&order), vec![CORE_MODULE, "z_early", "a_late"]);
+    }
+
+    #[test]
+    fn missing_and_old_dependencies() {
+        let (order, errors) = resolve_load_order(vec![
+            module(CORE_MODULE, "1.0", &[]),
+            module("lib", "1.2", &[]),
+            module("old", "1.0", &["lib@1.10"]),
+            module("lost", "1.0", &["nowhere"]),
+            module("user", "1.0", &["lost"]),
+            module("fine", "1.0", &["lib@1.2"]),
+        ]);
+
+        assert_eq!(ids(&order), vec![CORE_MODULE, "lib", "fine"]);
+        assert_eq!(reported(&errors, "or newer"), vec!["old"]);
+        assert_eq!(reported(&errors, "requires missing module"), vec!["lost", "user"]);
+    }
+
+    #[test]
+    fn cycle_and_blocked_modules() {
+        let (order, errors) = resolve_load_order(vec![
+            module(CORE_MODULE, "1.0", &[]),
+            module("a", "1.0", &["b"]),
+            module("b", "1.0", &["a"]),
+            module("c", "1.0", &["a"]),
+            module("d", "1.0", &[]),
+        ]);
+
+        assert_eq!(ids(&order), vec![CORE_MODULE, "d"]);
+        assert_eq!(reported(&errors, "is in a load order cycle"), vec!["a", "b"]);
+        assert_eq!(reported(&errors, "loads after the load order cycle"), vec!["c"]);
+    }
+
+    #[test]
+    fn duplicate_id_is_skipped() {
+        let mut copy = module("a", "2.0", &[]);
+        copy.dir = "a_copy".to_string();
+
+        let (order, errors) = resolve_load_order(vec![module(CORE_MODULE, "1.0", &[]), module("a", "1.0", &[]), copy]);
+
+        assert_eq!(ids(&order), vec![CORE_MODULE, "a"]);
+        assert_eq!(order[1].info.version, "1.0");
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -1,46 +1,104 @@
 #![allow(unused)]
 use bevy::prelude::*;
 
-use std::collections::HashMap;
+use std::collections::{
+    BTreeMap,
+    HashMap
+};
 
 use serde::{
+    de::DeserializeOwned,
     Deserialize,
     Serialize
 };
 
+use serde_json::{
+    Map,
+    Value
+};
+
 use crate::core::{
     ItemType::{
         ItemType,
         ItemSizeType,
         ItemStackType,
+        Tool
+    },
+    ObjType::PlantType,
+    TimeCycle::Season,
+    WeatherSystem::{
+        Biome,
+        WeatherKind
     },
-    EntityType::EntityType,
+    EntityType::{
+        EntityType,
+        EntityNeutrality
+    },
+    skills::SkillEffect,
+    interact::Interact::InteractionKind,
+    ContainerSystem::EquipSlot,
     // ObjType::ObjectSizeType,
     resource::graphic::Atlas::{
         AtlasType,
         AtlasRes,
     },
-    Craft::CraftResult,
+    Craft::{
+        CraftResult,
+        CraftIngredient,
+        CraftTool
+    },
+    Debug::{
+        GameError,
+        GameErrorType
+    },
+    Attributes::ItemAttributes,
     Util::{
         IVec2C,
         Vec2C
     }
 };
 
-#[derive(Serialize, Deserialize)]
+/// Описание модуля из `mod.json`
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ModuleRegistry {
-    pub id:     String,
-    pub name:   String,
-    pub desc:   String
+    pub id:             String,
+    #[serde(default)]
+    pub name:           String,
+    #[serde(default)]
+    pub desc:           String,
+    #[serde(default)]
+    pub version:        String,
+    #[serde(default)]
+    pub dependencies:   Vec<ModuleDependency>,  // Обязательные модули, загружаются раньше
+    #[serde(default)]
+    pub load_after:     Vec<String>,            // Необязательные модули, которые загружаются раньше, если есть
+    #[serde(default)]
+    pub load_before:    Vec<String>,            // Необязательные модули, которые загружаются позже, если есть
+}
+
+/// Зависимость модуля
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModuleDependency {
+    pub id:         String,
+    #[serde(default)]
+    pub version:    Option<String>,     // Минимальная версия
 }
 
 #[derive(Resource)]
 pub struct Registry {
     pub module_registry:    HashMap<String, ModuleRegistry>,
+    pub load_order:         Vec<String>,                        // Модули в порядке загрузки
     pub entity_registry:    HashMap<String, EntityRegistry>,    // Хэш-таблица с регистрируемыми сущностями
     pub object_registry:    HashMap<String, ObjectRegistry>,    // Хэш-таблица с регистрируемыми объектами
     pub object_ct_registry: HashMap<String, PersistentObjectRegistry>,
     pub item_registry:      HashMap<String, ItemRegistry>,      // Хэш-таблица с регистрируемыми предметами
+    pub progression_registry: HashMap<String, ProgressionRegistry>, // Хэш-таблица с кривыми опыта
+    pub skill_tree_registry:  HashMap<String, SkillNodeRegistry>,   // Хэш-таблица с узлами дерева навыков
+    pub skill_registry:     HashMap<String, SkillRegistry>,     // Хэш-таблица с активными навыками
+    pub recipe_registry:    HashMap<String, RecipeRegistry>,    // Хэш-таблица с рецептами
+    pub plant_registry:     HashMap<String, PlantRegistry>,     // Хэш-таблица с растениями
+    pub weather_registry:   HashMap<String, WeatherRegistry>,   // Хэш-таблица с погодой
+    pub patched_fields:     HashMap<(DefKind, String), BTreeMap<String, String>>,  // Модуль, последним изменивший поле верхнего уровня определения
 
     pub test:               HashMap<String, TestRegistry>,    // Хэш-таблица с тест
 }
@@ -52,9 +110,16 @@ pub struct EntityRegistry {
     pub id_texture_b:   String,
     pub id_texture_h:   Option<String>,
     pub entity_type:    EntityType,
-    pub health:         f32
+    pub health:         f32,
+    pub xp_reward:      Option<u32>,    // Опыт за убийство сущности
+    pub skills:         Option<Vec<String>>, // Активные навыки по слотам
+    #[serde(default)]
+    pub pawn:           bool            // Сущность - рабочий колонии, берёт работы с доски
 }
 
+/// Кол-во пикселей текстуры объекта на одну клетку мира
+pub const OBJECT_TILE_TEXELS: i32 = 32;
+
 #[derive(Serialize, Deserialize)]
 pub struct ObjectRegistry {
     pub id_name:        String,
@@ -63,7 +128,55 @@ pub struct ObjectRegistry {
     pub health:         u32,
     pub size:           IVec2C,
     pub collision:      Vec2C,
-    pub durability:     Option<usize>
+    pub durability:     Option<usize>,
+    pub container:      Option<ContainerRegistry>,  // Объект является хранилищем
+    #[serde(default)]
+    pub interactions:   Vec<InteractionKind>,       // Доступные взаимодействия
+    pub door:           Option<DoorRegistry>,       // Объект является дверью
+    #[serde(default)]
+    pub opaque:         bool,                       // Объект загораживает свет
+    pub light:          Option<LightRegistry>,      // Объект излучает свет
+    #[serde(default)]
+    pub beauty:         f32                         // Красота объекта для комнаты
+}
+
+/// Определение источника света у объекта или предмета
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct LightRegistry {
+    pub radius:     f32,                // Радиус в клетках
+    pub color:      (f32, f32, f32),    // Цвет света (0 - 1)
+    pub intensity:  f32,
+    pub flicker:    f32,                // Сила мерцания (0 - 1)
+}
+
+impl Default for LightRegistry {
+    fn default() -> Self {
+        Self {
+            radius:     4.0,
+            color:      (1.0, 1.0, 1.0),
+            intensity:  1.0,
+            flicker:    0.0,
+        }
+    }
+}
+
+/// Определение двери у объекта
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DoorRegistry {
+    #[serde(default)]
+    pub locked:             bool,                       // Закрытая дверь запирается
+    pub faction:            Option<EntityNeutrality>,   // Фракция, которая может открыть запертую дверь
+    pub id_texture_open:    Option<String>,             // Текстура открытой двери
+    pub auto_close:         Option<f32>,                // Закрывается сама через n секунд
+}
+
+/// Определение хранилища у объекта или предмета
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ContainerRegistry {
+    pub capacity:   usize,          // Кол-во слотов (у надеваемого предмета - доп. слоты инвентаря)
+    pub mass:       Option<f32>,    // Предельная масса содержимого
+    pub volume:     Option<f32>,    // Предельный объём содержимого (у надеваемого предмета - доп. объём)
 }
 
 // pub struct EntityObjectDefinition {
@@ -72,6 +185,14 @@ pub struct ObjectRegistry {
 // }
 
 impl ObjectRegistry {
+    /// Размер объекта в клетках, `size` задаётся в пикселях текстуры (32 пикселя на клетку)
+    pub fn footprint(&self) -> IVec2 {
+        IVec2::new(
+            ((self.size.x + OBJECT_TILE_TEXELS - 1) / OBJECT_TILE_TEXELS).max(1),
+            ((self.size.y + OBJECT_TILE_TEXELS - 1) / OBJECT_TILE_TEXELS).max(1)
+        )
+    }
+
     /// Получение прочности предмета
     pub fn get_base_durability(&self) -> Option<usize> {
         self.durability
@@ -98,7 +219,23 @@ pub struct ItemRegistry {
     pub range_info: Option<(f32, f32, f32)>, // Скорость стрельбы | Время полёта пули
     pub stackable:  Option<ItemStackType>,
     pub stack_size: Option<usize>,
-    pub durability: Option<usize>
+    pub durability: Option<usize>,
+    pub mass:       Option<f32>,                // Масса единицы предмета
+    pub volume:     Option<f32>,                // Объём единицы предмета
+    pub equip:      Option<EquipRegistry>,      // Предмет можно надеть
+    pub container:  Option<ContainerRegistry>,  // Надетый предмет расширяет инвентарь
+    pub light:      Option<LightRegistry>       // Предмет светит на земле и в руках
+}
+
+/// Определение экипировки предмета
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EquipRegistry {
+    pub slots:      Vec<EquipSlot>,         // Допустимые слоты
+    #[serde(default)]
+    pub two_handed: bool,                   // Занимает обе руки
+    #[serde(default)]
+    pub attributes: ItemAttributes,         // Бонусы, пока предмет надет
+    pub id_texture: Option<String>          // Текстура на персонаже, по умолчанию текстура предмета
 }
 
 impl ItemRegistry {
@@ -112,6 +249,16 @@ impl ItemRegistry {
         self.stack_size
     }
 
+    /// Масса единицы предмета (по умолчанию - от размера предмета)
+    pub fn get_mass(&self) -> f32 {
+        self.mass.unwrap_or(self.item_size.default_mass())
+    }
+
+    /// Объём единицы предмета (по умолчанию - от размера предмета)
+    pub fn get_volume(&self) -> f32 {
+        self.volume.unwrap_or(self.item_size.default_volume())
+    }
+
     pub fn def_stack(&mut self) {
         match self.item_type {
             (ItemType::Weapon(_) | ItemType::Tool(_)) => {
@@ -135,24 +282,381 @@ impl ItemRegistry {
     }
 }
 
+/// Определение рецепта
 #[derive(Serialize, Deserialize)]
 pub struct RecipeRegistry {
-    pub request:    Vec<String>,
-    pub result:     CraftResult,
+    pub id_name:        String,
+    pub id_source:      Option<String>,
+    pub request:        Vec<CraftIngredient>,   // Расходуемые ингредиенты
+    #[serde(default)]
+    pub tools:          Vec<CraftTool>,         // Необходимые инструменты
+    pub workstation:    Option<String>,         // Объект, рядом с которым возможен крафт
+    #[serde(default)]
+    pub craft_time:     f32,                    // Время крафта в секундах
+    #[serde(default)]
+    pub xp:             u32,                    // Опыт за крафт
+    #[serde(default)]
+    pub construction:   bool,                   // Рецепт постройки: `result.output` - объект, `craft_time` - объём работы
+    pub result:         CraftResult,
+}
+
+/// Определение прогрессии персонажа (кривая опыта)
+#[derive(Serialize, Deserialize)]
+pub struct ProgressionRegistry {
+    pub id_name:                String,
+    pub id_source:              Option<String>,
+    pub xp_curve:               Vec<u32>,   // Опыт для перехода с уровня n на n + 1
+    pub skill_points_per_level: u8
+}
+
+/// Определение узла дерева навыков
+#[derive(Serialize, Deserialize)]
+pub struct SkillNodeRegistry {
+    pub id_name:    String,
+    pub id_source:  Option<String>,
+    pub cost:       u8,                 // Стоимость в очках навыков
+    #[serde(default)]
+    pub requires:   Vec<String>,        // Узлы, которые должны быть изучены заранее
+    #[serde(default)]
+    pub attributes: ItemAttributes      // Бонусы, получаемые от узла
+}
+
+/// Определение активного навыка
+#[derive(Serialize, Deserialize)]
+pub struct SkillRegistry {
+    pub id_name:    String,
+    pub id_source:  Option<String>,
+    pub cooldown:   f32,            // Перезарядка в секундах
+    pub cost:       f32,            // Стоимость в энергии
+    pub cast_time:  f32,            // Время каста в секундах
+    pub effect:     SkillEffect
+}
+
+/// Определение растения
+#[derive(Serialize, Deserialize)]
+pub struct PlantRegistry {
+    pub id_name:        String,
+    pub id_source:      Option<String>,
+    pub plant_type:     PlantType,
+    pub stages:         Vec<PlantStageRegistry>,    // Стадии роста по порядку
+    #[serde(default)]
+    pub seasons:        HashMap<Season, f32>,       // Множитель скорости роста по временам года (по умолчанию 1)
+    #[serde(default)]
+    pub light_min:      f32,                        // Ниже этой освещённости растение не растёт
+    pub collision:      Option<Vec2C>,              // Растение без коллизии не мешает проходу физически
+    pub harvest:        Option<HarvestRegistry>,    // Растение можно собрать
+    #[serde(default)]
+    pub after_harvest:  AfterHarvest,
+}
+
+/// Стадия роста растения
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlantStageRegistry {
+    pub id_texture: String,
+    pub duration:   f32,        // Время стадии в секундах мира при множителе 1, у последней стадии не учитывается
+}
+
+/// Определение сбора урожая
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HarvestRegistry {
+    pub stage:  Option<usize>,          // Стадия, с которой можно собирать (по умолчанию последняя)
+    pub tool:   Option<Tool>,           // Необходимый инструмент
+    #[serde(default)]
+    pub work:   f32,                    // Время сбора рабочим в секундах
+    #[serde(default)]
+    pub xp:     u32,                    // Опыт за сбор
+    pub loot:   Vec<CraftIngredient>,   // Выпадающие предметы
+}
+
+/// Что происходит с растением после сбора
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum AfterHarvest {
+    /// Растение исчезает
+    #[default]
+    Remove,
+    /// Растение откатывается к указанной стадии и растёт заново
+    Regrow(usize),
+    /// На месте растения высаживается указанное растение с первой стадии
+    Replant(String),
+}
+
+impl PlantRegistry {
+    /// Скорость роста при данных времени года и освещённости
+    pub fn growth_rate(&self, season: Season, light: f32) -> f32 {
+        if light < self.light_min {
+            return 0.0;
+        }
+        self.seasons.get(&season).copied().unwrap_or(1.0).max(0.0) * light.clamp(0.0, 1.0)
+    }
+
+    /// Стадия, с которой растение можно собрать
+    pub fn harvest_stage(&self) -> Option<usize> {
+        self.harvest.as_ref().map(|harvest| harvest.stage.unwrap_or(self.stages.len().saturating_sub(1)))
+    }
+}
+
+/// Определение погоды
+#[derive(Serialize, Deserialize)]
+pub struct WeatherRegistry {
+    pub id_name:        String,
+    pub id_source:      Option<String>,
+    pub kind:           WeatherKind,
+    pub duration:       (f32, f32),                 // Длительность в игровых часах (мин | макс)
+    #[serde(default)]
+    pub temperature:    f32,                        // Сдвиг температуры на улице
+    #[serde(default)]
+    pub darkness:       f32,                        // Затемнение освещения от времени суток (0 - 1)
+    #[serde(default)]
+    pub wetness:        f32,                        // Намокание на улице за игровой час (0 - 1)
+    #[serde(default)]
+    pub slowdown:       f32,                        // Замедление передвижения на улице (0 - 1)
+    pub particle:       Option<WeatherParticleRegistry>,
+    #[serde(default)]
+    pub transitions:    HashMap<String, f32>,       // Веса смены на другую погоду
+    #[serde(default)]
+    pub biomes:         HashMap<Biome, f32>,        // Вероятность по биомам, пусто - в любом биоме
+    #[serde(default)]
+    pub seasons:        HashMap<Season, f32>,       // Вероятность по временам года, пусто - в любое время
+}
+
+/// Частицы погоды, текстура берётся из атласа частиц
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WeatherParticleRegistry {
+    pub id_texture:     String,
+    pub rate:           f32,                        // Частиц в секунду на экран
+    pub velocity:       (f32, f32),                 // Скорость в пикселях в секунду
+    pub lifetime:       f32,
+    #[serde(default)]
+    pub spread:         f32,                        // Разброс скорости (0 - 1)
+    #[serde(default)]
+    pub transparency:   f32,                        // Прозрачность частицы (0 - 1)
+}
+
+impl WeatherRegistry {
+    /// Вероятность погоды в данном биоме и времени года
+    pub fn weight(&self, biome: Biome, season: Season) -> f32 {
+        let biome = match self.biomes.is_empty() {
+            true  => 1.0,
+            false => self.biomes.get(&biome).copied().unwrap_or(0.0),
+        };
+        let season = match self.seasons.is_empty() {
+            true  => 1.0,
+            false => self.seasons.get(&season).copied().unwrap_or(0.0),
+        };
+        (biome * season).max(0.0)
+    }
 }
 
 pub struct TestRegistry(pub String);
 
+/// Вид определения, цель патча
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DefKind {
+    Entity,
+    Object,
+    ObjectCt,
+    Item,
+    Progression,
+    SkillNode,
+    Skill,
+    Recipe,
+    Plant,
+    Weather,
+}
+
+impl DefKind {
+    pub const ALL: [DefKind; 10] = [
+        DefKind::Entity,
+        DefKind::Object,
+        DefKind::ObjectCt,
+        DefKind::Item,
+        DefKind::Progression,
+        DefKind::SkillNode,
+        DefKind::Skill,
+        DefKind::Recipe,
+        DefKind::Plant,
+        DefKind::Weather,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DefKind::Entity         => "Entity",
+            DefKind::Object         => "Object",
+            DefKind::ObjectCt       => "Object_ct",
+            DefKind::Item           => "Item",
+            DefKind::Progression    => "Progression",
+            DefKind::SkillNode      => "Skill Node",
+            DefKind::Skill          => "Skill",
+            DefKind::Recipe         => "Recipe",
+            DefKind::Plant          => "Plant",
+            DefKind::Weather        => "Weather",
+        }
+    }
+}
+
+/// Патч: изменение отдельных полей определения, зарегистрированного ранее
+#[derive(Serialize, Deserialize)]
+pub struct PatchRegistry {
+    pub target:     DefKind,
+    pub id_name:    String,                 // Определение, к которому применяется патч
+    pub id_source:  Option<String>,
+    pub operations: Vec<PatchOperation>,
+}
+
+/// Операция патча, вложенные поля указываются через точку (`light.radius`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PatchOperation {
+    Set     { field: String, value: Value },
+    Add     { field: String, value: Value },    // Добавление в список (или ключей в таблицу)
+    Remove  { field: String, value: Value },    // Удаление из списка (или ключей из таблицы)
+}
+
+impl PatchOperation {
+    pub fn field(&self) -> &str {
+        match self {
+            PatchOperation::Set { field, .. } | PatchOperation::Add { field, .. } | PatchOperation::Remove { field, .. } => field,
+        }
+    }
+
+    /// Поле по пути, недостающие таблицы создаются
+    fn slot<'a>(root: &'a mut Value, field: &str) -> Result<&'a mut Value, String> {
+        let root_field = field.split('.').next().unwrap_or_default();
+        if matches!(root_field, "id_name" | "id_source") || !root.as_object().map_or(false, |root| root.contains_key(root_field)) {
+            return Err(format!("field {} can not be patched", root_field));
+        }
+
+        let mut slot = root;
+        for part in field.split('.') {
+            if slot.is_null() {
+                *slot = Value::Object(Map::new());
+            }
+            slot = slot.as_object_mut()
+                .ok_or_else(|| format!("{} is not an object", part))?
+                .entry(part)
+                .or_insert(Value::Null);
+        }
+        Ok(slot)
+    }
+
+    fn apply(&self, root: &mut Value) -> Result<(), String> {
+        let slot = Self::slot(root, self.field())?;
+
+        match self {
+            PatchOperation::Set { value, .. } => *slot = value.clone(),
+            PatchOperation::Add { field, value } => match (slot, value) {
+                (Value::Array(list), Value::Array(values))  => list.extend(values.iter().cloned()),
+                (Value::Array(list), value)                 => list.push(value.clone()),
+                (Value::Object(map), Value::Object(values)) => map.extend(values.clone()),
+                (slot @ Value::Null, Value::Array(_) | Value::Object(_)) => *slot = value.clone(),
+                (slot @ Value::Null, value)                 => *slot = Value::Array(vec![value.clone()]),
+                _ => return Err(format!("{} is not a list or a map", field)),
+            },
+            PatchOperation::Remove { field, value } => {
+                let values: Vec<&Value> = match value {
+                    Value::Array(values)    => values.iter().collect(),
+                    value                   => vec![value],
+                };
+                match slot {
+                    Value::Array(list)  => list.retain(|item| !values.contains(&item)),
+                    Value::Object(map)  => values.iter().filter_map(|key| key.as_str()).for_each(|key| { map.remove(key); }),
+                    Value::Null         => {},
+                    _ => return Err(format!("{} is not a list or a map", field)),
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Регистрация определения, определение более позднего модуля заменяет прежнее с тем же `id_name` вместе с его патчами
+fn register_def<T>(
+    registry:   &mut HashMap<String, T>,
+    patched:    &mut HashMap<(DefKind, String), BTreeMap<String, String>>,
+    kind:       DefKind,
+    id_name:    String,
+    source:     Option<String>,
+    def:        T
+) {
+    patched.remove(&(kind, id_name.clone()));
+
+    match registry.insert(id_name.clone(), def) {
+        Some(_) => info!("{} {} переопределён модулем {}", kind.label(), id_name, source.unwrap_or_default()),
+        None    => println!("Register {}: {}", kind.label(), id_name),
+    }
+}
+
+/// Ошибка определения, найденная при проверке реестра
+#[derive(Debug)]
+pub struct DefError {
+    pub kind:       DefKind,
+    pub id_name:    String,
+    pub field:      Option<&'static str>,
+    pub error:      GameError,
+}
+
+impl DefError {
+    pub fn new(kind: DefKind, id_name: &str, field: Option<&'static str>, error: GameError) -> Self {
+        Self {
+            kind,
+            id_name: id_name.to_string(),
+            field,
+            error,
+        }
+    }
+}
+
+impl std::fmt::Display for DefError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+/// Применение операций патча через json-представление определения
+fn patch_def<T: Serialize + DeserializeOwned>(def: Option<&mut T>, patch: &PatchRegistry) -> Result<(), GameError> {
+    let error = |message: String| GameError::new(
+        GameErrorType::InvalidDef,
+        format!("patch of {} {} from {}: {}", patch.target.label(), patch.id_name, patch.id_source.clone().unwrap_or_default(), message)
+    );
+
+    let Some(def) = def else {
+        return Err(error("target is not registered".to_string()));
+    };
+
+    let mut value = serde_json::to_value(&*def).map_err(|err| error(err.to_string()))?;
+    for operation in &patch.operations {
+        operation.apply(&mut value).map_err(error)?;
+    }
+    // Определение меняется только если все операции применились
+    *def = serde_json::from_value(value).map_err(|err| error(err.to_string()))?;
+    Ok(())
+}
+
+/// json-представление определения для просмотра
+fn def_value<T: Serialize>(def: Option<&T>) -> Option<Value> {
+    serde_json::to_value(def?).ok()
+}
+
 impl Registry {
     pub fn new() -> Self {
         Self {
             module_registry:    HashMap::new(),
+            load_order:         Vec::new(),
 
             entity_registry:    HashMap::new(),
             object_registry:    HashMap::new(),
             object_ct_registry: HashMap::new(),
             item_registry:      HashMap::new(),
 
+            progression_registry: HashMap::new(),
+            skill_tree_registry:  HashMap::new(),
+            skill_registry:     HashMap::new(),
+            recipe_registry:    HashMap::new(),
+            plant_registry:     HashMap::new(),
+            weather_registry:   HashMap::new(),
+            patched_fields:     HashMap::new(),
+
             test:               HashMap::new()
         }
     }
@@ -160,8 +664,10 @@ impl Registry {
     // ==============================
     // Module - Founder
     // ==============================
+    /// Модули регистрируются в порядке загрузки
     pub fn register_module(&mut self, module_type: ModuleRegistry) {
         if !self.module_registry.contains_key(&module_type.id) {
+            self.load_order.push(module_type.id.clone());
             self.module_registry.insert(module_type.id.clone(), module_type);
         }
     }
@@ -170,10 +676,7 @@ impl Registry {
     // Entity
     // ==============================
     pub fn register_entity(&mut self, entity_type: EntityRegistry) {
-        if !self.entity_registry.contains_key(&entity_type.id_name) {
-            println!("Register Entity: {}", &entity_type.id_name);
-            self.entity_registry.insert(entity_type.id_name.clone(), entity_type);
-        }
+        register_def(&mut self.entity_registry, &mut self.patched_fields, DefKind::Entity, entity_type.id_name.clone(), entity_type.id_source.clone(), entity_type);
     }
 
     pub fn get_entity_texture(&self, name: &str, atlas: &AtlasRes) -> Option<SpriteSheetBundle> {
@@ -188,10 +691,7 @@ impl Registry {
     // Objects
     // ==============================
     pub fn register_object(&mut self, object_type: ObjectRegistry) {
-        if !self.object_registry.contains_key(&object_type.id_name) {
-            println!("Register Object: {}", &object_type.id_name);
-            self.object_registry.insert(object_type.id_name.clone(), object_type);
-        }
+        register_def(&mut self.object_registry, &mut self.patched_fields, DefKind::Object, object_type.id_name.clone(), object_type.id_source.clone(), object_type);
     }
 
     pub fn get_object_texture(&self, name: &str, atlas: &AtlasRes) -> Option<SpriteSheetBundle> {
@@ -206,10 +706,7 @@ impl Registry {
     // ==========
     //
     pub fn register_object_ct(&mut self, object_ct_type: PersistentObjectRegistry) {
-        if !self.object_ct_registry.contains_key(&object_ct_type.id_name) {
-            println!("Register Object_ct: {}", &object_ct_type.id_name);
-            self.object_ct_registry.insert(object_ct_type.id_name.clone(), object_ct_type);
-        }
+        register_def(&mut self.object_ct_registry, &mut self.patched_fields, DefKind::ObjectCt, object_ct_type.id_name.clone(), object_ct_type.id_source.clone(), object_ct_type);
     }
 
     pub fn get_object_ct_texture(&self, name: &str, atlas: &AtlasRes) -> Option<SpriteSheetBundle> {
@@ -224,11 +721,8 @@ impl Registry {
     // Items
     // ==============================
     pub fn register_item(&mut self, mut item_type: ItemRegistry) {
-        if !self.item_registry.contains_key(&item_type.id_name) {
-            println!("Register Item: {}", &item_type.id_name);
-            item_type.def_stack();
-            self.item_registry.insert(item_type.id_name.clone(), item_type);
-        }
+        item_type.def_stack();
+        register_def(&mut self.item_registry, &mut self.patched_fields, DefKind::Item, item_type.id_name.clone(), item_type.id_source.clone(), item_type);
     }
 
     pub fn get_item_texture(&self, name: &str, atlas: &AtlasRes, item_type: AtlasType) -> Option<SpriteSheetBundle> {
@@ -239,6 +733,395 @@ impl Registry {
         self.item_registry.get(name)
     }
 
+    // ==============================
+    // Progression
+    // ==============================
+    pub fn register_progression(&mut self, progression_type: ProgressionRegistry) {
+        register_def(&mut self.progression_registry, &mut self.patched_fields, DefKind::Progression, progression_type.id_name.clone(), progression_type.id_source.clone(), progression_type);
+    }
+
+    pub fn get_progression_info(&self, name: &str) -> Option<&ProgressionRegistry> {
+        self.progression_registry.get(name)
+    }
+
+    pub fn register_skill_node(&mut self, skill_type: SkillNodeRegistry) {
+        register_def(&mut self.skill_tree_registry, &mut self.patched_fields, DefKind::SkillNode, skill_type.id_name.clone(), skill_type.id_source.clone(), skill_type);
+    }
+
+    pub fn get_skill_node_info(&self, name: &str) -> Option<&SkillNodeRegistry> {
+        self.skill_tree_registry.get(name)
+    }
+
+    // ==============================
+    // Skills
+    // ==============================
+    pub fn register_skill(&mut self, skill_type: SkillRegistry) {
+        register_def(&mut self.skill_registry, &mut self.patched_fields, DefKind::Skill, skill_type.id_name.clone(), skill_type.id_source.clone(), skill_type);
+    }
+
+    pub fn get_skill_info(&self, name: &str) -> Option<&SkillRegistry> {
+        self.skill_registry.get(name)
+    }
+
+    /// Проверка ссылок на навыки: неизвестные навыки убираются у сущностей, узлы с неизвестными требованиями удаляются
+    pub fn validate_skills(&mut self) -> Vec<DefError> {
+        let mut errors = Vec::new();
+
+        for entity in self.entity_registry.values_mut() {
+            let Some(skills) = &mut entity.skills else {
+                continue;
+            };
+            let missing: Vec<String> = skills.iter()
+                .filter(|skill| !self.skill_registry.contains_key(*skill))
+                .cloned()
+                .collect();
+
+            if !missing.is_empty() {
+                errors.push(DefError::new(DefKind::Entity, &entity.id_name, Some("skills"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("entity {} references unknown skills {:?}", entity.id_name, missing)
+                )));
+                skills.retain(|skill| !missing.contains(skill));
+            }
+        }
+
+        let mut invalid = Vec::new();
+        for node in self.skill_tree_registry.values() {
+            let missing: Vec<&str> = node.requires.iter()
+                .map(|id_name| id_name.as_str())
+                .filter(|id_name| !self.skill_tree_registry.contains_key(*id_name))
+                .collect();
+
+            if !missing.is_empty() {
+                errors.push(DefError::new(DefKind::SkillNode, &node.id_name, Some("requires"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("skill node {} requires unknown {:?}", node.id_name, missing)
+                )));
+                invalid.push(node.id_name.clone());
+            }
+        }
+
+        for id_name in invalid {
+            self.skill_tree_registry.remove(&id_name);
+        }
+
+        errors
+    }
+
+    // ==============================
+    // Recipes
+    // ==============================
+    pub fn register_recipe(&mut self, recipe_type: RecipeRegistry) {
+        register_def(&mut self.recipe_registry, &mut self.patched_fields, DefKind::Recipe, recipe_type.id_name.clone(), recipe_type.id_source.clone(), recipe_type);
+    }
+
+    pub fn get_recipe_info(&self, name: &str) -> Option<&RecipeRegistry> {
+        self.recipe_registry.get(name)
+    }
+
+    /// Рецепт постройки данного объекта
+    pub fn get_construction_recipe(&self, object: &str) -> Option<&RecipeRegistry> {
+        self.recipe_registry.values().find(|recipe| recipe.construction && recipe.result.output == object)
+    }
+
+    /// Проверка рецептов по реестрам предметов и объектов, невалидные рецепты удаляются
+    pub fn validate_recipes(&mut self) -> Vec<DefError> {
+        let mut errors = Vec::new();
+        let mut invalid = Vec::new();
+
+        for recipe in self.recipe_registry.values() {
+            let mut missing: Vec<&str> = recipe.request.iter().map(|ingredient| ingredient.item.as_str())
+                .chain(recipe.tools.iter().map(|tool| tool.item.as_str()))
+                .filter(|item| !self.item_registry.contains_key(*item))
+                .collect();
+
+            // Рецепт постройки создаёт объект, обычный - предмет
+            let output_known = match recipe.construction {
+                true  => self.object_registry.contains_key(&recipe.result.output),
+                false => self.item_registry.contains_key(&recipe.result.output),
+            };
+            if !output_known {
+                missing.push(&recipe.result.output);
+            }
+
+            if let Some(workstation) = &recipe.workstation {
+                if !self.object_registry.contains_key(workstation) {
+                    missing.push(workstation);
+                }
+            }
+
+            if !missing.is_empty() {
+                errors.push(DefError::new(DefKind::Recipe, &recipe.id_name, None, GameError::new(
+                    GameErrorType::ItemMissing,
+                    format!("recipe {} references unknown {:?}", recipe.id_name, missing)
+                )));
+                invalid.push(recipe.id_name.clone());
+            } else if recipe.result.count == 0 || recipe.request.iter().any(|ingredient| ingredient.count == 0) {
+                errors.push(DefError::new(DefKind::Recipe, &recipe.id_name, Some("request"), GameError::new(
+                    GameErrorType::CraftingFailed,
+                    format!("recipe {} has zero counts", recipe.id_name)
+                )));
+                invalid.push(recipe.id_name.clone());
+            }
+        }
+
+        for id_name in invalid {
+            self.recipe_registry.remove(&id_name);
+        }
+
+        errors
+    }
+
+    // ==============================
+    // Plants
+    // ==============================
+    pub fn register_plant(&mut self, plant_type: PlantRegistry) {
+        register_def(&mut self.plant_registry, &mut self.patched_fields, DefKind::Plant, plant_type.id_name.clone(), plant_type.id_source.clone(), plant_type);
+    }
+
+    pub fn get_plant_info(&self, name: &str) -> Option<&PlantRegistry> {
+        self.plant_registry.get(name)
+    }
+
+    /// Текстура стадии растения (стадии хранятся в атласе объектов)
+    pub fn get_plant_texture(&self, info: &PlantRegistry, stage: usize, atlas: &AtlasRes) -> Option<SpriteSheetBundle> {
+        atlas.get_spritesheet(AtlasType::Objects, &info.stages.get(stage)?.id_texture)
+    }
+
+    /// Проверка растений по реестру предметов, невалидные растения удаляются
+    pub fn validate_plants(&mut self) -> Vec<DefError> {
+        let mut errors = Vec::new();
+        let mut invalid = Vec::new();
+
+        for plant in self.plant_registry.values() {
+            if plant.stages.is_empty() {
+                errors.push(DefError::new(DefKind::Plant, &plant.id_name, Some("stages"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("plant {} has no stages", plant.id_name)
+                )));
+                invalid.push(plant.id_name.clone());
+                continue;
+            }
+
+            let mut missing: Vec<&str> = plant.harvest.iter()
+                .flat_map(|harvest| harvest.loot.iter().map(|loot| loot.item.as_str()))
+                .filter(|item| !self.item_registry.contains_key(*item))
+                .collect();
+            if let AfterHarvest::Replant(replant) = &plant.after_harvest {
+                if !self.plant_registry.contains_key(replant) {
+                    missing.push(replant);
+                }
+            }
+
+            let stage_out_of_range = plant.harvest_stage().map_or(false, |stage| stage >= plant.stages.len())
+                || matches!(plant.after_harvest, AfterHarvest::Regrow(stage) if stage >= plant.stages.len());
+
+            if !missing.is_empty() {
+                errors.push(DefError::new(DefKind::Plant, &plant.id_name, None, GameError::new(
+                    GameErrorType::ItemMissing,
+                    format!("plant {} references unknown {:?}", plant.id_name, missing)
+                )));
+                invalid.push(plant.id_name.clone());
+            } else if stage_out_of_range {
+                errors.push(DefError::new(DefKind::Plant, &plant.id_name, Some("after_harvest"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("plant {} references a stage out of range", plant.id_name)
+                )));
+                invalid.push(plant.id_name.clone());
+            }
+        }
+
+        for id_name in invalid {
+            self.plant_registry.remove(&id_name);
+        }
+
+        errors
+    }
+
+    // ==============================
+    // Weather
+    // ==============================
+    pub fn register_weather(&mut self, weather_type: WeatherRegistry) {
+        register_def(&mut self.weather_registry, &mut self.patched_fields, DefKind::Weather, weather_type.id_name.clone(), weather_type.id_source.clone(), weather_type);
+    }
+
+    pub fn get_weather_info(&self, name: &str) -> Option<&WeatherRegistry> {
+        self.weather_registry.get(name)
+    }
+
+    /// Проверка погоды: длительность и переходы, невалидная погода удаляется
+    pub fn validate_weather(&mut self) -> Vec<DefError> {
+        let mut errors = Vec::new();
+        let mut invalid = Vec::new();
+
+        for weather in self.weather_registry.values() {
+            let missing: Vec<&str> = weather.transitions.keys()
+                .map(|id_name| id_name.as_str())
+                .filter(|id_name| !self.weather_registry.contains_key(*id_name))
+                .collect();
+
+            if !missing.is_empty() {
+                errors.push(DefError::new(DefKind::Weather, &weather.id_name, Some("transitions"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("weather {} transitions to unknown {:?}", weather.id_name, missing)
+                )));
+                invalid.push(weather.id_name.clone());
+            } else if weather.duration.0 <= 0.0 || weather.duration.0 > weather.duration.1 {
+                errors.push(DefError::new(DefKind::Weather, &weather.id_name, Some("duration"), GameError::new(
+                    GameErrorType::InvalidDef,
+                    format!("weather {} has invalid duration {:?}", weather.id_name, weather.duration)
+                )));
+                invalid.push(weather.id_name.clone());
+            }
+        }
+
+        for id_name in invalid {
+            self.weather_registry.remove(&id_name);
+        }
+
+        errors
+    }
+
+    // ==============================
+    // Patches
+    // ==============================
+    /// Применение патча к зарегистрированному определению, изменённые поля запоминают модуль патча
+    pub fn apply_patch(&mut self, patch: &PatchRegistry) -> Result<(), GameError> {
+        let id_name = &patch.id_name;
+        match patch.target {
+            DefKind::Entity         => patch_def(self.entity_registry.get_mut(id_name), patch),
+            DefKind::Object         => patch_def(self.object_registry.get_mut(id_name), patch),
+            DefKind::ObjectCt       => patch_def(self.object_ct_registry.get_mut(id_name), patch),
+            DefKind::Item           => patch_def(self.item_registry.get_mut(id_name), patch),
+            DefKind::Progression    => patch_def(self.progression_registry.get_mut(id_name), patch),
+            DefKind::SkillNode      => patch_def(self.skill_tree_registry.get_mut(id_name), patch),
+            DefKind::Skill          => patch_def(self.skill_registry.get_mut(id_name), patch),
+            DefKind::Recipe         => patch_def(self.recipe_registry.get_mut(id_name), patch),
+            DefKind::Plant          => patch_def(self.plant_registry.get_mut(id_name), patch),
+            DefKind::Weather        => patch_def(self.weather_registry.get_mut(id_name), patch),
+        }?;
+
+        // Вложенное поле помечает своё поле верхнего уровня
+        let source = patch.id_source.clone().unwrap_or_default();
+        let fields = self.patched_fields.entry((patch.target, id_name.clone())).or_default();
+        for operation in &patch.operations {
+            let field = operation.field().split('.').next().unwrap_or_default();
+            fields.insert(field.to_string(), source.clone());
+        }
+        Ok(())
+    }
+
+    /// Перенос определения из другого реестра вместе с модулями его патчей, `false` - там его нет
+    pub fn take_def(&mut self, from: &mut Registry, kind: DefKind, id_name: &str) -> bool {
+        fn take<T>(to: &mut HashMap<String, T>, from: &mut HashMap<String, T>, id_name: &str) -> bool {
+            from.remove(id_name).map(|def| to.insert(id_name.to_string(), def)).is_some()
+        }
+
+        let taken = match kind {
+            DefKind::Entity         => take(&mut self.entity_registry, &mut from.entity_registry, id_name),
+            DefKind::Object         => take(&mut self.object_registry, &mut from.object_registry, id_name),
+            DefKind::ObjectCt       => take(&mut self.object_ct_registry, &mut from.object_ct_registry, id_name),
+            DefKind::Item           => take(&mut self.item_registry, &mut from.item_registry, id_name),
+            DefKind::Progression    => take(&mut self.progression_registry, &mut from.progression_registry, id_name),
+            DefKind::SkillNode      => take(&mut self.skill_tree_registry, &mut from.skill_tree_registry, id_name),
+            DefKind::Skill          => take(&mut self.skill_registry, &mut from.skill_registry, id_name),
+            DefKind::Recipe         => take(&mut self.recipe_registry, &mut from.recipe_registry, id_name),
+            DefKind::Plant          => take(&mut self.plant_registry, &mut from.plant_registry, id_name),
+            DefKind::Weather        => take(&mut self.weather_registry, &mut from.weather_registry, id_name),
+        };
+        if !taken {
+            return false;
+        }
+
+        let key = (kind, id_name.to_string());
+        match from.patched_fields.remove(&key) {
+            Some(fields)    => self.patched_fields.insert(key, fields),
+            None            => self.patched_fields.remove(&key),
+        };
+        true
+    }
+
+    /// Идентификаторы определений данного вида
+    pub fn def_ids(&self, kind: DefKind) -> Vec<String> {
+        let mut ids: Vec<String> = match kind {
+            DefKind::Entity         => self.entity_registry.keys().cloned().collect(),
+            DefKind::Object         => self.object_registry.keys().cloned().collect(),
+            DefKind::ObjectCt       => self.object_ct_registry.keys().cloned().collect(),
+            DefKind::Item           => self.item_registry.keys().cloned().collect(),
+            DefKind::Progression    => self.progression_registry.keys().cloned().collect(),
+            DefKind::SkillNode      => self.skill_tree_registry.keys().cloned().collect(),
+            DefKind::Skill          => self.skill_registry.keys().cloned().collect(),
+            DefKind::Recipe         => self.recipe_registry.keys().cloned().collect(),
+            DefKind::Plant          => self.plant_registry.keys().cloned().collect(),
+            DefKind::Weather        => self.weather_registry.keys().cloned().collect(),
+        };
+        ids.sort();
+        ids
+    }
+
+    /// Поля определения с модулем, последним их изменившим (0 - Поле | 1 - Значение | 2 - Модуль)
+    pub fn def_fields(&self, kind: DefKind, id_name: &str) -> Vec<(String, Value, String)> {
+        let value = match kind {
+            DefKind::Entity         => def_value(self.entity_registry.get(id_name)),
+            DefKind::Object         => def_value(self.object_registry.get(id_name)),
+            DefKind::ObjectCt       => def_value(self.object_ct_registry.get(id_name)),
+            DefKind::Item           => def_value(self.item_registry.get(id_name)),
+            DefKind::Progression    => def_value(self.progression_registry.get(id_name)),
+            DefKind::SkillNode      => def_value(self.skill_tree_registry.get(id_name)),
+            DefKind::Skill          => def_value(self.skill_registry.get(id_name)),
+            DefKind::Recipe         => def_value(self.recipe_registry.get(id_name)),
+            DefKind::Plant          => def_value(self.plant_registry.get(id_name)),
+            DefKind::Weather        => def_value(self.weather_registry.get(id_name)),
+        };
+        let Some(Value::Object(fields)) = value else {
+            return Vec::new();
+        };
+
+        let source = fields.get("id_source").and_then(|source| source.as_str()).unwrap_or_default().to_string();
+        let patched = self.patched_fields.get(&(kind, id_name.to_string()));
+
+        fields.into_iter().map(|(field, value)| {
+            let module = patched.and_then(|patched| patched.get(&field)).unwrap_or(&source).clone();
+            (field, value, module)
+        }).collect()
+    }
+
+    /// Текстуры определения с атласом и полем, в котором они указаны
+    pub fn def_textures(&self, kind: DefKind, id_name: &str) -> Vec<(AtlasType, &'static str, String)> {
+        let mut textures = Vec::new();
+        match kind {
+            DefKind::Entity => if let Some(info) = self.get_entity_info(id_name) {
+                textures.push((AtlasType::Entity, "id_texture_b", info.id_texture_b.clone()));
+                textures.extend(info.id_texture_h.clone().map(|texture| (AtlasType::Entity, "id_texture_h", texture)));
+            },
+            DefKind::Item => if let Some(info) = self.get_item_info(id_name) {
+                let atlas = match info.item_type {
+                    ItemType::Weapon(_) => AtlasType::Weapon,
+                    ItemType::Tool(_)   => AtlasType::Tools,
+                    _                   => AtlasType::Items,
+                };
+                textures.push((atlas, "id_texture", info.id_texture.clone()));
+            },
+            DefKind::Object => if let Some(info) = self.get_object_info(id_name) {
+                textures.push((AtlasType::Objects, "id_texture", info.id_texture.clone()));
+                textures.extend(info.door.as_ref()
+                    .and_then(|door| door.id_texture_open.clone())
+                    .map(|texture| (AtlasType::Objects, "door.id_texture_open", texture)));
+            },
+            DefKind::ObjectCt => if let Some(info) = self.get_object_ct_info(id_name) {
+                textures.push((AtlasType::ConnectObj, "id_texture", info.id_texture.clone()));
+            },
+            DefKind::Plant => if let Some(info) = self.get_plant_info(id_name) {
+                textures.extend(info.stages.iter().map(|stage| (AtlasType::Objects, "stages", stage.id_texture.clone())));
+            },
+            DefKind::Weather => if let Some(info) = self.get_weather_info(id_name) {
+                textures.extend(info.particle.as_ref().map(|particle| (AtlasType::Particle, "particle.id_texture", particle.id_texture.clone())));
+            },
+            _ => {},
+        }
+        textures
+    }
+
     // ==============================
     // Test
     // ==============================
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 
 use bevy::prelude::*;
 
+use crate::core::world::World::WorldInfo;
+
 #[derive(Clone, Copy)]
 pub struct Animation {
     pub cooldown: f32,
@@ -31,14 +33,15 @@ impl Default for Animator {
         }
     }
 }
-pub fn animate_sprite(time: Res<Time>, mut query: Query<(&mut Animator, &mut TextureAtlas)>) {
+/// Анимация идёт по игровым часам: останавливается на паузе и ускоряется вместе с миром
+pub fn animate_sprite(clock: Res<WorldInfo>, mut query: Query<(&mut Animator, &mut TextureAtlas)>) {
     for (mut animator, mut sprite) in query.iter_mut() {
         let anim = animator.animation_bank[animator.current_animation.as_str()];
         if animator.last_animation != animator.current_animation {
             sprite.index = anim.start - 1;
         }
 
-        animator.timer = animator.timer - time.delta().as_secs_f32();
+        animator.timer = animator.timer - clock.delta();
 
         if animator.timer <= 0. {
             animator.timer = anim.cooldown;
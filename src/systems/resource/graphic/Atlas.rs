@@ -24,7 +24,7 @@ pub struct AtlasData {
     pub ids:    Option<HashMap<String, usize>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AtlasType {
     Items,
     Tools,
@@ -56,7 +56,7 @@ impl AtlasRes {
         }
     }
 
-    fn def_atlastype(&self, atlas_type: AtlasType) -> &AtlasData {
+    pub fn def_atlastype(&self, atlas_type: AtlasType) -> &AtlasData {
         match atlas_type {
             AtlasType::Items        => return &self.items,
             AtlasType::Tools        => return &self.tools,
@@ -72,6 +72,22 @@ impl AtlasRes {
         };
     }
 
+    pub fn def_atlastype_mut(&mut self, atlas_type: AtlasType) -> &mut AtlasData {
+        match atlas_type {
+            AtlasType::Items        => &mut self.items,
+            AtlasType::Tools        => &mut self.tools,
+            AtlasType::Weapon       => &mut self.weapon,
+            AtlasType::Material     => &mut self.material,
+            AtlasType::Terrain      => &mut self.terrain,
+            AtlasType::Objects      => &mut self.objects,
+            AtlasType::ConnectObj   => &mut self.con_obj,
+            AtlasType::Particle     => &mut self.particle,
+            AtlasType::Entity       => &mut self.entity,
+            AtlasType::Test         => &mut self.test,
+            AtlasType::Ui           => &mut self.ui,
+        }
+    }
+
     pub fn get_spritesheet(&self, atlas_type: AtlasType, name: &str) -> Option<SpriteSheetBundle> {
         let atlas_data = Self::def_atlastype(&self, atlas_type);
 
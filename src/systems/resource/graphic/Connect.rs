@@ -1,11 +1,31 @@
 #![allow(unused)]
 use bevy::prelude::*;
 
+use std::collections::HashMap;
+
 use crate::core::{
+    resource::{
+        graphic::Atlas::AtlasRes,
+        Registry::Registry
+    },
     Object::PersistentObject,
     world::Grid::Grid,
+    AppState
 };
 
+//
+//
+//
+
+pub fn connect_plugin(app: &mut App) {
+    app.add_systems(Update, update_connected_objects.run_if(in_state(AppState::Game)));
+}
+
+/// Размер клетки соединяющегося объекта
+pub const CONNECTED_TILE_SIZE: i32 = 16;
+/// Кол-во клеток в стороне группы текстур одного объекта (4x4 варианта соединения)
+pub const CONNECTED_GROUP_SIZE: usize = 4;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 pub enum ConnectedObject {
     All             = 0,
@@ -32,24 +52,51 @@ impl ConnectedObject {
         *self as usize
     }
 
+    /// Вариант соединения по наличию соседей с четырёх сторон
+    pub fn from_neighbors(north: bool, east: bool, south: bool, west: bool) -> Self {
+        use ConnectedObject::*;
+
+        match (north, east, south, west) {
+            (true, true, true, true)        => All,
+            (true, true, false, true)       => NorthEastWest,
+            (false, true, true, true)       => EastWestSouth,
+            (false, true, false, true)      => EastWest,
+            (true, false, true, true)       => NorthWestSouth,
+            (true, false, false, true)      => NorthWest,
+            (false, false, true, true)      => WestSouth,
+            (false, false, false, true)     => West,
+            (true, true, true, false)       => NorthEastSouth,
+            (true, true, false, false)      => NorthEast,
+            (false, true, true, false)      => EastSouth,
+            (false, true, false, false)     => East,
+            (true, false, true, false)      => NorthSouth,
+            (true, false, false, false)     => North,
+            (false, false, true, false)     => South,
+            (false, false, false, false)    => None,
+        }
+    }
+
+    /// Индекс клетки в атласе
+    /// 
+    /// `atlas_size` - ширина атласа в клетках, `atlas_number` - номер группы 4x4 объекта в атласе
     pub fn calculate_index(
         atlas_size: usize,
         atlas_number: usize, 
         direction_index: usize
     ) -> usize {
-        let group_width = (atlas_size / 4).min(atlas_size);
+        let groups_in_row = (atlas_size / CONNECTED_GROUP_SIZE).max(1);
         
         // Определяем координаты группы
-        let group_x = atlas_number % (atlas_size / group_width);
-        let group_y = atlas_number / (atlas_size / group_width);
+        let group_x = atlas_number % groups_in_row;
+        let group_y = atlas_number / groups_in_row;
 
         // Определяем координаты внутри группы
-        let texture_x = direction_index % 4;
-        let texture_y = direction_index / 4;
+        let texture_x = direction_index % CONNECTED_GROUP_SIZE;
+        let texture_y = direction_index / CONNECTED_GROUP_SIZE;
 
         // Конечные глобальные координаты
-        let global_x = group_x * 4 + texture_x;
-        let global_y = group_y * 4 + texture_y;
+        let global_x = group_x * CONNECTED_GROUP_SIZE + texture_x;
+        let global_y = group_y * CONNECTED_GROUP_SIZE + texture_y;
 
         // Возвращаем индекс как одномерный
         global_y * atlas_size + global_x
@@ -84,67 +131,83 @@ impl Facing {
     }
 }
 
-// impl IndexableSprite for WallSprite {
-//     type AtlasHandleWrapper = CharacterAtlas;
-//     fn index(&self) -> usize {
-//         match self {
-//             ConnectedSprite::All => 12,
-//             ConnectedSprite::None => 13,
-//             ConnectedSprite::NorthSouth => 14,
-//             ConnectedSprite::EastWest => 15,
-
-//             ConnectedSprite::East => 12 + 16,
-//             ConnectedSprite::South => 13 + 16,
-//             ConnectedSprite::West => 14 + 16,
-//             ConnectedSprite::North => 15 + 16,
-
-//             ConnectedSprite::EastSouth => 12 + 16 * 2,
-//             ConnectedSprite::WestSouth => 13 + 16 * 2,
-//             ConnectedSprite::NorthWest => 14 + 16 * 2,
-//             ConnectedSprite::NorthEast => 15 + 16 * 2,
-
-//             ConnectedSprite::NorthWestSouth => 12 + 16 * 3,
-//             ConnectedSprite::NorthEastWest => 13 + 16 * 3,
-//             ConnectedSprite::NorthEastSouth => 14 + 16 * 3,
-//             ConnectedSprite::EastWestSouth => 15 + 16 * 3,
-
-//             ConnectedSprite::Outline => 15 + 16 * 4,
-//         }
-//     }
-// }
-
-// fn update_wall_sprite(mut sprites: Query<&mut ConnectedObject>, grid: Res<Grid<PersistentObject>>) {
-//     for (ent, location) in grid {
-//         if let Ok(mut wall) = sprites.get_mut(ent) {
-//             let east = &(location.0 + IVec2::new(1, 0)).into();
-//             let west = &(location.0 - IVec2::new(1, 0)).into();
-//             let north = &(location.0 + IVec2::new(0, 1)).into();
-//             let south = &(location.0 - IVec2::new(0, 1)).into();
-
-//             use ConnectedObject::*;
-//             *wall = match (
-//                 grid.occupied(west),
-//                 grid.occupied(east),
-//                 grid.occupied(north),
-//                 grid.occupied(south),
-//             ) {
-//                 (true, true, true, true)     => All,
-//                 (true, true, true, false)    => NorthEastWest,
-//                 (true, true, false, true)    => EastWestSouth,
-//                 (true, true, false, false)   => EastWest,
-//                 (true, false, true, true)    => NorthWestSouth,
-//                 (true, false, true, false)   => NorthWest,
-//                 (true, false, false, true)   => WestSouth,
-//                 (true, false, false, false)  => West,
-//                 (false, true, true, true)    => NorthEastSouth,
-//                 (false, true, true, false)   => NorthEast,
-//                 (false, true, false, true)   => EastSouth,
-//                 (false, true, false, false)  => East,
-//                 (false, false, true, true)   => NorthSouth,
-//                 (false, false, true, false)  => North,
-//                 (false, false, false, true)  => South,
-//                 (false, false, false, false) => None,
-//             };
-//         }
-//     }
-// }
\ No newline at end of file
+/// Индекс текстуры соединяющегося объекта в атласе с учётом варианта соединения
+pub fn connected_index(
+    atlas:      &AtlasRes,
+    layouts:    &Assets<TextureAtlasLayout>,
+    texture:    &str,
+    connection: ConnectedObject,
+) -> Option<usize> {
+    let group = *atlas.con_obj.ids.as_ref()?.get(texture)?;
+    let layout = layouts.get(atlas.con_obj.layout.as_ref()?)?;
+    let columns = layout.size.x as usize / CONNECTED_TILE_SIZE as usize;
+
+    Some(ConnectedObject::calculate_index(columns, group, connection.dir_index()))
+}
+
+/// Пересчёт соединений у поставленных и разрушенных объектов, а также у их соседей
+fn update_connected_objects(
+    mut objects:    Query<(Entity, &mut PersistentObject, &mut TextureAtlas, &Transform)>,
+    mut removed:    RemovedComponents<PersistentObject>,
+    mut tiles:      Local<HashMap<Entity, IVec2>>,
+        grid:       Res<Grid>,
+        atlas:      Res<AtlasRes>,
+        layouts:    Res<Assets<TextureAtlasLayout>>,
+        registry:   Res<Registry>,
+) {
+    let sides = [IVec2::Y, IVec2::X, IVec2::NEG_Y, IVec2::NEG_X];
+    let mut dirty: Vec<IVec2> = Vec::new();
+
+    for (entity, object, _, transform) in objects.iter_mut() {
+        if object.is_added() {
+            let tile = Grid::tile_of(transform.translation.truncate().as_ivec2());
+            tiles.insert(entity, tile);
+            dirty.push(tile);
+            dirty.extend(sides.iter().map(|side| tile + *side));
+        }
+    }
+
+    for entity in removed.read() {
+        if let Some(tile) = tiles.remove(&entity) {
+            dirty.extend(sides.iter().map(|side| tile + *side));
+        }
+    }
+
+    if dirty.is_empty() {
+        return;
+    }
+
+    // Объект на клетке, если он соединяющийся
+    let object_at = |tile: IVec2| {
+        grid.object_at(&(tile * CONNECTED_TILE_SIZE + CONNECTED_TILE_SIZE / 2))
+            .and_then(|entity| objects.get(entity).ok())
+            .map(|(entity, object, _, _)| (entity, object))
+    };
+
+    let mut connections: Vec<(Entity, ConnectedObject)> = Vec::new();
+    for tile in dirty {
+        let Some((entity, object)) = object_at(tile) else {
+            continue;
+        };
+
+        // Соединяются только объекты одного типа
+        let [north, east, south, west] = sides.map(|side| {
+            object_at(tile + side).map_or(false, |(_, neighbor)| neighbor.id_name == object.id_name)
+        });
+        connections.push((entity, ConnectedObject::from_neighbors(north, east, south, west)));
+    }
+
+    for (entity, connection) in connections {
+        let Ok((_, mut object, mut texture, _)) = objects.get_mut(entity) else {
+            continue;
+        };
+        let Some(info) = registry.get_object_ct_info(&object.id_name) else {
+            continue;
+        };
+
+        if let Some(index) = connected_index(&atlas, &layouts, &info.id_texture, connection) {
+            texture.index = index;
+        }
+        object.direction = connection;
+    }
+}
@@ -16,6 +16,7 @@ use crate::core::{
     resource::{
         graphic::Atlas::{
             AtlasRes,
+            AtlasType,
             // DirectionAtlas, 
             // TestTextureAtlas
         },
@@ -27,21 +28,15 @@ use crate::core::{
     AppState,
 };
 
-/// Проверка чего-то с каждым обновлением
+/// Ожидание загрузки папок текстур всех модулей
 pub fn check_textures(
     mut next_state:         ResMut<NextState<AppState>>,
     //    resource_folder:    Res<ResourceFolder>,
         resource_module:    Res<ResourceModule>,
-    mut events:             EventReader<AssetEvent<LoadedFolder>>,
+        asset_server:       Res<AssetServer>,
 ) {
-    for event in events.read() {
-        // if event.is_loaded_with_dependencies(&resource_folder.0) {
-        //     next_state.set(AppState::ResourceLoading);
-        // }
-
-        if event.is_loaded_with_dependencies(&resource_module.0) {
-            next_state.set(AppState::ResourceLoading);
-        }
+    if resource_module.0.iter().all(|folder| asset_server.is_loaded_with_dependencies(folder)) {
+        next_state.set(AppState::ResourceLoading);
     }
 }
 
@@ -58,149 +53,23 @@ pub fn setup_ex(
     mut next_state:         ResMut<NextState<AppState>>,
         load_buff:          ResMut<LoadingBuffer>
 ) {
-    // ==============================
-    // Entity
-    // ==============================
-    println!("Create atlas for Entities...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    let (texture_atlas_nearest, entity_texture, entity_hash) = load_and_index_atlas_ex(
-        &load_buff.verified_entity_texture,
-        &loaded_folder,
-        16.0,
-        4,
-        None,
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-    let entity_layout = texture_atlases.add(texture_atlas_nearest);
-
-    atlas.entity.layout =    Some(entity_layout);
-    atlas.entity.image =     Some(entity_texture);
-    atlas.entity.ids =       Some(entity_hash);
-
-    // ==============================
-    // Items
-    // ==============================
-    println!("Create atlas for Items...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    let (texture_atlas_nearest, items_texture, items_hash) = create_texture_atlas_ex(
-        &load_buff.verified_item_texture,
-        &loaded_folder,
-        Some(UVec2::splat(1)),
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-    let items_layout = texture_atlases.add(texture_atlas_nearest);
-
-    atlas.items.layout =    Some(items_layout);
-    atlas.items.image =     Some(items_texture);
-    atlas.items.ids =       Some(items_hash);
+    // Текстуры всех модулей в порядке загрузки, текстура позднего модуля перекрывает одноимённую раннюю
+    let loaded_folder = module_folder(&resource_module, &loaded_folders);
 
-    // ==============================
-    // Tools
-    // ==============================
-    // let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    // let (texture_atlas_nearest, tools_texture, tools_hash) = load_and_index_atlas_ex(
-    //     &load_buff.verified_tool_texture,
-    //     &loaded_folder,
-    //     16.0,
-    //     4,
-    //     None,
-    //     Some(ImageSampler::nearest()),
-    //     &mut textures,
-    // );
-    // let tools_layout = texture_atlases.add(texture_atlas_nearest);
-
-    // atlas.tools.layout =    Some(tools_layout);
-    // atlas.tools.image =     Some(tools_texture);
-    // atlas.tools.ids =       Some(tools_hash);
+    for (atlas_type, label) in MODULE_ATLASES {
+        println!("Create atlas for {}...", label);
 
-    // ==============================
-    // Weapon
-    // ==============================
-    println!("Create atlas for Weapons...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    let (texture_atlas_nearest, weapon_texture, weapon_hash) = load_and_index_atlas_ex(
-        &load_buff.verified_weapon_texture,
-        &loaded_folder,
-        32.0,
-        4,
-        None,
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-    let weapon_layout = texture_atlases.add(texture_atlas_nearest);
-
-    atlas.weapon.layout =    Some(weapon_layout);
-    atlas.weapon.image =     Some(weapon_texture);
-    atlas.weapon.ids =       Some(weapon_hash);
-
-    // ==============================
-    // Objects
-    // ==============================
-    println!("Create atlas for Objects...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    let (texture_atlas_nearest, objects_texture, objects_hash) = create_texture_atlas_ex(
-        &load_buff.verified_object_texture,
-        &loaded_folder,
-        Some(UVec2::splat(1)),
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-    let object_layout = texture_atlases.add(texture_atlas_nearest);
-
-    atlas.objects.layout =  Some(object_layout);
-    atlas.objects.image =   Some(objects_texture);
-    atlas.objects.ids =     Some(objects_hash);
+        let Some((texture_atlas_nearest, atlas_texture, atlas_hash)) = build_atlas(&atlas_type, &load_buff, &loaded_folder, &mut textures) else {
+            warn!("{} textures is not loading!", label);
+            continue;
+        };
+        let atlas_data = atlas.def_atlastype_mut(atlas_type);
 
-    // ==============================
-    // Connected Texture
-    // ==============================
-    println!("Create atlas for Connected Objects...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    if let Some((texture_atlas_nearest, con_objects_texture, con_objects_hash)) = create_connected_atlas(
-        &load_buff.verified_object_ct_texture,
-        &loaded_folder,
-        16.0,
-        4,
-        None,
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    ) {
-        let con_object_layout = texture_atlases.add(texture_atlas_nearest);
-
-        atlas.con_obj.layout =  Some(con_object_layout);
-        atlas.con_obj.image =   Some(con_objects_texture);
-        atlas.con_obj.ids =     Some(con_objects_hash);
-    } else {
-        warn!("Connecteed textures is not loading!");
+        atlas_data.layout = Some(texture_atlases.add(texture_atlas_nearest));
+        atlas_data.image =  Some(atlas_texture);
+        atlas_data.ids =    Some(atlas_hash);
     }
 
-    // ==============================
-    // gui
-    // ==============================
-    println!("Create atlas for UI...");
-    let loaded_folder = loaded_folders.get(&resource_module.0).unwrap();
-
-    let (texture_atlas_nearest, ui_texture, ui_hash) = create_texture_atlas_ex(
-        &load_buff.verified_ui_texture,
-        &loaded_folder,
-        Some(UVec2::splat(1)),
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-    let ui_layout = texture_atlases.add(texture_atlas_nearest);
-
-    atlas.ui.layout =    Some(ui_layout);
-    atlas.ui.image =     Some(ui_texture);
-    atlas.ui.ids =       Some(ui_hash);
-
     // ==============================
     // Test
     // ==============================
@@ -215,6 +84,53 @@ pub fn setup_ex(
     info!("State: MainMenu")
 }
 
+/// Атласы, собираемые из текстур модулей (0 - Атлас | 1 - Название в логе)
+pub const MODULE_ATLASES: [(AtlasType, &str); 8] = [
+    (AtlasType::Entity,     "Entities"),
+    (AtlasType::Items,      "Items"),
+    (AtlasType::Tools,      "Tools"),
+    (AtlasType::Weapon,     "Weapons"),
+    (AtlasType::Objects,    "Objects"),
+    (AtlasType::ConnectObj, "Connected Objects"),
+    (AtlasType::Particle,   "Particles"),
+    (AtlasType::Ui,         "UI"),
+];
+
+/// Текстуры всех модулей одной папкой
+pub fn module_folder(
+    resource_module:    &ResourceModule,
+    loaded_folders:     &Assets<LoadedFolder>,
+) -> LoadedFolder {
+    LoadedFolder {
+        handles: resource_module.0.iter()
+            .filter_map(|folder| loaded_folders.get(folder))
+            .flat_map(|folder| folder.handles.iter().cloned())
+            .collect()
+    }
+}
+
+/// Сборка атласа из проверенных текстур, `None` - атлас не собирается
+pub fn build_atlas(
+    atlas_type: &AtlasType,
+    load_buff:  &LoadingBuffer,
+    folder:     &LoadedFolder,
+    textures:   &mut ResMut<Assets<Image>>,
+) -> Option<(TextureAtlasLayout, Handle<Image>, HashMap<String, usize>)> {
+    let load_list = load_buff.verified_textures(atlas_type);
+
+    match atlas_type {
+        AtlasType::Entity       => Some(load_and_index_atlas_ex(load_list, folder, 16.0, 4, None, Some(ImageSampler::nearest()), textures)),
+        AtlasType::Weapon       => Some(load_and_index_atlas_ex(load_list, folder, 32.0, 4, None, Some(ImageSampler::nearest()), textures)),
+        AtlasType::ConnectObj   => create_connected_atlas(load_list, folder, 16.0, 4, None, Some(ImageSampler::nearest()), textures),
+        // Пустой атлас частиц не собирается, погода тогда остаётся без частиц
+        AtlasType::Particle if load_list.is_empty() => None,
+        AtlasType::Items | AtlasType::Tools | AtlasType::Objects | AtlasType::Particle | AtlasType::Ui => {
+            Some(create_texture_atlas_ex(load_list, folder, Some(UVec2::splat(1)), Some(ImageSampler::nearest()), textures))
+        },
+        _ => None,
+    }
+}
+
 /// Получить квадратный минимум для создания полностью заполняемого атласа.
 fn calculate_min_square_size(num_textures: usize) -> usize {
     return (num_textures as f64).sqrt().ceil() as usize;
@@ -422,8 +338,9 @@ fn create_connected_atlas(
             .padding(padding.unwrap_or_default());
 
     let mut textures_ids: HashMap<String, usize> = HashMap::new();
-    // Нумерация загруженных единиц
-    let mut num: usize = 0;
+    // Загруженные текстуры, номер группы определяется после упаковки атласа
+    let mut loaded: Vec<(String, AssetId<Image>)> = Vec::new();
+    let group_size = tile_size * pack_size as f32;
 
     // Прогон по имеющимся текстурам в loadedfolder
     for handle in folder.handles.iter() {
@@ -451,13 +368,23 @@ fn create_connected_atlas(
                                     continue;
                                 };
 
+                                // Текстура должна состоять ровно из 4x4 вариантов соединения
+                                if texture.width() as f32 != group_size || texture.height() as f32 != group_size {
+                                    warn!(
+                                        "{:?} has size {}x{}, expected {}x{}",
+                                        handle.path().unwrap(), texture.width(), texture.height(), group_size, group_size
+                                    );
+
+                                    continue;
+                                }
+
                                 // Получение имени загружаемого файла, чтобы использовать это как ключь-имя в hash-таблице
                                 if let Some(path) = handle.path() {
                                     if let Some(file_name) = path.to_string().as_str().split('/').last() {
                                         let file_fmt = Path::new(file_name).file_stem().unwrap().to_string_lossy();
 
                                         println!("Loaded module resource | {}", file_fmt);
-                                        textures_ids.insert(file_fmt.to_string(), num);
+                                        loaded.push((file_fmt.to_string(), id));
                                     } else {
                                         warn!("[Error] - An error occurred while reading the file name!")
                                     }
@@ -465,7 +392,6 @@ fn create_connected_atlas(
                                     warn!("[Error] - An error occurred while reading the file path!")
                                 }
 
-                                num += 1;
                                 // Добавление на полотно добавляемую текстуру
                                 texture_atlas_builder.add_texture(Some(id), texture);
                             }
@@ -476,43 +402,41 @@ fn create_connected_atlas(
         }
     }
 
-    // if let Ok((_, texture)) = texture_atlas_builder.finish() {
-    //     let texture = textures.add(texture);
-
-    //     // Обновление настройки выборки в атласе текстур
-    //     let image = textures.get_mut(&texture).unwrap();
-    //         image.sampler = sampling.unwrap_or_default();
-
-    //     let layout = TextureAtlasLayout::from_grid(
-    //         Vec2::splat(atlas_size as f32 * pack_size as f32),
-    //         tile_size as usize,
-    //         tile_size as usize,
-    //         None,
-    //         None,
-    //     );
+    if loaded.is_empty() {
+        return None;
+    }
 
-    //     return Some((layout, texture, textures_ids));
-    // }
+    let Ok((packed, texture)) = texture_atlas_builder.finish() else {
+        return None;
+    };
 
-    // None
+    // Номер группы 4x4 по положению текстуры на полотне
+    let groups_in_row = (packed.size.x / group_size) as usize;
+    for (name, id) in loaded {
+        if let Some(index) = packed.get_texture_index(id) {
+            let rect = packed.textures[index];
+            let group = (rect.min.y / group_size) as usize * groups_in_row + (rect.min.x / group_size) as usize;
 
-    let (_, texture) = texture_atlas_builder.finish().unwrap();
+            textures_ids.insert(name, group);
+        }
+    }
 
-        let texture = textures.add(texture);
+    let texture = textures.add(texture);
 
-        // Обновление настройки выборки в атласе текстур
-        let image = textures.get_mut(&texture).unwrap();
-            image.sampler = sampling.unwrap_or_default();
+    // Обновление настройки выборки в атласе текстур
+    let image = textures.get_mut(&texture).unwrap();
+        image.sampler = sampling.unwrap_or_default();
 
-        let layout = TextureAtlasLayout::from_grid(
-            Vec2::splat(atlas_size as f32 * pack_size as f32),
-            tile_size as usize,
-            tile_size as usize,
-            None,
-            None,
-        );
+    // Разбивка полотна на клетки, индекс клетки - `ConnectedObject::calculate_index`
+    let layout = TextureAtlasLayout::from_grid(
+        Vec2::splat(tile_size),
+        (packed.size.x / tile_size) as usize,
+        (packed.size.y / tile_size) as usize,
+        None,
+        None,
+    );
 
-    return Some((layout, texture, textures_ids));
+    Some((layout, texture, textures_ids))
 }
 // ==============================
 // 
@@ -1,7 +1,13 @@
 pub mod graphic;
+pub mod Diagnostic;
+pub mod HotReload;
+pub mod Module;
 pub mod Registry;
 
-use std::collections::HashSet;
+use std::collections::{
+    HashMap,
+    HashSet
+};
 use std::path::{Path, PathBuf};
 use std::fs;
 
@@ -17,6 +23,7 @@ use crate::core::{
     //     EntityType,
     //     HumonoidType
     // },
+    resource::graphic::Atlas::AtlasType,
     ItemType::*,
     Settings::Settings,
     AppState,
@@ -53,6 +60,8 @@ impl Plugin for ResourcePlugin {
         app
             // Init Plugins
             .add_plugins(SpriteLayerPlugin::<SpriteLayer>::default())
+            // Перезагрузка определений и текстур при их изменении на диске
+            .add_plugins(HotReload::hot_reload_plugin)
             // Проверка целостности данных ядра
             // Инициализация загрузки ресурсов ядра ==============================
             // Взятие ресурсов из assets
@@ -62,12 +71,14 @@ impl Plugin for ResourcePlugin {
             .insert_resource(graphic::Atlas::DirectionAtlas::default())
             // Init registry
             .insert_resource(Registry::Registry::new())
+            .init_resource::<Diagnostic::LoadDiagnostics>()
             // Проверка ресурсов на зависимости (Непонятно как оно точно работает)
             .add_systems(Update,graphic::check_textures.run_if(in_state(AppState::ResourceCheck)))
             .add_systems(OnEnter(AppState::ResourceLoading), (
                 Self::loading_module,
                 graphic::setup_ex,
                 Self::register_types,
+                Self::report_diagnostics,
             ).chain())
             // - Загрузка DLC
             // Инициализация загрузки пользовательских ресурсов (Текстуры, аддоны)
@@ -83,12 +94,16 @@ impl Plugin for ResourcePlugin {
 // #[derive(Resource, Default)]
 // pub struct ResourceFolder(Handle<LoadedFolder>, Handle<LoadedFolder>);
 
+/// Папки текстур модулей в порядке загрузки
 #[derive(Resource, Default)]
-pub struct ResourceModule(Handle<LoadedFolder>);
+pub struct ResourceModule(Vec<Handle<LoadedFolder>>);
 
 #[derive(Resource, Default)]
 pub struct LoadingBuffer {
     source_id:                  String,
+    modules:                    Vec<Module::ModuleEntry>,   // Модули в порядке загрузки
+    diagnostics:                Vec<Diagnostic::Diagnostic>,
+    def_files:                  HashMap<(Registry::DefKind, String), (String, PathBuf)>,   // Модуль и файл определения
     textures_path_buf:          Vec<String>,
     reg_item_tex_path:          Vec<String>,
     reg_tool_tex_path:          Vec<String>,
@@ -97,6 +112,7 @@ pub struct LoadingBuffer {
     reg_object_tex_path:        Vec<String>,
     reg_object_ct_tex_path:     Vec<String>,
     reg_ui_tex_path:            Vec<String>,
+    reg_particle_tex_path:      Vec<String>,
     verified_item_texture:      Vec<String>,
     verified_tool_texture:      Vec<String>,
     verified_weapon_texture:    Vec<String>,
@@ -104,6 +120,23 @@ pub struct LoadingBuffer {
     verified_object_texture:    Vec<String>,
     verified_object_ct_texture: Vec<String>,
     verified_ui_texture:        Vec<String>,
+    verified_particle_texture:  Vec<String>,
+}
+
+impl LoadingBuffer {
+    /// Проверенные текстуры атласа
+    fn verified_textures(&self, atlas_type: &AtlasType) -> &Vec<String> {
+        match atlas_type {
+            AtlasType::Entity       => &self.verified_entity_texture,
+            AtlasType::Tools        => &self.verified_tool_texture,
+            AtlasType::Weapon       => &self.verified_weapon_texture,
+            AtlasType::Objects      => &self.verified_object_texture,
+            AtlasType::ConnectObj   => &self.verified_object_ct_texture,
+            AtlasType::Particle     => &self.verified_particle_texture,
+            AtlasType::Ui           => &self.verified_ui_texture,
+            _                       => &self.verified_item_texture,
+        }
+    }
 }
 
 impl ResourcePlugin {
@@ -116,9 +149,22 @@ impl ResourcePlugin {
         //     asset_server.load_folder("core/textures/entity/player"),
         // ));
         
-        commands.insert_resource(ResourceModule(asset_server.load_folder("Data://Core/Textures")));
-
-        commands.insert_resource(LoadingBuffer::default());
+        // Порядок загрузки определяется до загрузки текстур, чтобы текстуры поздних модулей перекрывали ранние
+        let (modules, mut diagnostics) = Module::discover_modules(Path::new(Module::DATA_DIR));
+        let (modules, order_errors) = Module::resolve_load_order(modules);
+        diagnostics.extend(order_errors);
+
+        let folders = modules.iter()
+            .filter(|module| module.path.join("Textures").is_dir())
+            .map(|module| asset_server.load_folder(format!("Data://{}/Textures", module.dir)))
+            .collect();
+        commands.insert_resource(ResourceModule(folders));
+
+        commands.insert_resource(LoadingBuffer {
+            modules,
+            diagnostics,
+            ..default()
+        });
 
         commands.insert_resource(graphic::Atlas::AtlasRes::init());
 
@@ -160,7 +206,8 @@ impl ResourcePlugin {
     fn loading_module(
         // mut commands:       Commands,
         mut register:       ResMut<Registry::Registry>,
-        mut load_buff:      ResMut<LoadingBuffer>
+        mut load_buff:      ResMut<LoadingBuffer>,
+        mut diagnostics:    ResMut<Diagnostic::LoadDiagnostics>
     ) {
         /*
             прогон по папкам и рекурсивно внутри папок.
@@ -169,24 +216,70 @@ impl ResourcePlugin {
         
         // let textere_path: Vec<String> = Vec::new();
 
-        if let Ok(entries) = fs::read_dir("Data") {
-            println!("Reading Data...");
+        Self::load_modules(&mut register, &mut load_buff);
 
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
+        diagnostics.entries = std::mem::take(&mut load_buff.diagnostics);
+        diagnostics.strict = Settings::load().strict_loading;
+        diagnostics.log();
 
-                    if path.is_dir() && path.file_name() == Some("Core".as_ref()) {
-                        println!("Reading Core...");
-                        if let Err(err) = Self::process_loading(&mut register, &mut load_buff, &path) {
-                            panic!("Ошибка при обработке директории Core: {}", err)
-                        }
-                    }
-                }
+        // commands.remove_resource::<LoadingBuffer>();
+    }
+
+    /// Регистрация определений модулей и их проверка, без загрузки изображений и ECS
+    fn load_modules(
+        register:   &mut Registry::Registry,
+        load_buff:  &mut LoadingBuffer
+    ) {
+        // Определения модулей регистрируются в порядке загрузки, поздние перекрывают ранние
+        for module in load_buff.modules.clone() {
+            println!("Reading {} {}...", module.info.id, module.info.version);
+            load_buff.source_id = module.info.id.clone();
+            register.register_module(module.info.clone());
+
+            if let Err(err) = Self::process_loading(register, load_buff, &module.path) {
+                load_buff.diagnostics.push(
+                    Diagnostic::Diagnostic::error(format!("module {} is loaded partially: {}", module.info.id, err)).in_file(Some(&module.path))
+                );
             }
         }
+        Self::verify_textures(load_buff);
+        Self::check_missing_textures(register, load_buff);
+
+        // Рецепты проверяются после регистрации всех предметов и объектов
+        let errors = register.validate_recipes().into_iter()
+            .chain(register.validate_plants())
+            .chain(register.validate_weather())
+            .chain(register.validate_skills());
+        for error in errors {
+            let file = load_buff.def_files.get(&(error.kind, error.id_name.clone())).map(|(_, file)| file.clone());
+            load_buff.diagnostics.push(
+                Diagnostic::Diagnostic::from(error.error)
+                    .in_file(file.as_deref())
+                    .with_field(error.field)
+            );
+        }
+    }
 
-        // commands.remove_resource::<LoadingBuffer>();
+    /// Проверка модулей вне игры: реестр определений и сообщения загрузки
+    pub fn check_modules(modules: Vec<Module::ModuleEntry>) -> (Registry::Registry, Vec<Diagnostic::Diagnostic>) {
+        let mut register = Registry::Registry::new();
+        let mut load_buff = LoadingBuffer {
+            modules,
+            ..default()
+        };
+
+        Self::load_modules(&mut register, &mut load_buff);
+        (register, load_buff.diagnostics)
+    }
+
+    /// Ошибки загрузки показываются отдельным экраном вместо главного меню
+    fn report_diagnostics(
+        mut next_state:     ResMut<NextState<AppState>>,
+            diagnostics:    Res<Diagnostic::LoadDiagnostics>
+    ) {
+        if diagnostics.has_errors() {
+            next_state.set(AppState::LoadingError);
+        }
     }
 
     fn process_loading(
@@ -194,20 +287,6 @@ impl ResourcePlugin {
         mut load_buff:  &mut LoadingBuffer,
             path:       &Path,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        // Регистрация модуля
-        let modul_path = path.join("mod.json");
-        if modul_path.exists() {
-            if let Ok(contents) = fs::read_to_string(&modul_path) {
-                if let Ok(module) = serde_json::from_str::<Registry::ModuleRegistry>(&contents) {
-                    load_buff.source_id = module.id.clone();
-                    register.register_module(module);
-                    println!("Модуль зарегистрирован!");
-                }
-            }
-        } else {
-            return Err("Нет файла регистрации модуля!".into());
-        }
-
         for entry in fs::read_dir(path)? {
             if let Ok(entry) = entry {
                 let path = entry.path();
@@ -239,6 +318,11 @@ impl ResourcePlugin {
                                 Self::process_directory_assets(&mut register, &mut load_buff.textures_path_buf, &assets_path)?;
                             }
 
+                            let assets_path = path.join("particles");
+                            if assets_path.exists() {
+                                Self::process_directory_assets(&mut register, &mut load_buff.textures_path_buf, &assets_path)?;
+                            }
+
                             let assets_path = path.join("gui");
                             if assets_path.exists() {
                                 Self::process_directory_assets(&mut register, &mut load_buff.reg_ui_tex_path, &assets_path)?;
@@ -269,6 +353,42 @@ impl ResourcePlugin {
                             if res_path.exists() {
                                 Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
                             }
+
+                            let res_path = path.join("progression");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            let res_path = path.join("skill_tree");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            let res_path = path.join("skills");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            let res_path = path.join("recipes");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            let res_path = path.join("plants");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            let res_path = path.join("weather");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
+
+                            // Патчи применяются после всех определений модуля
+                            let res_path = path.join("patches");
+                            if res_path.exists() {
+                                Self::process_directory_res(&mut register, &mut load_buff, &res_path)?;
+                            }
                         }
                         _ => continue,
                     }
@@ -276,6 +396,11 @@ impl ResourcePlugin {
             }
         }
 
+        Ok(())
+    }
+
+    /// Выбор текстур, запрошенных определениями всех модулей
+    fn verify_textures(load_buff: &mut LoadingBuffer) {
         load_buff.verified_item_texture         = Self::process_assets(&load_buff.reg_item_tex_path, &load_buff.textures_path_buf);
         load_buff.verified_tool_texture         = Self::process_assets(&load_buff.reg_tool_tex_path, &load_buff.textures_path_buf);
         load_buff.verified_weapon_texture       = Self::process_assets(&load_buff.reg_weapon_tex_path, &load_buff.textures_path_buf);
@@ -285,9 +410,116 @@ impl ResourcePlugin {
         load_buff.verified_object_texture       = Self::process_assets(&load_buff.reg_object_tex_path, &load_buff.textures_path_buf);
         load_buff.verified_object_ct_texture    = Self::process_assets(&load_buff.reg_object_ct_tex_path, &load_buff.textures_path_buf);
 
+        load_buff.verified_particle_texture     = Self::process_assets(&load_buff.reg_particle_tex_path, &load_buff.textures_path_buf);
+
         load_buff.verified_ui_texture           = Self::process_ui_assets(&load_buff.reg_ui_tex_path);
-        
-        Ok(())
+    }
+
+    /// Текстуры, которых нет ни в одном модуле, отмечаются в файле запросившего их определения
+    fn check_missing_textures(
+        register:   &Registry::Registry,
+        load_buff:  &mut LoadingBuffer
+    ) {
+        let stems: HashSet<String> = load_buff.textures_path_buf.iter()
+            .filter_map(|path| Path::new(path).file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .collect();
+
+        for kind in Registry::DefKind::ALL {
+            for id_name in register.def_ids(kind) {
+                for (_, field, texture) in register.def_textures(kind, &id_name) {
+                    let stem = Path::new(&texture).file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+                    if stems.contains(&stem) {
+                        continue;
+                    }
+
+                    let file = load_buff.def_files.get(&(kind, id_name.clone())).map(|(_, file)| file.clone());
+                    load_buff.diagnostics.push(
+                        Diagnostic::Diagnostic::error(format!("{} {} requests missing texture {}", kind.label(), id_name, texture))
+                            .in_file(file.as_deref())
+                            .with_field(Some(field))
+                    );
+                }
+            }
+        }
+    }
+
+    /// Чтение и разбор файла определения, повторный id внутри одного модуля отмечается предупреждением
+    fn parse_def<T: serde::Serialize + serde::de::DeserializeOwned>(
+        load_buff:  &mut LoadingBuffer,
+        path:       &Path,
+        kind:       Option<Registry::DefKind>
+    ) -> Option<T> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                load_buff.diagnostics.push(Diagnostic::Diagnostic::error(err.to_string()).in_file(Some(path)));
+                return None;
+            }
+        };
+        let def = Diagnostic::parse_json::<T>(path, &contents, &mut load_buff.diagnostics)?;
+
+        let id_name = serde_json::from_str::<serde_json::Value>(&contents).ok()
+            .and_then(|value| value.get("id_name").and_then(|id| id.as_str()).map(str::to_string));
+        if let (Some(kind), Some(id_name)) = (kind, id_name) {
+            let key = (kind, id_name);
+            if let Some((module, file)) = load_buff.def_files.get(&key) {
+                if *module == load_buff.source_id {
+                    let mut diagnostic = Diagnostic::Diagnostic::warning(
+                        format!("duplicate {} id {} (also in {}), the later file is used", kind.label(), key.1, file.display())
+                    ).in_file(Some(path)).with_field(Some("id_name"));
+                    if let Some((line, column)) = Diagnostic::key_position(&contents, "id_name") {
+                        diagnostic = diagnostic.at(line, column);
+                    }
+                    load_buff.diagnostics.push(diagnostic);
+                }
+            }
+            load_buff.def_files.insert(key, (load_buff.source_id.clone(), path.to_path_buf()));
+        }
+
+        Some(def)
+    }
+
+    /// Списки текстур определений собираются заново по реестру, перечитанные определения не оставляют в них старых текстур
+    fn collect_def_textures(
+        register:   &Registry::Registry,
+        load_buff:  &mut LoadingBuffer
+    ) {
+        load_buff.reg_item_tex_path.clear();
+        load_buff.reg_tool_tex_path.clear();
+        load_buff.reg_weapon_tex_path.clear();
+        load_buff.reg_entity_tex_path.clear();
+        load_buff.reg_object_tex_path.clear();
+        load_buff.reg_object_ct_tex_path.clear();
+        load_buff.reg_particle_tex_path.clear();
+
+        for kind in Registry::DefKind::ALL {
+            for id_name in register.def_ids(kind) {
+                Self::push_def_textures(register, load_buff, kind, &id_name);
+            }
+        }
+    }
+
+    /// Текстуры определения, изменённого патчем, тоже попадают в загрузку
+    fn push_def_textures(
+        register:   &Registry::Registry,
+        load_buff:  &mut LoadingBuffer,
+        kind:       Registry::DefKind,
+        id_name:    &str
+    ) {
+        for (atlas, _, texture) in register.def_textures(kind, id_name) {
+            let paths = match atlas {
+                AtlasType::Entity       => &mut load_buff.reg_entity_tex_path,
+                AtlasType::Weapon       => &mut load_buff.reg_weapon_tex_path,
+                AtlasType::Tools        => &mut load_buff.reg_tool_tex_path,
+                AtlasType::Objects      => &mut load_buff.reg_object_tex_path,
+                AtlasType::ConnectObj   => &mut load_buff.reg_object_ct_tex_path,
+                AtlasType::Particle     => &mut load_buff.reg_particle_tex_path,
+                _                       => &mut load_buff.reg_item_tex_path,
+            };
+            if !paths.contains(&texture) {
+                paths.push(texture);
+            }
+        }
     }
 
     /// Проверка соответствия текстур с запрашиваемыми текстурами объектами регистра
@@ -377,110 +609,203 @@ impl ResourcePlugin {
             let path = entry.path();
     
             if path.is_file() && path.extension().map_or(false, |ext| ext == "json") {
-                // Обработка json файлов определяющих сущности
-                if dir.file_name().map_or(false, |name| name == "entities") {
-                    if let Ok(contents) = fs::read_to_string(&path) {
-                        if let Ok(module) = serde_json::from_str::<Registry::EntityRegistry>(&contents) {
-
-                            load_buff.reg_entity_tex_path.push(module.id_texture_b.clone());
-                            if !module.id_texture_h.is_none() {
-                                if let Some(texture_h) = module.id_texture_h.clone() {
-                                    load_buff.reg_entity_tex_path.push(texture_h);
-                                }
-                            }
+                Self::process_def_file(register, load_buff, &path);
+            } else if path.is_dir() {
+                Self::process_directory_res(&mut register, &mut load_buff, &path)?;
+            }
+        }
+    
+        Ok(())
+    }
 
-                            register.register_entity(Registry::EntityRegistry {
-                                id_name:        module.id_name,
-                                id_source:      Some(load_buff.source_id.clone()),
-                                id_texture_b:   module.id_texture_b,
-                                id_texture_h:   module.id_texture_h,
-                                entity_type:    module.entity_type,
-                                health:         module.health
-                            });
-                        }
+    /// Разбор одного файла определения, вид определения задаётся папкой файла
+    fn process_def_file(
+        register:   &mut Registry::Registry,
+        load_buff:  &mut LoadingBuffer,
+        path:       &Path
+    ) {
+        let dir = path.parent().and_then(|dir| dir.file_name());
+
+        // Обработка json файлов определяющих сущности
+        if dir.map_or(false, |name| name == "entities") {
+            if let Some(module) = Self::parse_def::<Registry::EntityRegistry>(load_buff, path, Some(Registry::DefKind::Entity)) {
+
+                load_buff.reg_entity_tex_path.push(module.id_texture_b.clone());
+                if !module.id_texture_h.is_none() {
+                    if let Some(texture_h) = module.id_texture_h.clone() {
+                        load_buff.reg_entity_tex_path.push(texture_h);
                     }
                 }
-                // Обработка json файлов определяющие предметы
-                if dir.file_name().map_or(false, |name| name == "items") {
-                    if let Ok(contents) = fs::read_to_string(&path) {
-                        if let Ok(module) = serde_json::from_str::<Registry::ItemRegistry>(&contents) {
-
-                            match module.item_type {
-                                ItemType::Item(_) => {
-                                    load_buff.reg_item_tex_path.push(module.id_texture.clone());
-                                },
-                                ItemType::Weapon(_) => {
-                                    load_buff.reg_weapon_tex_path.push(module.id_texture.clone());
-                                },
-                                ItemType::Tool(_) => {
-                                    load_buff.reg_tool_tex_path.push(module.id_texture.clone());
-                                },
-                                ItemType::None => {
-                                    load_buff.reg_item_tex_path.push(module.id_texture.clone());
-                                },
-                            }
 
-                            register.register_item(Registry::ItemRegistry {
-                                id_name:    module.id_name,
-                                id_source:  Some(load_buff.source_id.clone()),
-                                id_texture: module.id_texture,
-                                item_type:  module.item_type,
-                                range_info: module.range_info,
-                                item_size:  module.item_size,
-                                stackable:  module.stackable,
-                                stack_size: module.stack_size,
-                                durability: module.durability
-                            });
-                        }
-                    }
-                    
+                register.register_entity(Registry::EntityRegistry {
+                    id_name:        module.id_name,
+                    id_source:      Some(load_buff.source_id.clone()),
+                    id_texture_b:   module.id_texture_b,
+                    id_texture_h:   module.id_texture_h,
+                    entity_type:    module.entity_type,
+                    health:         module.health,
+                    xp_reward:      module.xp_reward,
+                    skills:         module.skills,
+                    pawn:           module.pawn
+                });
+            }
+        }
+        // Обработка json файлов определяющие предметы
+        if dir.map_or(false, |name| name == "items") {
+            if let Some(module) = Self::parse_def::<Registry::ItemRegistry>(load_buff, path, Some(Registry::DefKind::Item)) {
+
+                match module.item_type {
+                    ItemType::Item(_) => {
+                        load_buff.reg_item_tex_path.push(module.id_texture.clone());
+                    },
+                    ItemType::Weapon(_) => {
+                        load_buff.reg_weapon_tex_path.push(module.id_texture.clone());
+                    },
+                    ItemType::Tool(_) => {
+                        load_buff.reg_tool_tex_path.push(module.id_texture.clone());
+                    },
+                    ItemType::None => {
+                        load_buff.reg_item_tex_path.push(module.id_texture.clone());
+                    },
                 }
 
-                // Обработка json файлов определяющие объекты
-                if dir.file_name().map_or(false, |name| name == "objects") {
-                    if let Ok(contents) = fs::read_to_string(&path) {
-                        if let Ok(module) = serde_json::from_str::<Registry::ObjectRegistry>(&contents) {
-
-                            load_buff.reg_object_tex_path.push(module.id_texture.clone());
-
-                            register.register_object(Registry::ObjectRegistry {
-                                id_name:        module.id_name,
-                                id_source:      Some(load_buff.source_id.clone()),
-                                id_texture:     module.id_texture,
-                                health:         module.health,
-                                size:           module.size,
-                                collision:      module.collision,
-                                durability:     module.durability
-                            });
-                        }
-                    }
-                    
+                register.register_item(Registry::ItemRegistry {
+                    id_name:    module.id_name,
+                    id_source:  Some(load_buff.source_id.clone()),
+                    id_texture: module.id_texture,
+                    item_type:  module.item_type,
+                    range_info: module.range_info,
+                    item_size:  module.item_size,
+                    stackable:  module.stackable,
+                    stack_size: module.stack_size,
+                    durability: module.durability,
+                    mass:       module.mass,
+                    volume:     module.volume,
+                    equip:      module.equip,
+                    container:  module.container,
+                    light:      module.light
+                });
+            }
+            
+        }
+
+        // Обработка json файлов определяющие объекты
+        if dir.map_or(false, |name| name == "objects") {
+            if let Some(module) = Self::parse_def::<Registry::ObjectRegistry>(load_buff, path, Some(Registry::DefKind::Object)) {
+
+                load_buff.reg_object_tex_path.push(module.id_texture.clone());
+                if let Some(texture_open) = module.door.as_ref().and_then(|door| door.id_texture_open.clone()) {
+                    load_buff.reg_object_tex_path.push(texture_open);
                 }
 
-                // Обработка json файлов определяющие объекты с соединяющимися текстурами
-                if dir.file_name().map_or(false, |name| name == "objects_ct") {
-                    if let Ok(contents) = fs::read_to_string(&path) {
-                        if let Ok(module) = serde_json::from_str::<Registry::PersistentObjectRegistry>(&contents) {
-
-                            load_buff.reg_object_ct_tex_path.push(module.id_texture.clone());
-
-                            register.register_object_ct(Registry::PersistentObjectRegistry {
-                                id_name:        module.id_name,
-                                id_source:      Some(load_buff.source_id.clone()),
-                                id_texture:     module.id_texture,
-                                size:           module.size,
-                                collision:      module.collision,
-                                durability:     module.durability
-                            });
-                        }
-                    }
-                    
+                register.register_object(Registry::ObjectRegistry {
+                    id_name:        module.id_name,
+                    id_source:      Some(load_buff.source_id.clone()),
+                    id_texture:     module.id_texture,
+                    health:         module.health,
+                    size:           module.size,
+                    collision:      module.collision,
+                    durability:     module.durability,
+                    container:      module.container,
+                    interactions:   module.interactions,
+                    door:           module.door,
+                    opaque:         module.opaque,
+                    light:          module.light,
+                    beauty:         module.beauty
+                });
+            }
+            
+        }
+
+        // Обработка json файлов определяющие объекты с соединяющимися текстурами
+        if dir.map_or(false, |name| name == "objects_ct") {
+            if let Some(module) = Self::parse_def::<Registry::PersistentObjectRegistry>(load_buff, path, Some(Registry::DefKind::ObjectCt)) {
+
+                load_buff.reg_object_ct_tex_path.push(module.id_texture.clone());
+
+                register.register_object_ct(Registry::PersistentObjectRegistry {
+                    id_name:        module.id_name,
+                    id_source:      Some(load_buff.source_id.clone()),
+                    id_texture:     module.id_texture,
+                    size:           module.size,
+                    collision:      module.collision,
+                    durability:     module.durability
+                });
+            }
+            
+        }
+
+        // Обработка json файлов определяющие прогрессию персонажа
+        if dir.map_or(false, |name| name == "progression") {
+            if let Some(mut module) = Self::parse_def::<Registry::ProgressionRegistry>(load_buff, path, Some(Registry::DefKind::Progression)) {
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_progression(module);
+            }
+        }
+
+        // Обработка json файлов определяющие узлы дерева навыков
+        if dir.map_or(false, |name| name == "skill_tree") {
+            if let Some(mut module) = Self::parse_def::<Registry::SkillNodeRegistry>(load_buff, path, Some(Registry::DefKind::SkillNode)) {
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_skill_node(module);
+            }
+        }
+
+        // Обработка json файлов определяющие активные навыки
+        if dir.map_or(false, |name| name == "skills") {
+            if let Some(mut module) = Self::parse_def::<Registry::SkillRegistry>(load_buff, path, Some(Registry::DefKind::Skill)) {
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_skill(module);
+            }
+        }
+
+        // Обработка json файлов определяющие рецепты
+        if dir.map_or(false, |name| name == "recipes") {
+            if let Some(mut module) = Self::parse_def::<Registry::RecipeRegistry>(load_buff, path, Some(Registry::DefKind::Recipe)) {
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_recipe(module);
+            }
+        }
+
+        // Обработка json файлов определяющие растения, текстуры стадий попадают в атлас объектов
+        if dir.map_or(false, |name| name == "plants") {
+            if let Some(mut module) = Self::parse_def::<Registry::PlantRegistry>(load_buff, path, Some(Registry::DefKind::Plant)) {
+                for stage in &module.stages {
+                    load_buff.reg_object_tex_path.push(stage.id_texture.clone());
+                }
+
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_plant(module);
+            }
+        }
+
+        // Обработка json файлов определяющие погоду, текстуры частиц попадают в атлас частиц
+        if dir.map_or(false, |name| name == "weather") {
+            if let Some(mut module) = Self::parse_def::<Registry::WeatherRegistry>(load_buff, path, Some(Registry::DefKind::Weather)) {
+                if let Some(particle) = &module.particle {
+                    load_buff.reg_particle_tex_path.push(particle.id_texture.clone());
+                }
+
+                module.id_source = Some(load_buff.source_id.clone());
+                register.register_weather(module);
+            }
+        }
+
+        // Обработка json файлов определяющие патчи определений уже загруженных модулей
+        if dir.map_or(false, |name| name == "patches") {
+            if let Some(mut module) = Self::parse_def::<Registry::PatchRegistry>(load_buff, path, None) {
+                module.id_source = Some(load_buff.source_id.clone());
+
+                // Патч неизвестного определения указывает на его `id_name`, ошибка операций - на `operations`
+                let field = match register.def_ids(module.target).contains(&module.id_name) {
+                    true  => "operations",
+                    false => "id_name",
+                };
+                match register.apply_patch(&module) {
+                    Ok(())      => Self::push_def_textures(register, load_buff, module.target, &module.id_name),
+                    Err(error)  => load_buff.diagnostics.push(Diagnostic::Diagnostic::from(error).in_file(Some(path)).with_field(Some(field))),
                 }
-            } else if path.is_dir() {
-                Self::process_directory_res(&mut register, &mut load_buff, &path)?;
             }
         }
-    
-        Ok(())
     }
 }
@@ -15,6 +15,8 @@ use std::{
 
 use crate::core::{
     world::chunk::Chunk::ChunkX as Chunk,
+    EntityType::EntityNeutrality,
+    Object::Footprint,
     resource::graphic::Atlas::{
         AtlasRes,
         AtlasType,
@@ -24,6 +26,8 @@ use crate::core::{
 use futures_lite::future;
 
 pub const CHUNK_SIZE: i32 = 256;
+/// Стоимость шага через закрытую дверь при поиске пути
+pub const DOOR_PATH_COST: usize = 3;
 
 //
 //
@@ -35,6 +39,27 @@ pub struct Grid {
     pub render_distance:    i32,
     pub debug_mode:         bool,
     pub debug_chunks:       HashMap<IVec2, Entity>,
+    pub doors:              HashMap<IVec2, DoorPassage>,    // Двери по координатам клеток
+    pub roofs:              HashSet<IVec2>,                 // Клетки под крышей
+}
+
+/// Проходимость двери для поиска пути
+#[derive(Clone, Copy, Debug)]
+pub struct DoorPassage {
+    pub entity:     Entity,
+    pub open:       bool,
+    pub locked:     bool,
+    pub faction:    Option<EntityNeutrality>,   // Фракция, для которой запертая дверь проходима
+}
+
+impl DoorPassage {
+    pub fn passable_for(&self, faction: Option<EntityNeutrality>) -> bool {
+        !self.locked || (self.faction.is_some() && self.faction == faction)
+    }
+
+    pub fn path_cost(&self) -> usize {
+        if self.open { 1 } else { DOOR_PATH_COST }
+    }
 }
 
 impl Grid {
@@ -43,7 +68,9 @@ impl Grid {
             chunks:         HashMap::new(),
             render_distance,
             debug_mode:     !false,
-            debug_chunks:   HashMap::new()
+            debug_chunks:   HashMap::new(),
+            doors:          HashMap::new(),
+            roofs:          HashSet::new()
         }
     }
 
@@ -158,6 +185,35 @@ impl Grid {
         }
     }
 
+    /// Проверка, что все клетки объекта свободны и находятся в загруженных чанках
+    pub fn can_place(&self, footprint: &Footprint) -> bool {
+        footprint.tiles().all(|tile| {
+            let coord = tile * 16;
+            self.chunks.contains_key(&get_format_current_chunk(coord)) && !self.check_exist_object(&coord)
+        })
+    }
+
+    /// Размещение объекта на всех его клетках (всё или ничего)
+    pub fn place_object(&mut self, entity: Entity, footprint: &Footprint) -> bool {
+        if !self.can_place(footprint) {
+            return false;
+        }
+
+        for tile in footprint.tiles() {
+            self.add_object_to_chunk(entity, tile * 16);
+        }
+        true
+    }
+
+    /// Удаление объекта со всех его клеток
+    pub fn remove_object(&mut self, entity: Entity, footprint: &Footprint) {
+        for tile in footprint.tiles() {
+            if let Some(chunk) = self.chunks.get_mut(&get_format_current_chunk(tile * 16)) {
+                chunk.remove_object(entity);
+            }
+        }
+    }
+
     /// По идеи проверка есть на том положении объект
     pub fn is_object_present(&self, coord: IVec2) -> bool {
         if let Some(chunk) = self.chunks.get(&coord) {
@@ -176,6 +232,34 @@ impl Grid {
         }
     } 
 
+    /// Объект по данным глобальным координатам
+    pub fn object_at(&self, coord: &IVec2) -> Option<Entity> {
+        self.chunks.get(&get_format_current_chunk(*coord))?.get_object(*coord)
+    }
+
+    /// Координаты клетки по глобальным координатам
+    pub fn tile_of(coord: IVec2) -> IVec2 {
+        IVec2::new(coord.x.div_euclid(16), coord.y.div_euclid(16))
+    }
+
+    /// Дверь по данным глобальным координатам
+    pub fn door_at(&self, coord: &IVec2) -> Option<&DoorPassage> {
+        self.doors.get(&Self::tile_of(*coord))
+    }
+
+    /// Находится ли клетка под крышей
+    pub fn is_roofed(&self, tile: IVec2) -> bool {
+        self.roofs.contains(&tile)
+    }
+
+    /// Стоимость шага по данным глобальным координатам, `None` - клетка непроходима
+    pub fn step_cost(&self, coord: &IVec2, faction: Option<EntityNeutrality>) -> Option<usize> {
+        if let Some(door) = self.door_at(coord) {
+            return door.passable_for(faction).then(|| door.path_cost());
+        }
+        (!self.check_exist_object(coord)).then_some(1)
+    }
+
     pub fn check_exist_object_ex(&self, chunk: IVec2, local: IVec2) {
         if let Some(chunk) = self.chunks.get(&chunk) {
 
@@ -209,21 +293,17 @@ impl Grid {
 
 /// Получение координат чанка по вводным данным
 pub fn get_format_current_chunk(input_var: IVec2) -> IVec2 {
-    let mut chunk_x = input_var.x / CHUNK_SIZE;
-    let mut chunk_y = input_var.y / CHUNK_SIZE;
-    if input_var.x < 0 {
-        chunk_x -= 1;
-    }
-    if input_var.y < 0 {
-        chunk_y -= 1;
-    }
-    IVec2::new(chunk_x, chunk_y)
+    // Деление с округлением вниз, чтобы -1 и -256 попадали в чанк -1
+    IVec2::new(
+        input_var.x.div_euclid(CHUNK_SIZE),
+        input_var.y.div_euclid(CHUNK_SIZE)
+    )
 }
 
 pub fn global_to_local(coord: IVec2) -> UVec2 {
     UVec2::new(
-        (coord.x.abs() as u32 % 256) / 16,
-        (coord.y.abs() as u32 % 256) / 16
+        (coord.x.rem_euclid(CHUNK_SIZE) / 16) as u32,
+        (coord.y.rem_euclid(CHUNK_SIZE) / 16) as u32
     )
 }
 
@@ -11,7 +11,10 @@ use crate::core::{
         ai::AiPlugin,
         EntitySystem::EntitySystem,
     },
-    resource::graphic::Atlas::AtlasRes,
+    resource::graphic::{
+        Atlas::AtlasRes,
+        Connect::connect_plugin
+    },
     world::{
         chunk::Chunk::Chunk, 
         // TileMap::{
@@ -19,10 +22,21 @@ use crate::core::{
         //     DischargeChunkPos, 
         //     LoadChunkPos
         // }, 
+        WorldSVD::world_save_plugin,
         WorldTaskManager
     }, 
     AppState, 
     ContainerSystem::ContainerPlugin, 
+    CraftSystem::craft_plugin,
+    BuildSystem::build_plugin,
+    StockpileSystem::stockpile_plugin,
+    PlantSystem::plant_plugin,
+    LightSystem::light_plugin,
+    RoomSystem::room_plugin,
+    WeatherSystem::{
+        Biome,
+        weather_plugin
+    },
     Entity::{
         EntitySpawn,
         spawn_entity
@@ -31,12 +45,27 @@ use crate::core::{
     ItemType::ItemType, 
     Object::{
         ObjectSpawn,
-        spawn_object
+        PersistentObjectSpawn,
+        spawn_object,
+        spawn_persistent_object
+    },
+    Door::door_plugin,
+    TimeCycle::{
+        ClockEvent,
+        Season,
+        time_cycle_plugin,
+        DAWN,
+        DUSK,
+        NIGHT_LIGHT,
+        TWILIGHT
     },
     Settings::Settings, 
     UserSystem::UserControl,
     PlayerSystem::PlayerPlugin,
-    interact::Damage::DamageSystem
+    interact::{
+        Damage::DamageSystem,
+        Interact::InteractSystem
+    }
 };
 
 use super::Grid::Grid;
@@ -62,17 +91,21 @@ impl Plugin for WorldSystem {
                     AiPlugin,
                     PlayerPlugin,   // Инициализация плагина, отвечающего за работу управления entity-player
                     DamageSystem,
+                    InteractSystem,
                     ContainerPlugin::<ItemType> {
                         phantom: PhantomData {}
                     },
                 )
             )
-            .add_plugins(item_plugin)
+            .add_plugins((time_cycle_plugin, light_plugin, weather_plugin, room_plugin))
+            .add_plugins((item_plugin, craft_plugin, build_plugin, stockpile_plugin, plant_plugin, door_plugin, connect_plugin, world_save_plugin))
             // Init Event
             .add_event::<ObjectSpawn>()
+            .add_event::<PersistentObjectSpawn>()
             .add_event::<EntitySpawn>()
             // Init Resource
             .init_resource::<WorldRes>()
+            .init_resource::<WorldInfo>()
             .init_resource::<Chunk>()
             // Init Systems
             .add_systems(
@@ -90,6 +123,7 @@ impl Plugin for WorldSystem {
             .add_systems(FixedUpdate, 
                 (
                     spawn_object,
+                    spawn_persistent_object,
                     spawn_entity
                 ).run_if(in_state(AppState::Game))
             )
@@ -348,15 +382,11 @@ impl WorldSystem {
 
     /// Функция для форматирования значения чанков по координатной системе
     pub fn get_format_current_chunk(input_var: IVec2) -> IVec2 {
-        let mut chunk_x = input_var.x / 256;
-        let mut chunk_y = input_var.y / 256;
-        if input_var.x < 0 {
-            chunk_x -= 1;
-        }
-        if input_var.y < 0 {
-            chunk_y -= 1;
-        }
-        IVec2::new(chunk_x, chunk_y)
+        // Деление с округлением вниз, чтобы -1 и -256 попадали в чанк -1
+        IVec2::new(
+            input_var.x.div_euclid(256),
+            input_var.y.div_euclid(256)
+        )
     }
 
     /// Функция для определения точных координат тайла в чанке
@@ -435,6 +465,7 @@ impl Default for WorldRes {
 //
 //
 
+/// Часы мира, единый источник игрового времени
 #[allow(unused)]
 #[derive(Resource)]
 pub struct WorldInfo {
@@ -442,13 +473,21 @@ pub struct WorldInfo {
 
     pub name:           String,
 
+    pub biome:          Biome,
+
+    // Доля прошедших суток (0 - 1)
     pub daytime:        f32,
 
     // seconds a day time long
     pub daytime_length: f32,
 
     // seconds
-    pub time_inhabited: f32,
+    pub time_inhabited: f64,
+
+    // Номер текущих суток с начала мира
+    pub day:            u32,
+    // Суток в одном времени года
+    pub season_length:  u32,
 
         time_created:   u64,
         time_modified:  u64,
@@ -458,6 +497,11 @@ pub struct WorldInfo {
     pub is_paused:      bool,
     pub paused_steps:   i32,
     // pub is_manipulating: bool, 
+
+    // Множитель скорости игрового времени
+    pub speed:          f32,
+    // Игровые секунды, прошедшие за текущий кадр
+        delta:          f32,
 }
 
 impl Default for WorldInfo {
@@ -465,10 +509,13 @@ impl Default for WorldInfo {
         WorldInfo {
             seed: 0,
             name: "None Name".into(),
-            daytime: 0.15,
+            biome: Biome::default(),
+            daytime: 0.3,
             daytime_length: 60. * 24.,
 
             time_inhabited: 0.,
+            day: 0,
+            season_length: 7,
             time_created: 0,
             time_modified: 0,
 
@@ -477,6 +524,89 @@ impl Default for WorldInfo {
             is_paused: false,
             paused_steps: 0,
             // is_manipulating: true,
+
+            speed: 1.,
+            delta: 0.,
         }
     }
+}
+
+impl WorldInfo {
+    /// Игровые секунды за текущий кадр, 0 на паузе
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Игровые часы за текущий кадр
+    pub fn delta_hours(&self) -> f32 {
+        self.delta * 24. / self.daytime_length.max(1.)
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.daytime * 24.) as u32 % 24
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.daytime * 24. * 60.) as u32 % 60
+    }
+
+    pub fn season(&self) -> Season {
+        Season::ALL[(self.day / self.season_length.max(1)) as usize % Season::ALL.len()]
+    }
+
+    pub fn day_of_season(&self) -> u32 {
+        self.day % self.season_length.max(1)
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.daytime < DAWN || self.daytime >= DUSK
+    }
+
+    /// Освещённость солнцем (NIGHT_LIGHT - 1), рассвет и закат плавные
+    pub fn daylight(&self) -> f32 {
+        let sunrise = ((self.daytime - DAWN) / TWILIGHT + 0.5).clamp(0., 1.);
+        let sunset = ((DUSK - self.daytime) / TWILIGHT + 0.5).clamp(0., 1.);
+
+        NIGHT_LIGHT + (1. - NIGHT_LIGHT) * sunrise.min(sunset)
+    }
+
+    /// Средняя освещённость за сутки
+    pub fn average_daylight() -> f32 {
+        NIGHT_LIGHT + (1. - NIGHT_LIGHT) * (DUSK - DAWN)
+    }
+
+    /// Продвижение часов на реальное время кадра, возвращает пройденные рассветы, закаты и смены суток
+    pub fn advance(&mut self, real_delta: f32) -> Vec<ClockEvent> {
+        let mut events = Vec::new();
+
+        self.delta = if self.is_paused { 0. } else { real_delta * self.speed };
+        self.time_inhabited += self.delta as f64;
+
+        let mut remaining = self.delta / self.daytime_length.max(1.);
+        while remaining > 0. {
+            let boundary = [DAWN, DUSK, 1.].into_iter()
+                .find(|boundary| *boundary > self.daytime)
+                .unwrap_or(1.);
+            let step = boundary - self.daytime;
+
+            if remaining < step {
+                self.daytime += remaining;
+                break;
+            }
+
+            remaining -= step;
+            self.daytime = boundary;
+            if boundary == DAWN {
+                events.push(ClockEvent::Dawn);
+            } else if boundary == DUSK {
+                events.push(ClockEvent::Dusk);
+            } else {
+                self.daytime = 0.;
+                self.day += 1;
+                events.push(ClockEvent::NewDay);
+            }
+        }
+
+        events
+    }
 }
\ No newline at end of file
@@ -0,0 +1,522 @@
+use std::fs;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::core::{
+    resource::{
+        graphic::Atlas::AtlasRes,
+        Registry::Registry
+    },
+    Craft::CraftIngredient,
+    entities::ai::Job::{
+        JobBoard,
+        JobKind,
+        JobPriority
+    },
+    BuildSystem::{
+        Blueprint,
+        spawn_blueprint
+    },
+    StockpileSystem::{
+        Stockpile,
+        StockpileFilter,
+        spawn_stockpile
+    },
+    PlantSystem::Plants,
+    WeatherSystem::{
+        Biome,
+        Weather
+    },
+    ObjType::{
+        PlantObject,
+        PlantState
+    },
+    ContainerSystem::{
+        Inventory,
+        Equipment,
+        drop_subtile
+    },
+    entities::ai::{
+        Pawn,
+        Job::HaulCargo
+    },
+    CraftSystem::{
+        CraftQueue,
+        CraftTask
+    },
+    Item::{
+        ItemEntity,
+        ItemInstance,
+        ItemSpawn
+    },
+    Object::{
+        EntityObject,
+        Footprint
+    },
+    ObjectAnimation::ObjectDirectionState,
+    UserSystem::UserControl,
+    world::{
+        World::{
+            WorldSystem,
+            WorldInfo
+        },
+        Grid::Grid,
+        chunk::Chunk::Chunk
+    },
+    AppState
+};
+
+//
+//
+//
+
+pub fn world_save_plugin(app: &mut App) {
+    app
+        // Init Events
+        .add_event::<SaveWorld>()
+        .add_event::<LoadWorld>()
+        // Init Systems
+        .add_systems(Update,
+            (
+                quick_save,
+                save_world,
+                load_world
+            ).chain().run_if(in_state(AppState::Game))
+        );
+}
+
+//
+//
+//
+
+/// Папка с сохранениями
+pub const SAVE_DIR: &str = "saves";
+/// Имя быстрого сохранения
+pub const QUICK_SAVE: &str = "quicksave";
+/// Версия формата сохранения
+pub const SAVE_VERSION: u32 = 5;
+
+/// Ивент сохранения мира (0 - Имя сохранения)
+#[derive(Event)]
+pub struct SaveWorld(pub String);
+
+/// Ивент загрузки мира (0 - Имя сохранения)
+#[derive(Event)]
+pub struct LoadWorld(pub String);
+
+/// Файл сохранения
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct WorldSave {
+    pub version:    u32,
+    pub items:      Vec<SavedItem>,
+    pub player:     Option<SavedPlayer>,
+    pub storages:   Vec<SavedStorage>,
+    pub blueprints: Vec<SavedBlueprint>,
+    pub jobs:       Vec<SavedJob>,
+    pub stockpiles: Vec<SavedStockpile>,
+    pub plants:     Vec<SavedPlant>,
+    pub clock:      Option<SavedClock>,
+    pub weather:    Option<SavedWeather>,
+}
+
+/// Часы мира
+#[derive(Serialize, Deserialize)]
+pub struct SavedClock {
+    pub daytime:        f32,
+    pub day:            u32,
+    pub time_inhabited: f64,
+    pub speed:          f32,
+    #[serde(default)]
+    pub biome:          Biome,
+}
+
+/// Погода, смена которой сохраняется уже завершённой
+#[derive(Serialize, Deserialize)]
+pub struct SavedWeather {
+    pub id_name:    String,
+    pub remaining:  f32,
+}
+
+/// Предмет, лежащий на земле
+#[derive(Serialize, Deserialize)]
+pub struct SavedItem {
+    pub id_name:    String,
+    pub subtile:    (i32, i32),
+    pub count:      usize,
+    pub instance:   ItemInstance,
+}
+
+/// Инвентарь, экипировка и очередь крафтов игрока (ингредиенты крафтов уже изъяты из инвентаря)
+#[derive(Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub inventory:  Inventory,
+    pub equipment:  Equipment,
+    #[serde(default)]
+    pub crafts:     Vec<CraftTask>,
+}
+
+/// Содержимое хранилища, привязанное к положению объекта
+#[derive(Serialize, Deserialize)]
+pub struct SavedStorage {
+    pub position:   (i32, i32),
+    pub inventory:  Inventory,
+}
+
+/// Чертёж с доставленными материалами и оставшейся работой
+#[derive(Serialize, Deserialize)]
+pub struct SavedBlueprint {
+    pub object:     String,
+    pub anchor:     (i32, i32),
+    pub direction:  ObjectDirectionState,
+    pub materials:  Vec<CraftIngredient>,
+    pub delivered:  Vec<CraftIngredient>,
+    pub work:       f32,
+    pub total:      f32,
+    pub xp:         u32,
+}
+
+/// Работа на доске, цель находится по клетке (рабочий не сохраняется, работа берётся заново)
+#[derive(Serialize, Deserialize)]
+pub struct SavedJob {
+    pub kind:       JobKind,
+    pub tile:       (i32, i32),
+    pub priority:   JobPriority,
+    pub recipe:     Option<String>,
+    pub progress:   f32,
+    pub started:    bool,
+}
+
+/// Зона склада с фильтром и приоритетом
+#[derive(Serialize, Deserialize)]
+pub struct SavedStockpile {
+    pub anchor:     (i32, i32),
+    pub size:       (i32, i32),
+    pub filter:     StockpileFilter,
+    pub priority:   JobPriority,
+}
+
+/// Растение со стадией роста, `pending` - время мира, рост за которое ещё не посчитан
+#[derive(Serialize, Deserialize)]
+pub struct SavedPlant {
+    pub id_name:    String,
+    pub tile:       (i32, i32),
+    pub stage:      usize,
+    pub progress:   f32,
+    pub pending:    f64,
+    pub designated: bool,
+}
+
+/// Растения мира и их сущности в загруженных чанках
+#[derive(SystemParam)]
+pub struct PlantSave<'w, 's> {
+    pub plants:     ResMut<'w, Plants>,
+    pub objects:    Query<'w, 's, (Entity, &'static Footprint), With<PlantObject>>,
+}
+
+/// Инвентари хранилищ и рабочих (груз рабочих сохраняется предметами на земле)
+#[derive(SystemParam)]
+pub struct InventorySave<'w, 's> {
+    pub storages:   Query<'w, 's, (&'static mut Inventory, &'static Transform), (With<EntityObject>, Without<UserControl>)>,
+    pub pawns:      Query<'w, 's, (Entity, &'static mut Inventory), (With<Pawn>, Without<EntityObject>, Without<UserControl>)>,
+}
+
+fn save_path(name: &str) -> String {
+    format!("{}/{}.json", SAVE_DIR, name)
+}
+
+fn position_key(transform: &Transform) -> (i32, i32) {
+    let position = transform.translation.truncate().round().as_ivec2();
+    (position.x, position.y)
+}
+
+/// Быстрое сохранение (F6) и загрузка (F7)
+fn quick_save(
+        keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut save_event:     EventWriter<SaveWorld>,
+    mut load_event:     EventWriter<LoadWorld>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        save_event.send(SaveWorld(QUICK_SAVE.to_string()));
+    }
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        load_event.send(LoadWorld(QUICK_SAVE.to_string()));
+    }
+}
+
+fn save_world(
+    mut event:      EventReader<SaveWorld>,
+        items:      Query<(&ItemEntity, &Transform)>,
+        player:     Query<(&Inventory, Option<&Equipment>, Option<&CraftQueue>), With<UserControl>>,
+        storages:   Query<(&Inventory, &Transform), (With<EntityObject>, Without<UserControl>)>,
+        pawns:      Query<(&Inventory, &Transform), (With<Pawn>, Without<UserControl>)>,
+        blueprints: Query<(&Blueprint, &Footprint)>,
+        stockpiles: Query<&Stockpile>,
+        board:      Res<JobBoard>,
+        plants:     Res<Plants>,
+        clock:      Res<WorldInfo>,
+        weather:    Res<Weather>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let save = WorldSave {
+            version:    SAVE_VERSION,
+            items:      items.iter().map(|(item, transform)| {
+                let subtile = WorldSystem::get_currect_chunk_subtile(transform.translation.truncate().as_ivec2());
+                SavedItem {
+                    id_name:    item.id_name.clone(),
+                    subtile:    (subtile.x, subtile.y),
+                    count:      item.count,
+                    instance:   item.instance.clone(),
+                }
+            })
+            // Рабочие не сохраняются, поэтому их груз сохраняется лежащим у их ног
+            .chain(pawns.iter().flat_map(|(inventory, transform)| {
+                let position = transform.translation.truncate().as_ivec2();
+                inventory.iter_some().enumerate().map(move |(index, slot)| {
+                    let subtile = drop_subtile(position, index);
+                    SavedItem {
+                        id_name:    slot.id_name.clone(),
+                        subtile:    (subtile.x, subtile.y),
+                        count:      slot.count,
+                        instance:   slot.instance.clone(),
+                    }
+                })
+            }))
+            .collect(),
+            player:     player.get_single().ok().map(|(inventory, equipment, queue)| SavedPlayer {
+                inventory:  inventory.clone(),
+                equipment:  equipment.cloned().unwrap_or_default(),
+                crafts:     queue.map_or(Vec::new(), |queue| queue.tasks.iter().cloned().collect()),
+            }),
+            storages:   storages.iter().map(|(inventory, transform)| SavedStorage {
+                position:   position_key(transform),
+                inventory:  inventory.clone(),
+            }).collect(),
+            blueprints: blueprints.iter().map(|(blueprint, footprint)| SavedBlueprint {
+                object:     blueprint.object.clone(),
+                anchor:     (footprint.0.x, footprint.0.y),
+                direction:  blueprint.direction,
+                materials:  blueprint.materials.clone(),
+                delivered:  blueprint.delivered.clone(),
+                work:       blueprint.work,
+                total:      blueprint.total,
+                xp:         blueprint.xp,
+            }).collect(),
+            // Работы над предметами на земле не сохраняются, они появляются заново при загрузке предметов
+            // Работы сбора восстанавливаются по отметкам растений
+            jobs:       board.jobs.iter().filter(|job| !matches!(job.kind, JobKind::Haul | JobKind::Harvest)).map(|job| {
+                let tile = Grid::tile_of(job.position.as_ivec2());
+                SavedJob {
+                    kind:       job.kind,
+                    tile:       (tile.x, tile.y),
+                    priority:   job.priority,
+                    recipe:     job.recipe.clone(),
+                    progress:   job.progress,
+                    started:    job.started,
+                }
+            }).collect(),
+            stockpiles: stockpiles.iter().map(|stockpile| SavedStockpile {
+                anchor:     (stockpile.area.0.x, stockpile.area.0.y),
+                size:       (stockpile.area.1.x, stockpile.area.1.y),
+                filter:     stockpile.filter.clone(),
+                priority:   stockpile.priority,
+            }).collect(),
+            plants:     plants.0.iter().map(|(tile, state)| SavedPlant {
+                id_name:    state.id_name.clone(),
+                tile:       (tile.x, tile.y),
+                stage:      state.stage,
+                progress:   state.progress,
+                pending:    (clock.time_inhabited - state.last_update).max(0.0),
+                designated: state.designated,
+            }).collect(),
+            clock:      Some(SavedClock {
+                daytime:        clock.daytime,
+                day:            clock.day,
+                time_inhabited: clock.time_inhabited,
+                speed:          clock.speed,
+                biome:          clock.biome,
+            }),
+            weather:    Some(SavedWeather {
+                id_name:    weather.current.clone(),
+                remaining:  weather.remaining,
+            }),
+        };
+
+        let result = fs::create_dir_all(SAVE_DIR)
+            .map_err(|err| err.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&save).map_err(|err| err.to_string()))
+            .and_then(|json| fs::write(save_path(&event.0), json).map_err(|err| err.to_string()));
+
+        match result {
+            Ok(()) => info!("Save - мир сохранён в {}", save_path(&event.0)),
+            Err(err) => warn!("Error - Не удалось сохранить мир {}: {}", event.0, err),
+        }
+    }
+}
+
+fn load_world(
+    mut commands:   Commands,
+    mut event:      EventReader<LoadWorld>,
+    mut chunk_res:  ResMut<Chunk>,
+    mut spawn_i:    EventWriter<ItemSpawn>,
+    mut player:     Query<(&mut Inventory, Option<&mut Equipment>, Option<&mut CraftQueue>), With<UserControl>>,
+    mut inv_save:   InventorySave,
+    mut grid:       ResMut<Grid>,
+    mut board:      ResMut<JobBoard>,
+    mut plant_save: PlantSave,
+    mut clock:      ResMut<WorldInfo>,
+    mut weather:    ResMut<Weather>,
+        items:      Query<Entity, With<ItemEntity>>,
+        blueprints: Query<(Entity, &Footprint), With<Blueprint>>,
+        stockpiles: Query<Entity, With<Stockpile>>,
+        atlas:      Res<AtlasRes>,
+        registry:   Res<Registry>,
+) {
+    if event.is_empty() {
+        return;
+    }
+
+    for event in event.read() {
+        let save = match fs::read_to_string(save_path(&event.0))
+            .map_err(|err| err.to_string())
+            .and_then(|contents| serde_json::from_str::<WorldSave>(&contents).map_err(|err| err.to_string()))
+        {
+            Ok(save) => save,
+            Err(err) => {
+                warn!("Error - Не удалось загрузить сохранение {}: {}", event.0, err);
+                continue;
+            }
+        };
+
+        if save.version > SAVE_VERSION {
+            warn!("Error - Сохранение {} создано более новой версией ({})", event.0, save.version);
+            continue;
+        }
+
+        // Предметы на земле заменяются сохранёнными
+        for item in &items {
+            chunk_res.remove_sub_object_ex(item);
+            commands.entity(item).despawn_recursive();
+        }
+        for item in save.items {
+            spawn_i.send(ItemSpawn(item.id_name, IVec2::new(item.subtile.0, item.subtile.1), item.count, Some(item.instance)));
+        }
+        // Груз рабочих сохранён среди предметов на земле
+        for (pawn, mut inventory) in &mut inv_save.pawns {
+            inventory.take_all();
+            commands.entity(pawn).remove::<HaulCargo>();
+        }
+
+        if let (Some(saved), Ok((mut inventory, equipment, queue))) = (save.player, player.get_single_mut()) {
+            *inventory = saved.inventory;
+            if let Some(mut equipment) = equipment {
+                *equipment = saved.equipment;
+            }
+            if let Some(mut queue) = queue {
+                queue.tasks = saved.crafts.into();
+            }
+        }
+
+        for saved in save.storages {
+            if let Some((mut inventory, _)) = inv_save.storages.iter_mut().find(|(_, transform)| position_key(transform) == saved.position) {
+                *inventory = saved.inventory;
+            } else {
+                warn!("Save - хранилище на {:?} не найдено", saved.position);
+            }
+        }
+
+        // Чертежи заменяются сохранёнными, работы находят свои цели по клеткам
+        for (blueprint, footprint) in &blueprints {
+            grid.remove_object(blueprint, footprint);
+            commands.entity(blueprint).despawn_recursive();
+        }
+        for saved in save.blueprints {
+            let direction = saved.direction;
+            let footprint = registry.get_object_info(&saved.object)
+                .map(|info| Footprint::new(IVec2::new(saved.anchor.0, saved.anchor.1), info.footprint(), direction));
+            let Some(footprint) = footprint else {
+                warn!("Save - неизвестный объект чертежа {}", saved.object);
+                continue;
+            };
+
+            spawn_blueprint(&mut commands, &mut grid, &registry, &atlas, Blueprint {
+                object:     saved.object,
+                direction,
+                materials:  saved.materials,
+                delivered:  saved.delivered,
+                work:       saved.work,
+                total:      saved.total,
+                xp:         saved.xp,
+            }, footprint);
+        }
+
+        for stockpile in &stockpiles {
+            commands.entity(stockpile).despawn_recursive();
+        }
+        for saved in save.stockpiles {
+            let area = Footprint(IVec2::new(saved.anchor.0, saved.anchor.1), IVec2::new(saved.size.0, saved.size.1));
+            spawn_stockpile(&mut commands, area, saved.filter, saved.priority);
+        }
+
+        // Часы восстанавливаются до растений, их рост отсчитывается от времени мира
+        if let Some(saved) = save.clock {
+            clock.daytime = saved.daytime;
+            clock.day = saved.day;
+            clock.time_inhabited = saved.time_inhabited;
+            clock.speed = saved.speed;
+            clock.biome = saved.biome;
+        }
+        if let Some(saved) = save.weather {
+            *weather = Weather {
+                current:    saved.id_name,
+                previous:   None,
+                remaining:  saved.remaining,
+                blend:      1.0,
+            };
+        }
+
+        // Растения заменяются сохранёнными, сущности появятся заново в `PlantSystem`
+        for (plant, footprint) in &plant_save.objects {
+            grid.remove_object(plant, footprint);
+            commands.entity(plant).despawn_recursive();
+        }
+        let now = clock.time_inhabited;
+        plant_save.plants.0 = save.plants.into_iter().map(|saved| {
+            let tile = IVec2::new(saved.tile.0, saved.tile.1);
+            let mut state = PlantState::new(saved.id_name, now - saved.pending);
+            state.stage = saved.stage;
+            state.progress = saved.progress;
+            state.designated = saved.designated;
+            (tile, state)
+        }).collect();
+
+        board.jobs.clear();
+        for saved in save.jobs {
+            let tile = IVec2::new(saved.tile.0, saved.tile.1);
+            let Some(target) = grid.object_at(&(tile * 16 + 8)) else {
+                warn!("Save - цель работы {:?} на {:?} не найдена", saved.kind, saved.tile);
+                continue;
+            };
+
+            let id = board.post(saved.kind, target, (tile * 16 + 8).as_vec2(), saved.priority, saved.recipe);
+            if let Some(job) = id.and_then(|id| board.get_mut(id)) {
+                job.progress = saved.progress;
+                job.started = saved.started;
+            }
+        }
+
+        info!("Save - мир загружен из {}", save_path(&event.0));
+    }
+}
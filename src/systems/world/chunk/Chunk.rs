@@ -153,7 +153,7 @@ impl ChunkX {
         false
     }
 
-    pub fn get_object(&mut self, coord: IVec2) -> Option<Entity> {
+    pub fn get_object(&self, coord: IVec2) -> Option<Entity> {
         if let Some((x, y)) = self.global_to_local(coord) {
             return self.objects[x][y];
         }
@@ -217,17 +217,16 @@ impl ChunkX {
     /// 
     /// Путём деления по модулю и деления на размер чанка по клеткам
     /// 
+    /// Остаток берётся неотрицательный, чтобы клетка -1 была последней в чанке -1, а не первой
+    /// 
     /// ```
-    /// (var.x.abs() % 256) / 16;
-    /// (var.y.abs() % 256) / 16;
+    /// let coord = bevy::math::IVec2::new(-1, 300);
+    /// assert_eq!(coord.x.rem_euclid(256) / 16, 15);
+    /// assert_eq!(coord.y.rem_euclid(256) / 16, 2);
     /// ```
     fn global_to_local(&self, coord: IVec2) -> Option<(usize, usize)> {
-        let local_x = (coord.x.abs() % 256) / 16;
-        let local_y = (coord.y.abs() % 256) / 16;
-        if local_x >= 0 && local_y >= 0 {
-            Some((local_x as usize, local_y as usize))
-        } else {
-            None
-        }
+        let local_x = coord.x.rem_euclid(256) / 16;
+        let local_y = coord.y.rem_euclid(256) / 16;
+        Some((local_x as usize, local_y as usize))
     }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ pub mod chunk;
 pub mod Grid;
 pub mod TileMap;
 pub mod World;
+pub mod WorldSVD;
 
 //pub mod chunk;
 
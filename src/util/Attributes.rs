@@ -5,7 +5,7 @@ use bevy::{
     // reflect::{FromReflect, GetTypeRegistration},
     // utils::HashMap,
 };
-// use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 // use std::{fmt::Debug, fmt::Display, hash::Hash, iter::Sum, ops::Add};
 // // use strum::IntoEnumIterator;
 
@@ -147,7 +147,8 @@ pub struct BlockAttributeBundle {
     pub health: CurrentHealth,
 }
 
-#[derive(Component, PartialEq, Clone, Reflect, Default, Debug)]
+#[derive(Component, PartialEq, Clone, Reflect, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ItemAttributes {
     pub health: i32,
     pub attack: i32,
@@ -249,8 +250,10 @@ impl ItemAttributes {
     }
 
     pub fn add_attribute_components(&self, entity: &mut EntityCommands) {
-        if self.health > 0 {
+        if self.health != 0 {
             entity.insert(MaxHealth(self.health));
+        } else {
+            entity.remove::<MaxHealth>();
         }
         if self.attack_cooldown > 0. {
             entity.insert(AttackCooldown(self.attack_cooldown));
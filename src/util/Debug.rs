@@ -51,6 +51,7 @@ impl GameError {
 pub enum GameErrorType {
     ItemMissing,
     CraftingFailed,
+    InvalidDef,
 }
 
 impl fmt::Display for GameError {
@@ -34,6 +34,8 @@ pub struct Settings {
     pub vsync: bool,
     pub rendering_distance: i32,
     pub chunk_size: i32,
+    #[serde(default)]
+    pub strict_loading: bool,   // Ошибки в модулях останавливают загрузку
 }
 
 impl Settings {
@@ -69,6 +71,7 @@ impl Default for Settings {
             vsync: false,
             rendering_distance: 3,
             chunk_size: 16,
+            strict_loading: false,
         }
     }
 }